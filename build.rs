@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::{PathBuf,Path};
+use std::process::Command;
 
 fn main() {
     // Copy assets/presets to exedir/presets
@@ -17,10 +18,72 @@ fn main() {
         }
     }
 
+    // build info for the About window
+    println!("cargo:rustc-env=COLORLOOK_GIT_HASH={}", get_git_hash());
+    println!("cargo:rustc-env=COLORLOOK_EGUI_VERSION={}", get_egui_version());
+
+    // with any rerun-if-changed cargo only reruns for the listed paths, so the assets are listed too
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=assets");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    for path in git_head_files() {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
     // compile resource file
     embed_resource::compile("assets/icon.rc", embed_resource::NONE);
 }
 
+fn get_git_hash() -> String {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            return String::from_utf8_lossy(&output.stdout).trim().to_string();
+        }
+        _ => return "unknown".to_string(),
+    }
+}
+
+// HEAD changes on checkout, the branch it points to on commit
+fn git_head_files() -> Vec<PathBuf> {
+    let git_dir = Path::new(".git");
+    let head = git_dir.join("HEAD");
+    let Ok(text) = fs::read_to_string(&head) else {
+        return Vec::new();
+    };
+    let mut files = vec![head];
+    if let Some(reference) = text.trim().strip_prefix("ref: ") {
+        let ref_file = git_dir.join(reference);
+        // a branch that has not moved since the last gc only lives in packed-refs
+        if ref_file.exists() {
+            files.push(ref_file);
+        } else if git_dir.join("packed-refs").exists() {
+            files.push(git_dir.join("packed-refs"));
+        }
+    }
+    return files;
+}
+
+fn get_egui_version() -> String {
+    // the resolved version lives in Cargo.lock, the manifest only says "*"
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == "name = \"egui\"" {
+            if let Some(version) = lines.next() {
+                return version
+                    .trim()
+                    .trim_start_matches("version = ")
+                    .trim_matches('"')
+                    .to_string();
+            }
+        }
+    }
+    return "unknown".to_string();
+}
+
 fn get_output_path() -> PathBuf {
     //<root or manifest path>/target/<profile>/
     let manifest_dir_string = std::env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -0,0 +1,183 @@
+use crate::color_item;
+use crate::utils::auto_palette::color_struct::Color;
+use crate::utils::auto_palette::delta_e::DeltaE;
+use crate::utils::auto_palette::rgb::RGB;
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pairing {
+    // i-th with i-th, the shorter palette repeats its last color
+    Index,
+    // every current color with its closest color of the other palette
+    Nearest,
+}
+
+pub struct Blend {
+    // (name, colors) of the palette blended into the current one
+    other: Option<(String, Vec<color_item::ColorItem>)>,
+    presets: Vec<(String, Vec<color_item::ColorItem>)>,
+    ratio: f32,
+    pairing: Pairing,
+    dialog: Option<egui_file::FileDialog>,
+    error: Option<String>,
+}
+
+fn to_lab(color: &color_item::ColorItem) -> Color<f64> {
+    return Color::from(&RGB::new(color.r, color.g, color.b));
+}
+
+/// mix palette `a` toward palette `b` in Lab, 0 is `a` and 1 is `b`
+fn blend(
+    a: &[color_item::ColorItem],
+    b: &[color_item::ColorItem],
+    ratio: f32,
+    pairing: Pairing,
+) -> Vec<color_item::ColorItem> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let pairs: Vec<(&color_item::ColorItem, &color_item::ColorItem)> = match pairing {
+        Pairing::Index => (0..a.len().max(b.len()))
+            .map(|i| (&a[i.min(a.len() - 1)], &b[i.min(b.len() - 1)]))
+            .collect(),
+        Pairing::Nearest => a
+            .iter()
+            .map(|color| {
+                let lab = to_lab(color);
+                let nearest = b
+                    .iter()
+                    .min_by(|x, y| {
+                        let dx = lab.difference(&to_lab(x), &DeltaE::CIE2000);
+                        let dy = lab.difference(&to_lab(y), &DeltaE::CIE2000);
+                        dx.partial_cmp(&dy).unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .unwrap();
+                (color, nearest)
+            })
+            .collect(),
+    };
+    return pairs
+        .iter()
+        .enumerate()
+        .map(|(i, (x, y))| {
+            let rgb = to_lab(x).mix(&to_lab(y), ratio as f64).to_rgb();
+            color_item::ColorItem::new(&format!("blend-{}", i + 1), rgb.r(), rgb.g(), rgb.b())
+        })
+        .collect();
+}
+
+impl Blend {
+    pub fn new() -> Self {
+        Self {
+            other: None,
+            presets: super::preset::Preset::load_colorlist().unwrap_or_default(),
+            ratio: 0.5,
+            pairing: Pairing::Index,
+            dialog: None,
+            error: None,
+        }
+    }
+    fn load_file(&mut self, path: &std::path::Path) {
+        let result = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                serde_json::from_str::<Vec<color_item::ColorItem>>(&json).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(colors) => {
+                let name = path
+                    .file_stem()
+                    .map_or("file".into(), |s| s.to_string_lossy().to_string());
+                self.other = Some((name, colors));
+            }
+            Err(e) => self.error = Some(format!("Cannot load palette: {}", e)),
+        }
+    }
+}
+
+impl super::AddColor for Blend {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Blend Palettes".into();
+    }
+    fn take_error(&mut self) -> Option<String> {
+        return self.error.take();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _buffer: &image::DynamicImage,
+        colors: &Vec<color_item::ColorItem>,
+    ) -> Option<Vec<color_item::ColorItem>> {
+        let mut ret = None;
+        ui.horizontal(|ui| {
+            ui.label("\u{eb9c} Blend With:");
+            let selected = self.other.as_ref().map_or("None".into(), |o| o.0.clone());
+            egui::ComboBox::from_id_salt("blend_other")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (name, preset) in self.presets.iter() {
+                        if ui.selectable_label(false, name).clicked() {
+                            self.other = Some((name.clone(), preset.clone()));
+                        }
+                    }
+                });
+            if ui.button("\u{f0214} Load JSON").clicked() {
+                let mut dialog = egui_file::FileDialog::open_file(None)
+                    .title("Load Palette")
+                    .filename_filter(Box::new(|name| name.ends_with(".json")));
+                dialog.open();
+                self.dialog = Some(dialog);
+            }
+        });
+        let mut selected_path = None;
+        if let Some(dialog) = &mut self.dialog {
+            if dialog.show(ui.ctx()).selected() {
+                selected_path = dialog.path().map(|p| p.to_path_buf());
+            }
+        }
+        if let Some(path) = selected_path {
+            self.dialog = None;
+            self.load_file(&path);
+        }
+        ui.horizontal(|ui| {
+            ui.label("\u{f0ad5} Ratio:");
+            ui.add(egui::Slider::new(&mut self.ratio, 0f32..=1f32).fixed_decimals(2));
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{e9d9} Pairing:");
+            ui.selectable_value(&mut self.pairing, Pairing::Index, "By Index")
+                .on_hover_text("the shorter palette repeats its last color");
+            ui.selectable_value(&mut self.pairing, Pairing::Nearest, "Nearest \u{0394}E")
+                .on_hover_text("every current color blends with its closest match");
+        });
+        ui.separator();
+        let Some((_, other)) = &self.other else {
+            ui.label("Choose a preset or load a palette to blend with.");
+            return None;
+        };
+        if colors.is_empty() || other.is_empty() {
+            ui.label("Both palettes need at least 1 color.");
+            return None;
+        }
+        // the preview is exactly what gets added
+        let blended = blend(colors, other, self.ratio, self.pairing);
+        ui.horizontal_wrapped(|ui| {
+            for color in blended.iter() {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(24f32, 24f32), egui::Sense::hover());
+                color_item::paint_swatch(
+                    ui.painter(),
+                    rect,
+                    color_item::swatch_rounding().max(2f32),
+                    color.to_color32(),
+                    egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+                );
+                response.on_hover_text(color.get_hex());
+            }
+        });
+        if ui.button("\u{ea60} Add").clicked() {
+            ret = Some(blended);
+        }
+        return ret;
+    }
+}
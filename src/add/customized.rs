@@ -1,9 +1,12 @@
 use crate::color_item;
+use crate::utils::{auto_palette, lch};
 use eframe::egui::{self, RichText};
 
 pub struct Customized {
     name: String,
     color: Color,
+    // names given so far, steps the name seed
+    names: u64,
 }
 
 #[derive(Clone)]
@@ -11,6 +14,9 @@ enum Color {
     RGB((u8, u8, u8)),
     HEX(String),
     HSV((f32, f32, f32)),
+    CMYK((f32, f32, f32, f32)),
+    // CIE LCh(ab), hue in degrees
+    LCH((f32, f32, f32)),
 }
 
 impl Color {
@@ -19,6 +25,8 @@ impl Color {
             Color::RGB(_) => ColorType::RGB,
             Color::HEX(_) => ColorType::HEX,
             Color::HSV(_) => ColorType::HSV,
+            Color::CMYK(_) => ColorType::CMYK,
+            Color::LCH(_) => ColorType::LCH,
         }
     }
     fn set_rgb(&mut self, r: u8, g: u8, b: u8) {
@@ -30,6 +38,12 @@ impl Color {
     fn set_hsv(&mut self, h: f32, s: f32, v: f32) {
         *self = Color::HSV((h, s, v));
     }
+    fn set_cmyk(&mut self, c: f32, m: f32, y: f32, k: f32) {
+        *self = Color::CMYK((c, m, y, k));
+    }
+    fn set_lch(&mut self, l: f32, c: f32, h: f32) {
+        *self = Color::LCH((l, c, h));
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -37,13 +51,36 @@ enum ColorType {
     RGB,
     HEX,
     HSV,
+    CMYK,
+    LCH,
 }
 
 impl Customized {
     pub fn new() -> Self {
+        let mut names = 0;
         Self {
-            name: crate::utils::get_random_name(8),
+            name: super::next_name(8, &mut names),
             color: Color::RGB((0, 0, 0)),
+            names,
+        }
+    }
+    fn channel_edit(ui: &mut egui::Ui, value: &mut u8, float_values: bool) {
+        if float_values {
+            // stored as u8, only the entry is normalized
+            let mut normalized = *value as f32 / 255.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut normalized)
+                        .range(0f32..=1f32)
+                        .fixed_decimals(3)
+                        .speed(0.005),
+                )
+                .changed()
+            {
+                *value = (normalized * 255.0).round() as u8;
+            }
+        } else {
+            ui.add(egui::DragValue::new(value).range(0..=255).speed(1.0));
         }
     }
 }
@@ -52,54 +89,50 @@ impl super::AddColor for Customized {
     fn get_name(&self) -> String {
         return "\u{eae6} Customized Color".into();
     }
-    fn paint_ui(&mut self, ui: &mut egui::Ui, _buffer:&image::DynamicImage) -> Option<Vec<crate::color_item::ColorItem>> {
+    fn paint_ui(&mut self, ui: &mut egui::Ui, _buffer:&image::DynamicImage, _colors: &Vec<crate::color_item::ColorItem>) -> Option<Vec<crate::color_item::ColorItem>> {
         let mut ret = false;
         let mut colortype = self.color.get_type();
+        let float_values = crate::settings::with(|s| s.float_values);
         ui.horizontal(|ui| {
             ui.label("\u{f1050} Name:");
-            ui.text_edit_singleline(&mut self.name);
+            if let Some([r, g, b]) = color_item::name_edit(ui, &mut self.name) {
+                self.color.set_rgb(r, g, b);
+            }
         });
         let color;
         ui.horizontal(|ui| {
             if ui.button("\u{ea60} Add").clicked() {
                 ret = true;
-                self.name = crate::utils::get_random_name(8);
+                self.name = super::next_name(8, &mut self.names);
             }
             ui.selectable_value(&mut colortype, ColorType::RGB, "\u{f0ae4} RGB");
             ui.selectable_value(&mut colortype, ColorType::HEX, "\u{f12a7} HEX");
             ui.selectable_value(&mut colortype, ColorType::HSV, "\u{f04c5} HSV");
+            ui.selectable_value(&mut colortype, ColorType::CMYK, "\u{f042e} CMYK");
+            ui.selectable_value(&mut colortype, ColorType::LCH, "\u{f03d8} LCh")
+                .on_hover_text("perceptual lightness, chroma and hue");
         });
         match self.color {
             Color::RGB((ref mut r, ref mut g, ref mut b)) => {
-                color = color_item::ColorItem {
-                    name: self.name.clone(),
-                    r: *r,
-                    g: *g,
-                    b: *b,
-                };
+                color = color_item::ColorItem::new(&self.name, *r, *g, *b);
                 ui.horizontal(|ui| {
                     ui.label(RichText::new("R:").color(egui::Color32::RED));
-                    ui.add(egui::DragValue::new(r).range(0..=255).speed(1.0));
+                    Self::channel_edit(ui, r, float_values);
                 });
                 ui.horizontal(|ui| {
                     ui.label(RichText::new("G:").color(egui::Color32::GREEN));
-                    ui.add(egui::DragValue::new(g).range(0..=255).speed(1.0));
+                    Self::channel_edit(ui, g, float_values);
                 });
                 ui.horizontal(|ui| {
                     ui.label(RichText::new("B:").color(egui::Color32::BLUE));
-                    ui.add(egui::DragValue::new(b).range(0..=255).speed(1.0));
+                    Self::channel_edit(ui, b, float_values);
                 });
             }
             Color::HEX(ref mut hex) => {
                 let c = color_item::ColorItem::from_hex(hex, self.name.as_str());
                 color = match &c {
                     Some(e) => e.clone(),
-                    None => color_item::ColorItem {
-                        name: self.name.clone(),
-                        r: 0,
-                        g: 0,
-                        b: 0,
-                    },
+                    None => color_item::ColorItem::new(&self.name, 0, 0, 0),
                 };
                 ui.horizontal(|ui| {
                     ui.label("HEX:");
@@ -139,6 +172,55 @@ impl super::AddColor for Customized {
                     );
                 });
             }
+            Color::CMYK((ref mut c, ref mut m, ref mut y, ref mut k)) => {
+                color = color_item::ColorItem::from_cmyk(*c, *m, *y, *k, self.name.as_str());
+                let channels = [
+                    ("C:", egui::Color32::from_rgb(0, 255, 255), c),
+                    ("M:", egui::Color32::from_rgb(255, 0, 255), m),
+                    ("Y:", egui::Color32::YELLOW, y),
+                    ("K:", egui::Color32::GRAY, k),
+                ];
+                for (label, label_color, value) in channels {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(label).color(label_color));
+                        ui.add(
+                            egui::DragValue::new(value)
+                                .range(0f32..=1f32)
+                                .fixed_decimals(3)
+                                .speed(0.005),
+                        );
+                    });
+                }
+            }
+            Color::LCH((ref mut l, ref mut c, ref mut h)) => {
+                let (lab_l, lab_a, lab_b) = lch::lch_to_lab(*l as f64, *c as f64, *h as f64);
+                let lab = auto_palette::lab::Lab::<f64>::new(lab_l, lab_a, lab_b);
+                // channels outside sRGB are clipped by the conversion
+                let rgb = auto_palette::color_struct::Color::from(&lab).to_rgb();
+                color = color_item::ColorItem::new(&self.name, rgb.r, rgb.g, rgb.b);
+                let channels = [
+                    ("L:", egui::Color32::WHITE, l, 100f32),
+                    ("C:", egui::Color32::KHAKI, c, 128f32),
+                    ("H:", egui::Color32::LIGHT_BLUE, h, 360f32),
+                ];
+                for (label, label_color, value, max) in channels {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(label).color(label_color));
+                        ui.add(
+                            egui::DragValue::new(value)
+                                .range(0f32..=max)
+                                .fixed_decimals(1)
+                                .speed(0.5),
+                        );
+                    });
+                }
+                if !lch::in_gamut(lab_l, lab_a, lab_b) {
+                    ui.label(
+                        RichText::new("\u{f0026} Out of gamut, clipped to the nearest sRGB color")
+                            .color(egui::Color32::LIGHT_RED),
+                    );
+                }
+            }
         }
         // preview it use painter
         ui.label("\u{eb28} Preview:");
@@ -146,9 +228,10 @@ impl super::AddColor for Customized {
         let mut cursor = ui.cursor();
         cursor.set_height(60f32);
 
-        painter.rect(
+        color_item::paint_swatch(
+            painter,
             cursor,
-            0f32,
+            color_item::swatch_rounding(),
             color.to_color32(),
             egui::Stroke::new(2f32, egui::Color32::WHITE),
         );
@@ -165,6 +248,15 @@ impl super::AddColor for Customized {
                     self.color
                         .set_hsv(color.get_h(), color.get_s(), color.get_v());
                 }
+                ColorType::CMYK => {
+                    let (c, m, y, k) = color.to_cmyk();
+                    self.color.set_cmyk(c, m, y, k);
+                }
+                ColorType::LCH => {
+                    let (l, a, b) = color.get_lab();
+                    let (l, c, h) = lch::lab_to_lch(l, a, b);
+                    self.color.set_lch(l as f32, c as f32, h as f32);
+                }
             }
         }
         if ret {
@@ -1,6 +1,8 @@
 use crate::color_item;
 use eframe::egui;
-use std::sync::mpsc;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use crate::utils::auto_palette;
 
@@ -9,25 +11,532 @@ pub enum PaletteTheme {
     Vivid,Muted,Light,Dark
 }
 
+// seconds the clustering parameters must stay still before live mode re-extracts
+const LIVE_DEBOUNCE: f64 = 0.4;
+// bytes sampled from the image to notice that it changed
+const FINGERPRINT_SAMPLES: usize = 4096;
+// longest side of the thumbnail the crop is drawn on
+const CROP_THUMBNAIL: u32 = 256;
+
+// converted pixels of the last image, with the source, crop, alpha mode and size limit they came from
+type PixelCache = Arc<Mutex<Option<((Source, Option<[u32; 4]>, bool, u32), Arc<auto_palette::Pixels<f64>>)>>>;
+
 pub struct Extract {
     name: String,
+    // names given so far, steps the name seed
+    names: u64,
     theme: PaletteTheme,
     algo: auto_palette::Algorithm,
     max_color: usize,
+    // cluster tile by tile, keeps memory low on large images
+    tiled: bool,
+    tile_size: u32,
+    // larger images are scaled down to this width and height before the clustering
+    max_dimension: u32,
+    // gaussian falloff from the image center, 0 counts all pixels the same
+    center_bias: f64,
+    // how much pixels on edges and detail count over flat areas, 0 to 1
+    edge_bias: f64,
+    // cluster on opacity too, semi-transparent areas get swatches of their own
+    alpha: bool,
+    // region to extract from, normalized to the image size, empty for the whole image
+    crop: egui::Rect,
+    // where the drag that draws the crop started
+    crop_start: Option<egui::Pos2>,
+    // thumbnail of the image the crop is drawn on, with the fingerprint it was made from
+    thumbnail: Option<(u64, egui::TextureHandle)>,
+    // DBSCAN neighborhood radius
+    epsilon: f64,
+    // distance between pixels for GMeans and DBSCAN, the radius is measured in it too
+    metric: auto_palette::DistanceMetric,
+    // radius from the k-distance graph, shown until the user takes it
+    suggestion: Option<f64>,
+    suggest_channel: Option<mpsc::Receiver<Option<f64>>>,
     hthread: Option<thread::JoinHandle<()>>,
-    channel: Option<mpsc::Receiver<Vec<color_item::ColorItem>>>,
+    channel: Option<mpsc::Receiver<(ClusterKey, Result<auto_palette::Palette<f64>, String>)>>,
+    // fraction of the running clustering that is done, and where updates to it arrive
+    progress: f32,
+    progress_channel: Option<mpsc::Receiver<f64>>,
+    // set to stop the running clustering
+    cancel: Arc<AtomicBool>,
+    // run once on the next frame without waiting for the button
+    autostart: bool,
+    // remote image to extract from instead of the current one
+    url: String,
+    // failed download, reported once as a toast
+    error: Option<String>,
+    // re-extract on parameter changes and add from the preview
+    live: bool,
+    // last clustering, theme and color count are picked from it without a new run
+    cache: Option<(ClusterKey, auto_palette::Palette<f64>)>,
+    // shared with the workers, switching the algorithm or bias skips the conversion
+    pixels: PixelCache,
+    // colors picked from the cache for (theme, max color, name)
+    preview: Option<((PaletteTheme, usize, String), Vec<color_item::ColorItem>)>,
+    // clustering parameters waiting out the debounce, with the time they were set
+    pending: Option<(ClusterKey, f64)>,
+    // the next finished clustering goes straight into the color list
+    deliver: bool,
+}
+
+#[derive(Clone, PartialEq)]
+enum Source {
+    // fingerprint of the working image
+    Image(u64),
+    Url(String),
+    // one of the images of a batch run, nothing is cached for it
+    Batch,
+}
+
+// everything that needs a new clustering when it changes
+#[derive(Clone, PartialEq)]
+struct ClusterKey {
+    source: Source,
+    algorithm: auto_palette::Algorithm,
+    tile_size: Option<u32>,
+    max_dimension: u32,
+    center_bias: f64,
+    edge_bias: f64,
+    alpha: bool,
+    // (x, y, width, height) of the working image to cluster, none for all of it
+    crop: Option<[u32; 4]>,
+}
+
+impl ClusterKey {
+    // the clustering itself, run on the worker thread
+    fn run(
+        &self,
+        img: &image::DynamicImage,
+        pixels: Option<&PixelCache>,
+        progress: &dyn Fn(f64) -> bool,
+    ) -> auto_palette::Palette<f64> {
+        let (width, height) = (img.width(), img.height());
+        let edges = if self.edge_bias > 0f64 {
+            crate::utils::edge_map(&img.to_luma8())
+        } else {
+            Vec::new()
+        };
+        let weight = |x: u32, y: u32| {
+            let mut value = auto_palette::center_weight(x, y, width, height, self.center_bias);
+            if let Some(edge) = edges.get((y * width + x) as usize) {
+                value *= 1f64 - self.edge_bias + self.edge_bias * *edge as f64;
+            }
+            return value;
+        };
+        return match self.tile_size {
+            Some(tile_size) => {
+                auto_palette::Palette::extract_tiled_with_progress(
+                    img,
+                    &self.algorithm,
+                    tile_size,
+                    &weight,
+                    self.alpha,
+                    progress,
+                )
+            }
+            None => {
+                let pixels = match pixels {
+                    Some(cache) => self.pixels(img, cache),
+                    None => Arc::new(auto_palette::Pixels::with_max_dimension(img, self.max_dimension)),
+                };
+                auto_palette::Palette::extract_from_pixels(
+                    &pixels,
+                    &self.algorithm,
+                    &weight,
+                    self.alpha,
+                    progress,
+                )
+            }
+        };
+    }
+    // converted once per image, crop, alpha mode and size limit
+    fn pixels(&self, img: &image::DynamicImage, cache: &PixelCache) -> Arc<auto_palette::Pixels<f64>> {
+        let key = (self.source.clone(), self.crop, self.alpha, self.max_dimension);
+        // a worker that panicked mid conversion left nothing behind
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached, pixels)) = cache.as_ref() {
+            if *cached == key {
+                return pixels.clone();
+            }
+        }
+        let pixels = Arc::new(auto_palette::Pixels::with_max_dimension(img, self.max_dimension));
+        *cache = Some((key, pixels.clone()));
+        return pixels;
+    }
+    // rgba keeps the opacity for alpha clustering, rgb treats every pixel as opaque
+    fn prepare(&self, img: image::DynamicImage) -> image::DynamicImage {
+        let img = match self.crop {
+            Some([x, y, width, height]) => img.crop_imm(x, y, width, height),
+            None => img,
+        };
+        if self.alpha {
+            return img.into_rgba8().into();
+        }
+        return img.into_rgb8().into();
+    }
+}
+
+// pixel rectangle of a normalized selection, none when it is empty or covers the whole image
+fn crop_pixels(crop: egui::Rect, width: u32, height: u32) -> Option<[u32; 4]> {
+    let crop = crop.intersect(egui::Rect::from_min_max(egui::pos2(0f32, 0f32), egui::pos2(1f32, 1f32)));
+    if !crop.is_positive() {
+        return None;
+    }
+    let x0 = (crop.min.x * width as f32).floor() as u32;
+    let y0 = (crop.min.y * height as f32).floor() as u32;
+    let x1 = ((crop.max.x * width as f32).ceil() as u32).min(width);
+    let y1 = ((crop.max.y * height as f32).ceil() as u32).min(height);
+    if x1 <= x0 || y1 <= y0 || (x1 - x0, y1 - y0) == (width, height) {
+        return None;
+    }
+    return Some([x0, y0, x1 - x0, y1 - y0]);
+}
+
+// cheap enough for every frame, samples the pixels instead of hashing them all
+pub(super) fn fingerprint(img: &image::DynamicImage) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (img.width(), img.height()).hash(&mut hasher);
+    let bytes = img.as_bytes();
+    let step = (bytes.len() / FINGERPRINT_SAMPLES).max(1);
+    for byte in bytes.iter().step_by(step) {
+        byte.hash(&mut hasher);
+    }
+    return hasher.finish();
+}
+
+fn palette_colors(
+    palette: &auto_palette::Palette<f64>,
+    theme: PaletteTheme,
+    max_color: usize,
+    basename: &str,
+    offset: (u32, u32),
+) -> Vec<color_item::ColorItem> {
+    let swatches = match theme {
+        PaletteTheme::Vivid => palette.swatches_with_theme(max_color, &auto_palette::Vivid),
+        PaletteTheme::Muted => palette.swatches_with_theme(max_color, &auto_palette::Muted),
+        PaletteTheme::Light => palette.swatches_with_theme(max_color, &auto_palette::Light),
+        PaletteTheme::Dark => palette.swatches_with_theme(max_color, &auto_palette::Dark),
+    };
+    let mut colors : Vec<color_item::ColorItem> = swatches.iter().map(|swatch| {
+        let clr = swatch.color().to_rgb();
+        // positions in the whole image, not the crop
+        let pos = (swatch.position().0 + offset.0, swatch.position().1 + offset.1);
+        let pop = swatch.population();
+        let name = format!("{}-({},{})-{}",basename,pos.0,pos.1,pop);
+        let alpha = (swatch.alpha() * 255f64).round() as u8;
+        let color = color_item::ColorItem::new(&name, clr.r(), clr.g(), clr.b()).with_alpha(alpha);
+        color
+    }).collect();
+    colors.dedup();
+    colors.sort_by(|a,b| a.name.cmp(&b.name));
+    return colors;
+}
+
+/// the clustering and picking settings of an Extract component, to run on other images
+#[derive(Clone)]
+pub struct BatchSettings {
+    key: ClusterKey,
+    theme: PaletteTheme,
+    max_color: usize,
+}
+
+impl BatchSettings {
+    /// what a fresh Extract component would use
+    pub fn new() -> Self {
+        return Extract::new().to_batch_settings();
+    }
+    /// the palette of a whole image, colors named after `basename`, empty once `cancel` is set
+    pub fn extract(&self, img: image::DynamicImage, basename: &str, cancel: &AtomicBool) -> Vec<color_item::ColorItem> {
+        let palette = self.key.run(&self.key.prepare(img), None, &|_| !cancel.load(Ordering::Relaxed));
+        if cancel.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+        return palette_colors(&palette, self.theme, self.max_color, basename, (0, 0));
+    }
 }
 
 impl Extract {
     pub fn new() -> Self {
+        let mut names = 0;
         Self {
-            name: crate::utils::get_random_name(5),
+            name: super::next_name(5, &mut names),
+            names,
             theme: PaletteTheme::Vivid,
             algo: auto_palette::Algorithm::GMeans,
             max_color: 10,
+            tiled: false,
+            tile_size: 256,
+            max_dimension: auto_palette::MAX_DIMENSION,
+            center_bias: 0f64,
+            edge_bias: 0f64,
+            alpha: false,
+            crop: egui::Rect::NOTHING,
+            crop_start: None,
+            thumbnail: None,
+            epsilon: auto_palette::DBSCAN_EPSILON,
+            metric: auto_palette::DistanceMetric::Euclidean,
+            suggestion: None,
+            suggest_channel: None,
             hthread: None,
             channel: None,
+            progress: 0f32,
+            progress_channel: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            autostart: false,
+            url: String::new(),
+            error: None,
+            live: false,
+            cache: None,
+            pixels: Arc::new(Mutex::new(None)),
+            preview: None,
+            pending: None,
+            deliver: false,
+        }
+    }
+    pub fn autostart() -> Self {
+        let mut extract = Self::new();
+        extract.autostart = true;
+        return extract;
+    }
+    fn key(&self, source: Source, buffer: &image::DynamicImage) -> ClusterKey {
+        // the crop is drawn on the working image, a remote or batch one is used whole
+        let crop = match source {
+            Source::Image(_) => crop_pixels(self.crop, buffer.width(), buffer.height()),
+            Source::Url(_) | Source::Batch => None,
+        };
+        return self.cluster_key(source, crop);
+    }
+    fn cluster_key(&self, source: Source, crop: Option<[u32; 4]>) -> ClusterKey {
+        return ClusterKey {
+            source,
+            algorithm: match self.algo {
+                auto_palette::Algorithm::GMeans => auto_palette::Algorithm::GMeansWith {
+                    metric: self.metric,
+                },
+                auto_palette::Algorithm::DBSCAN => auto_palette::Algorithm::DBSCANWith {
+                    epsilon: self.epsilon,
+                    metric: self.metric,
+                },
+                algo => algo,
+            },
+            tile_size: if self.tiled { Some(self.tile_size) } else { None },
+            max_dimension: self.max_dimension,
+            center_bias: self.center_bias,
+            edge_bias: self.edge_bias,
+            alpha: self.alpha,
+            crop,
+        };
+    }
+    // a fresh progress channel and cancel flag, both go to the worker
+    fn progress_sender(&mut self) -> (mpsc::Sender<f64>, Arc<AtomicBool>) {
+        let (tx, rx) = mpsc::channel();
+        self.progress = 0f32;
+        self.progress_channel = Some(rx);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        return (tx, self.cancel.clone());
+    }
+    fn start(&mut self, key: ClusterKey, img: &image::DynamicImage) {
+        let img = key.prepare(img.clone());
+        let (progress, cancel) = self.progress_sender();
+        let pixels = self.pixels.clone();
+        let (tx, rx) = mpsc::channel();
+        self.channel = Some(rx);
+        self.hthread = Some(thread::spawn(move || {
+            let palette = key.run(&img, Some(&pixels), &|p| {
+                let _ = progress.send(p);
+                return !cancel.load(Ordering::Relaxed);
+            });
+            // a cancelled run drops the channel without sending
+            if !cancel.load(Ordering::Relaxed) {
+                let _ = tx.send((key, Ok(palette)));
+            }
+        }));
+    }
+    fn start_url(&mut self, key: ClusterKey, url: String) {
+        let (progress, cancel) = self.progress_sender();
+        let (tx, rx) = mpsc::channel();
+        self.channel = Some(rx);
+        self.hthread = Some(thread::spawn(move || {
+            let result = crate::utils::download_image(&url).map(|img| {
+                // a download may differ every time, so it is not cached
+                key.run(&key.prepare(img), None, &|p| {
+                    let _ = progress.send(p);
+                    return !cancel.load(Ordering::Relaxed);
+                })
+            });
+            if !cancel.load(Ordering::Relaxed) {
+                let _ = tx.send((key, result));
+            }
+        }));
+    }
+    // stop the running clustering, live mode is switched off so it does not start again
+    fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.hthread = None;
+        self.channel = None;
+        self.progress_channel = None;
+        self.deliver = false;
+        self.pending = None;
+        self.live = false;
+    }
+    // bar for the running clustering, only the latest update counts
+    fn progress_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(rx) = &self.progress_channel {
+            if let Some(p) = rx.try_iter().last() {
+                self.progress = p as f32;
+            }
+        }
+        ui.add(
+            egui::ProgressBar::new(self.progress)
+                .desired_width(120f32)
+                .show_percentage()
+                .animate(true),
+        );
+    }
+    // colors of the cached clustering with the current theme and count
+    fn cached_colors(&mut self) -> Option<Vec<color_item::ColorItem>> {
+        let (key, palette) = self.cache.as_ref()?;
+        let preview_key = (self.theme, self.max_color, self.name.clone());
+        if self.preview.as_ref().map(|(key, _)| key) != Some(&preview_key) {
+            let offset = key.crop.map_or((0, 0), |[x, y, _, _]| (x, y));
+            let colors = palette_colors(palette, self.theme, self.max_color, &self.name, offset);
+            self.preview = Some((preview_key, colors));
+        }
+        return self.preview.as_ref().map(|(_, colors)| colors.clone());
+    }
+    fn is_cached(&self, key: &ClusterKey) -> bool {
+        return self.cache.as_ref().is_some_and(|(cached, _)| cached == key);
+    }
+    fn epsilon_ui(&mut self, ui: &mut egui::Ui, buffer: &image::DynamicImage) {
+        if let Some(rx) = &self.suggest_channel {
+            if let Ok(suggestion) = rx.try_recv() {
+                self.suggest_channel = None;
+                self.suggestion = suggestion;
+                if suggestion.is_none() {
+                    self.error = Some("Too few opaque pixels to suggest an epsilon".into());
+                }
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.label("\u{f0d1e} Epsilon:");
+            ui.add(
+                egui::DragValue::new(&mut self.epsilon)
+                    .speed(0.001)
+                    .range(0.005..=0.5)
+                    .fixed_decimals(3),
+            )
+            .on_hover_text("neighborhood radius, larger merges more colors into one cluster");
+            if ui
+                .add_enabled(self.suggest_channel.is_none(), egui::Button::new("\u{f0068} Auto"))
+                .on_hover_text("suggest a Euclidean radius from the knee of the k-distance graph")
+                .clicked()
+            {
+                let img = buffer.clone();
+                let (tx, rx) = mpsc::channel();
+                self.suggest_channel = Some(rx);
+                thread::spawn(move || {
+                    // the component may be gone by the time the suggestion is ready
+                    let _ = tx.send(auto_palette::suggest_dbscan_epsilon::<f64>(&img));
+                });
+            }
+            if self.suggest_channel.is_some() {
+                ui.spinner();
+            }
+            if let Some(suggestion) = self.suggestion {
+                ui.label(format!("Suggested: {:.3}", suggestion));
+                if ui.small_button("\u{f012c} Use").clicked() {
+                    self.epsilon = suggestion.clamp(0.005, 0.5);
+                    self.suggestion = None;
+                }
+            }
+        });
+    }
+    // thumbnail of the image with the selection on it, dragging draws a new one
+    fn crop_ui(&mut self, ui: &mut egui::Ui, buffer: &image::DynamicImage, print: u64) {
+        if self.thumbnail.as_ref().map(|(of, _)| *of) != Some(print) {
+            let small = buffer.thumbnail(CROP_THUMBNAIL, CROP_THUMBNAIL).to_rgba8();
+            let size = [small.width() as _, small.height() as _];
+            let image = egui::ColorImage::from_rgba_unmultiplied(size, small.as_flat_samples().as_slice());
+            let texture = ui.ctx().load_texture("extract_crop", image, egui::TextureOptions::default());
+            self.thumbnail = Some((print, texture));
+        }
+        let Some((_, texture)) = &self.thumbnail else {
+            return;
+        };
+        let (rect, response) = ui.allocate_exact_size(texture.size_vec2(), egui::Sense::drag());
+        let uv = egui::Rect::from_min_max(egui::pos2(0f32, 0f32), egui::pos2(1f32, 1f32));
+        ui.painter().image(texture.id(), rect, uv, egui::Color32::WHITE);
+        let to_unit = |pos: egui::Pos2| {
+            let v = (pos - rect.min) / rect.size();
+            egui::pos2(v.x.clamp(0f32, 1f32), v.y.clamp(0f32, 1f32))
+        };
+        if response.drag_started() {
+            self.crop_start = response.interact_pointer_pos().map(to_unit);
+        }
+        if let (Some(start), Some(pos)) = (self.crop_start, response.interact_pointer_pos()) {
+            if response.dragged() {
+                self.crop = egui::Rect::from_two_pos(start, to_unit(pos));
+            }
+        }
+        if response.drag_stopped() {
+            self.crop_start = None;
         }
+        if response.clicked() {
+            self.crop = egui::Rect::NOTHING;
+        }
+        if self.crop.is_positive() {
+            let selection = egui::Rect::from_min_max(
+                rect.min + self.crop.min.to_vec2() * rect.size(),
+                rect.min + self.crop.max.to_vec2() * rect.size(),
+            );
+            // dim what is left out
+            let shade = egui::Color32::from_black_alpha(140);
+            for outside in [
+                egui::Rect::from_min_max(rect.min, egui::pos2(rect.max.x, selection.min.y)),
+                egui::Rect::from_min_max(egui::pos2(rect.min.x, selection.max.y), rect.max),
+                egui::Rect::from_min_max(
+                    egui::pos2(rect.min.x, selection.min.y),
+                    egui::pos2(selection.min.x, selection.max.y),
+                ),
+                egui::Rect::from_min_max(
+                    egui::pos2(selection.max.x, selection.min.y),
+                    egui::pos2(rect.max.x, selection.max.y),
+                ),
+            ] {
+                ui.painter().rect_filled(outside, 0f32, shade);
+            }
+            ui.painter()
+                .rect_stroke(selection, 0f32, egui::Stroke::new(1f32, egui::Color32::WHITE));
+        }
+        ui.horizontal(|ui| {
+            match crop_pixels(self.crop, buffer.width(), buffer.height()) {
+                Some([x, y, width, height]) => {
+                    ui.label(format!("{}x{} at ({},{})", width, height, x, y));
+                }
+                None => {
+                    ui.label("Whole image, drag to select a region");
+                }
+            }
+            if ui
+                .add_enabled(self.crop.is_positive(), egui::Button::new("\u{f0450} Reset"))
+                .clicked()
+            {
+                self.crop = egui::Rect::NOTHING;
+            }
+        });
+    }
+    fn to_batch_settings(&self) -> BatchSettings {
+        return BatchSettings {
+            key: self.cluster_key(Source::Batch, None),
+            theme: self.theme,
+            max_color: self.max_color,
+        };
+    }
+    fn finish(&mut self) -> Option<Vec<color_item::ColorItem>> {
+        self.deliver = false;
+        let colors = self.cached_colors();
+        self.name = super::next_name(5, &mut self.names);
+        return colors;
     }
 }
 
@@ -35,11 +544,19 @@ impl super::AddColor for Extract {
     fn get_name(&self) -> String {
         return "\u{eae6} Extract Palette".into();
     }
+    fn take_error(&mut self) -> Option<String> {
+        return self.error.take();
+    }
+    fn batch_settings(&self) -> Option<BatchSettings> {
+        return Some(self.to_batch_settings());
+    }
     fn paint_ui(
         &mut self,
         ui: &mut egui::Ui,
         buffer: &image::DynamicImage,
+        _colors: &Vec<color_item::ColorItem>,
     ) -> Option<Vec<color_item::ColorItem>> {
+        let mut ret = None;
         ui.horizontal(|ui| {
             ui.label("\u{f1050} Name:");
             ui.text_edit_singleline(&mut self.name);
@@ -63,55 +580,184 @@ impl super::AddColor for Extract {
             ui.label("\u{e9d9} Algorithm:");
             ui.selectable_value(&mut self.algo, auto_palette::Algorithm::GMeans, "GMeans");
             ui.selectable_value(&mut self.algo, auto_palette::Algorithm::DBSCAN, "DBSCAN").on_hover_text("Slow");
-
+            ui.selectable_value(&mut self.algo, auto_palette::Algorithm::KMeans, "KMeans")
+                .on_hover_text("fixed number of clusters, similar ones merged");
+            ui.selectable_value(&mut self.algo, auto_palette::Algorithm::MedianCut, "Median Cut")
+                .on_hover_text("Fast");
         });
+        if matches!(self.algo, auto_palette::Algorithm::GMeans | auto_palette::Algorithm::DBSCAN) {
+            ui.horizontal(|ui| {
+                ui.label("\u{f0ac7} Distance:");
+                ui.selectable_value(&mut self.metric, auto_palette::DistanceMetric::Euclidean, "Euclidean");
+                ui.selectable_value(&mut self.metric, auto_palette::DistanceMetric::Manhattan, "Manhattan")
+                    .on_hover_text("sum of the differences in lightness, hue axes and position");
+                ui.selectable_value(&mut self.metric, auto_palette::DistanceMetric::Chebyshev, "Chebyshev")
+                    .on_hover_text("largest difference in lightness, hue axes or position");
+            });
+        }
+        if self.algo == auto_palette::Algorithm::DBSCAN {
+            self.epsilon_ui(ui, buffer);
+        }
         ui.horizontal(|ui| {
-            if ui.button("\u{ea60} Extract").clicked() && self.hthread.is_none() {
-                let img = buffer.clone().into_rgb8().into();
-                let max_color = self.max_color;
-                let basename = self.name.clone();
-                let algorithm = self.algo.clone();
-                let theme = self.theme.clone();
-                let (tx, rx) = mpsc::channel();
-                self.channel = Some(rx);
-                self.hthread = Some(thread::spawn(move || {
-                    let palette: auto_palette::Palette<f64> = auto_palette::Palette::extract_with_algorithm(&img, &algorithm);
-                    let swatches = match theme {
-                        PaletteTheme::Vivid => palette.swatches_with_theme(max_color, &auto_palette::Vivid),
-                        PaletteTheme::Muted => palette.swatches_with_theme(max_color, &auto_palette::Muted),
-                        PaletteTheme::Light => palette.swatches_with_theme(max_color, &auto_palette::Light),
-                        PaletteTheme::Dark => palette.swatches_with_theme(max_color, &auto_palette::Dark),
-                    };
-                    let mut colors : Vec<color_item::ColorItem> = swatches.iter().map(|swatch| {
-                        let clr = swatch.color().to_rgb();
-                        let pos = swatch.position();
-                        let pop = swatch.population();
-                        let name = format!("{}-({},{})-{}",basename,pos.0,pos.1,pop);
-                        let color = color_item::ColorItem{
-                            name:name,
-                            r: clr.r(),
-                            g: clr.g(),
-                            b: clr.b(),
-                        };
-                        color
-                    }).collect();
-                    colors.dedup();
-                    colors.sort_by(|a,b| a.name.cmp(&b.name));
-                    tx.send(colors).unwrap();
-                }));
+            ui.checkbox(&mut self.tiled, "\u{f0a5a} Tiled")
+                .on_hover_text("extract per tile in parallel and merge, for large images");
+            ui.add_enabled(
+                self.tiled,
+                egui::DragValue::new(&mut self.tile_size)
+                    .speed(4)
+                    .range(32..=4096)
+                    .suffix(" px"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0a4d} Max Size:");
+            ui.add_enabled(
+                !self.tiled,
+                egui::DragValue::new(&mut self.max_dimension)
+                    .speed(4)
+                    .range(64..=8192)
+                    .suffix(" px"),
+            )
+            .on_hover_text("larger images are scaled down first, higher is slower but keeps small details")
+            .on_disabled_hover_text("every tile is scaled down on its own");
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0453} Center Bias:");
+            ui.add(egui::Slider::new(&mut self.center_bias, 0f64..=16f64).fixed_decimals(1))
+                .on_hover_text("count pixels near the center more, for a central subject");
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0b33} Edge Bias:");
+            ui.add(egui::Slider::new(&mut self.edge_bias, 0f64..=1f64).fixed_decimals(2))
+                .on_hover_text("count pixels on edges and detail more than flat areas, pulls out accent colors");
+        });
+        ui.checkbox(&mut self.alpha, "\u{f0ee4} Alpha Aware")
+            .on_hover_text("cluster on opacity too, for logos and icons with transparency");
+        let print = fingerprint(buffer);
+        ui.collapsing("\u{f019f} Crop", |ui| {
+            self.crop_ui(ui, buffer, print);
+        });
+        let key = self.key(Source::Image(print), buffer);
+        ui.horizontal(|ui| {
+            let clicked = ui.button("\u{ea60} Extract").clicked();
+            if clicked || self.autostart {
+                self.autostart = false;
+                self.deliver = true;
             }
+            ui.checkbox(&mut self.live, "\u{f0450} Live")
+                .on_hover_text("re-extract while the settings change, then add from the preview");
             if self.hthread.is_some() {
-                ui.spinner();
+                self.progress_ui(ui);
+                if ui.button("\u{f0156} Cancel").clicked() {
+                    self.cancel();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0337} URL:");
+            ui.text_edit_singleline(&mut self.url);
+            let enabled = !self.url.trim().is_empty() && self.hthread.is_none();
+            if ui
+                .add_enabled(enabled, egui::Button::new("\u{f01da} Extract from URL"))
+                .clicked()
+            {
+                self.deliver = true;
+                let url = self.url.trim().to_string();
+                self.start_url(self.key(Source::Url(url.clone()), buffer), url);
             }
         });
         if let Some(rx) = &self.channel {
-            if let Ok(colors) = rx.try_recv() {
+            if let Ok((finished, result)) = rx.try_recv() {
                 self.hthread = None;
                 self.channel = None;
-                self.name = crate::utils::get_random_name(5);
-                return Some(colors);
+                self.progress_channel = None;
+                match result {
+                    Ok(palette) => {
+                        let is_url = matches!(finished.source, Source::Url(_));
+                        let current = finished == key;
+                        self.cache = Some((finished, palette));
+                        self.preview = None;
+                        // a stale result is kept but the button waits for the current one
+                        if self.deliver && (current || is_url) {
+                            ret = self.finish();
+                        }
+                    }
+                    Err(e) => {
+                        self.deliver = false;
+                        self.error = Some(e);
+                    }
+                }
             }
         }
-        return None;
+        if self.deliver && self.hthread.is_none() {
+            if self.is_cached(&key) {
+                ret = self.finish();
+            } else {
+                self.pending = None;
+                self.start(key.clone(), buffer);
+            }
+        }
+        if self.live {
+            // one run at a time, changes made meanwhile wait for it to finish
+            if self.hthread.is_none() && !self.is_cached(&key) {
+                let now = ui.input(|i| i.time);
+                if self.pending.as_ref().map(|(pending, _)| pending) != Some(&key) {
+                    self.pending = Some((key.clone(), now));
+                }
+                let since = now - self.pending.as_ref().map_or(now, |(_, at)| *at);
+                if since >= LIVE_DEBOUNCE {
+                    self.pending = None;
+                    self.start(key.clone(), buffer);
+                } else {
+                    ui.ctx().request_repaint_after(std::time::Duration::from_secs_f64(
+                        LIVE_DEBOUNCE - since,
+                    ));
+                }
+            }
+            ui.separator();
+            match self.cached_colors() {
+                Some(colors) => {
+                    ui.horizontal_wrapped(|ui| {
+                        for color in colors.iter() {
+                            let (rect, response) =
+                                ui.allocate_exact_size(egui::vec2(24f32, 24f32), egui::Sense::hover());
+                            color_item::paint_swatch(
+                                ui.painter(),
+                                rect,
+                                color_item::swatch_rounding().max(2f32),
+                                color.to_color32(),
+                                egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+                            );
+                            response.on_hover_text(color.get_hex());
+                        }
+                    });
+                    if ui.button("\u{ea60} Add").clicked() {
+                        ret = self.finish();
+                    }
+                }
+                None => {
+                    ui.label("Extracting...");
+                }
+            }
+        }
+        return ret;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop_pixels() {
+        let rect = |x0: f32, y0: f32, x1: f32, y1: f32| {
+            egui::Rect::from_min_max(egui::pos2(x0, y0), egui::pos2(x1, y1))
+        };
+        assert_eq!(crop_pixels(egui::Rect::NOTHING, 100, 50), None);
+        assert_eq!(crop_pixels(rect(0.0, 0.0, 1.0, 1.0), 100, 50), None);
+        assert_eq!(crop_pixels(rect(0.5, 0.5, 0.5, 0.9), 100, 50), None);
+        assert_eq!(crop_pixels(rect(0.25, 0.5, 0.75, 1.0), 100, 50), Some([25, 25, 50, 25]));
+        // partial pixels are included, the image bounds are kept
+        assert_eq!(crop_pixels(rect(0.101, -0.5, 0.25, 0.5), 100, 50), Some([10, 0, 15, 25]));
     }
 }
@@ -0,0 +1,110 @@
+use crate::color_item;
+use crate::settings;
+use eframe::egui;
+use std::collections::BTreeMap;
+
+// colors shown in the quick-add grid
+const MAX_SHOWN: usize = 32;
+
+/// (hex, count) of the most added colors, most first, ties in hex order
+fn most_used(usage: &BTreeMap<String, u32>, n: usize) -> Vec<(String, u32)> {
+    let mut used: Vec<(String, u32)> = usage.iter().map(|(hex, count)| (hex.clone(), *count)).collect();
+    used.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    used.truncate(n);
+    return used;
+}
+
+/// count every added color in the settings, keyed by hex.
+/// the counts are written with the settings on exit, not on every add
+pub fn record_usage(colors: &[color_item::ColorItem]) {
+    if colors.is_empty() {
+        return;
+    }
+    settings::update_unsaved(|settings| {
+        for color in colors {
+            *settings.color_usage.entry(color.get_hex()).or_insert(0) += 1;
+        }
+    });
+}
+
+pub struct Frequent {}
+
+impl Frequent {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl super::AddColor for Frequent {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Frequently Used".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _buffer: &image::DynamicImage,
+        _colors: &Vec<color_item::ColorItem>,
+    ) -> Option<Vec<color_item::ColorItem>> {
+        let mut ret = None;
+        let used = settings::with(|s| most_used(&s.color_usage, MAX_SHOWN));
+        if used.is_empty() {
+            ui.label("Colors you add are counted here, the most used ones can be added with one click.");
+            return None;
+        }
+        ui.label("Click a color to add it again.");
+        ui.horizontal_wrapped(|ui| {
+            for (hex, count) in used.iter() {
+                let Some(color) = color_item::ColorItem::from_hex(hex, hex) else {
+                    continue;
+                };
+                let (rect, response) = ui.allocate_exact_size(egui::vec2(32f32, 32f32), egui::Sense::click());
+                color_item::paint_swatch(
+                    ui.painter(),
+                    rect,
+                    color_item::swatch_rounding().max(2f32),
+                    color.to_color32(),
+                    egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+                );
+                let response = response.on_hover_text(format!(
+                    "{}, added {} time{}",
+                    hex,
+                    count,
+                    if *count == 1 { "" } else { "s" }
+                ));
+                if response.clicked() {
+                    ret = Some(vec![color]);
+                }
+            }
+        });
+        ui.separator();
+        if ui.button("\u{f0b89} Clear History").clicked() {
+            let mut settings = settings::get();
+            settings.color_usage.clear();
+            settings::set(settings);
+        }
+        return ret;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_used() {
+        let usage: BTreeMap<String, u32> = [("#000000", 2), ("#ff0000", 5), ("#00ff00", 2), ("#0000ff", 1)]
+            .iter()
+            .map(|(hex, count)| (hex.to_string(), *count))
+            .collect();
+        let used = most_used(&usage, 3);
+        assert_eq!(
+            used,
+            vec![
+                ("#ff0000".to_string(), 5),
+                ("#000000".to_string(), 2),
+                ("#00ff00".to_string(), 2),
+            ]
+        );
+        assert!(most_used(&BTreeMap::new(), 3).is_empty());
+    }
+}
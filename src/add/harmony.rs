@@ -0,0 +1,151 @@
+use crate::color_item;
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HarmonyKind {
+    Complementary,
+    Analogous,
+    Triadic,
+    Tetradic,
+}
+
+impl HarmonyKind {
+    // hue offsets in degrees with the suffix each color is named with
+    fn offsets(&self) -> Vec<(f32, &'static str)> {
+        return match self {
+            HarmonyKind::Complementary => vec![(180f32, "comp")],
+            HarmonyKind::Analogous => vec![(-30f32, "ana1"), (30f32, "ana2")],
+            HarmonyKind::Triadic => vec![(120f32, "tri1"), (240f32, "tri2")],
+            HarmonyKind::Tetradic => vec![(90f32, "tet1"), (180f32, "tet2"), (270f32, "tet3")],
+        };
+    }
+}
+
+pub struct Harmony {
+    name: String,
+    base: [u8; 3],
+    hex: String,
+    kind: HarmonyKind,
+    // add the base color in front of the derived ones
+    include_base: bool,
+}
+
+// the base rotated by every offset of `kind` in HSV, saturation and value are kept
+fn harmony(base: &color_item::ColorItem, kind: HarmonyKind) -> Vec<color_item::ColorItem> {
+    let (h, s, v) = (base.get_h(), base.get_s(), base.get_v());
+    return kind
+        .offsets()
+        .into_iter()
+        .map(|(offset, suffix)| {
+            let name = format!("{}-{}", base.name, suffix);
+            color_item::ColorItem::from_hsv((h + offset).rem_euclid(360f32), s, v, &name)
+        })
+        .collect();
+}
+
+impl Harmony {
+    pub fn new() -> Self {
+        Self {
+            name: crate::utils::get_random_name(5),
+            base: [220, 80, 60],
+            hex: "#dc503c".into(),
+            kind: HarmonyKind::Complementary,
+            include_base: true,
+        }
+    }
+}
+
+impl super::AddColor for Harmony {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Color Harmony".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _buffer: &image::DynamicImage,
+        _colors: &Vec<color_item::ColorItem>,
+    ) -> Option<Vec<color_item::ColorItem>> {
+        let mut ret = None;
+        ui.horizontal(|ui| {
+            ui.label("\u{f1050} Name:");
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{e22b} Base:");
+            if egui::color_picker::color_edit_button_srgb(ui, &mut self.base).changed() {
+                let [r, g, b] = self.base;
+                self.hex = color_item::ColorItem::new("", r, g, b).get_hex();
+            }
+            if ui
+                .add(egui::TextEdit::singleline(&mut self.hex).desired_width(80f32))
+                .changed()
+            {
+                if let Some(color) = color_item::ColorItem::from_hex(&self.hex, "") {
+                    self.base = [color.r, color.g, color.b];
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.kind, HarmonyKind::Complementary, "Complementary");
+            ui.selectable_value(&mut self.kind, HarmonyKind::Analogous, "Analogous")
+                .on_hover_text("\u{00b1}30\u{00b0}");
+            ui.selectable_value(&mut self.kind, HarmonyKind::Triadic, "Triadic");
+            ui.selectable_value(&mut self.kind, HarmonyKind::Tetradic, "Tetradic");
+        });
+        ui.checkbox(&mut self.include_base, "Include Base");
+        let [r, g, b] = self.base;
+        let base = color_item::ColorItem::new(&self.name, r, g, b);
+        let mut colors = harmony(&base, self.kind);
+        if self.include_base {
+            colors.insert(0, base);
+        }
+        ui.horizontal_wrapped(|ui| {
+            for color in colors.iter() {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(40f32, 20f32), egui::Sense::hover());
+                color_item::paint_swatch(
+                    ui.painter(),
+                    rect,
+                    color_item::swatch_rounding(),
+                    color.to_color32(),
+                    egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+                );
+                response.on_hover_text(format!("{} {}", color.name, color.get_hex()));
+            }
+        });
+        if ui.button("\u{ea60} Add").clicked() {
+            ret = Some(colors);
+        }
+        return ret;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complementary() {
+        let red = color_item::ColorItem::new("red", 255, 0, 0);
+        let colors = harmony(&red, HarmonyKind::Complementary);
+        assert_eq!(colors, vec![color_item::ColorItem::new("red-comp", 0, 255, 255)]);
+    }
+
+    #[test]
+    fn test_names_and_hues() {
+        let base = color_item::ColorItem::new("sky", 0, 128, 255);
+        let analogous = harmony(&base, HarmonyKind::Analogous);
+        let names: Vec<&str> = analogous.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["sky-ana1", "sky-ana2"]);
+        let tetradic = harmony(&base, HarmonyKind::Tetradic);
+        assert_eq!(tetradic.len(), 3);
+        for (color, offset) in tetradic.iter().zip([90f32, 180f32, 270f32]) {
+            let expected = (base.get_h() + offset).rem_euclid(360f32);
+            assert!((color.get_h() - expected).abs() < 1.5);
+        }
+        // red at 0 degrees wraps around for the analogous hue below it
+        let red = color_item::ColorItem::new("red", 255, 0, 0);
+        let below = &harmony(&red, HarmonyKind::Analogous)[0];
+        assert!((below.get_h() - 330f32).abs() < 1.5);
+    }
+}
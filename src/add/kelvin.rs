@@ -0,0 +1,93 @@
+use crate::color_item;
+use eframe::egui;
+
+const MIN_KELVIN: f32 = 1000f32;
+const MAX_KELVIN: f32 = 40000f32;
+const TRACK_STEPS: usize = 64;
+
+pub struct Kelvin {
+    kelvin: f32,
+    brightness: f32,
+}
+
+impl Kelvin {
+    pub fn new() -> Self {
+        Self {
+            kelvin: 6500f32,
+            brightness: 1f32,
+        }
+    }
+    // warm to cool strip under the slider, same logarithmic mapping as the slider
+    fn paint_track(ui: &mut egui::Ui, width: f32) {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(width, 12f32), egui::Sense::hover());
+        let painter = ui.painter();
+        let step = rect.width() / TRACK_STEPS as f32;
+        for i in 0..TRACK_STEPS {
+            let t = (i as f32 + 0.5) / TRACK_STEPS as f32;
+            let kelvin = MIN_KELVIN * (MAX_KELVIN / MIN_KELVIN).powf(t);
+            let (r, g, b) = crate::utils::kelvin_to_rgb(kelvin, 1f32);
+            let x = rect.left() + step * i as f32;
+            painter.rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(x, rect.top()),
+                    egui::pos2(x + step + 0.5, rect.bottom()),
+                ),
+                0f32,
+                egui::Color32::from_rgb(r, g, b),
+            );
+        }
+    }
+}
+
+impl super::AddColor for Kelvin {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Color Temperature".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _buffer: &image::DynamicImage,
+        _colors: &Vec<color_item::ColorItem>,
+    ) -> Option<Vec<color_item::ColorItem>> {
+        let mut ret = None;
+        let slider = ui
+            .horizontal(|ui| {
+                ui.label("\u{f050f} Temperature:");
+                return ui.add(
+                    egui::Slider::new(&mut self.kelvin, MIN_KELVIN..=MAX_KELVIN)
+                        .logarithmic(true)
+                        .fixed_decimals(0)
+                        .suffix(" K"),
+                );
+            })
+            .inner;
+        ui.horizontal(|ui| {
+            ui.add_space((slider.rect.left() - ui.cursor().left()).max(0f32));
+            Self::paint_track(ui, ui.spacing().slider_width);
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f00e0} Brightness:");
+            ui.add(egui::Slider::new(&mut self.brightness, 0f32..=1f32).fixed_decimals(2));
+        });
+        let (r, g, b) = crate::utils::kelvin_to_rgb(self.kelvin, self.brightness);
+        let color = color_item::ColorItem::new(&format!("{}K", self.kelvin.round()), r, g, b);
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(40f32, 20f32), egui::Sense::hover());
+            color_item::paint_swatch(
+                ui.painter(),
+                rect,
+                color_item::swatch_rounding(),
+                color.to_color32(),
+                egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+            );
+            ui.label(
+                egui::RichText::new(color.get_hex()).color(color.get_full_value_color32()),
+            );
+        });
+        if ui.button("\u{ea60} Add").clicked() {
+            ret = Some(vec![color]);
+        }
+        return ret;
+    }
+}
@@ -6,14 +6,39 @@ mod customized;
 mod picker;
 mod preset;
 mod extract;
+mod kelvin;
+mod random;
+mod blend;
+mod harmony;
+pub mod frequent;
+
+pub use extract::BatchSettings;
+
+// the name of the next color of a component, numbered from the name seed when one is set
+fn next_name(len: usize, count: &mut u64) -> String {
+    let Some(seed) = crate::settings::with(|s| s.name_seed) else {
+        return crate::utils::get_random_name(len);
+    };
+    let name = crate::utils::get_random_name_seeded(len, seed.wrapping_add(*count));
+    *count += 1;
+    return name;
+}
 
 lazy_static::lazy_static!{
     pub static ref NAMELIST: Vec<(String,AddColorComponent)> = get_component_namelist();
 }
 
 pub trait AddColor {
-    fn paint_ui(&mut self, ui: &mut egui::Ui, buffer: &image::DynamicImage) -> Option<Vec<color_item::ColorItem>>;
+    fn paint_ui(&mut self, ui: &mut egui::Ui, buffer: &image::DynamicImage, colors: &Vec<color_item::ColorItem>) -> Option<Vec<color_item::ColorItem>>;
     fn get_name(&self) -> String;
+    /// a failure to report to the user, taken once
+    fn take_error(&mut self) -> Option<String> {
+        return None;
+    }
+    /// settings to extract a folder of images with, for components that extract palettes
+    fn batch_settings(&self) -> Option<BatchSettings> {
+        return None;
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -22,6 +47,11 @@ pub enum AddColorComponent {
     Picker,
     Preset,
     Extract,
+    Kelvin,
+    Random,
+    Blend,
+    Frequent,
+    Harmony,
 }
 
 pub fn get_component(component: AddColorComponent) -> Box<dyn AddColor> {
@@ -30,15 +60,30 @@ pub fn get_component(component: AddColorComponent) -> Box<dyn AddColor> {
         AddColorComponent::Picker => Box::new(picker::Picker::new()),
         AddColorComponent::Preset => Box::new(preset::Preset::new()),
         AddColorComponent::Extract => Box::new(extract::Extract::new()),
+        AddColorComponent::Kelvin => Box::new(kelvin::Kelvin::new()),
+        AddColorComponent::Random => Box::new(random::Random::new()),
+        AddColorComponent::Blend => Box::new(blend::Blend::new()),
+        AddColorComponent::Frequent => Box::new(frequent::Frequent::new()),
+        AddColorComponent::Harmony => Box::new(harmony::Harmony::new()),
     }
 }
 
+/// extraction component that starts on the current image right away
+pub fn get_extract_autostart() -> Box<dyn AddColor> {
+    return Box::new(extract::Extract::autostart());
+}
+
 pub fn get_component_namelist() -> Vec<(String, AddColorComponent)> {
     let mut list = Vec::new();
     list.push(("\u{f03a} Customized".into(), AddColorComponent::Customized));
     list.push(("\u{f0485} Color Picker".into(), AddColorComponent::Picker));
     list.push(("\u{eb9c} Presets".into(), AddColorComponent::Preset));
     list.push(("\u{ebac} Extract Palette".into(), AddColorComponent::Extract));
+    list.push(("\u{f050f} Color Temperature".into(), AddColorComponent::Kelvin));
+    list.push(("\u{f049d} Random Palette".into(), AddColorComponent::Random));
+    list.push(("\u{f0ad5} Blend Palettes".into(), AddColorComponent::Blend));
+    list.push(("\u{f0c4e} Frequently Used".into(), AddColorComponent::Frequent));
+    list.push(("\u{f0e09} Color Harmony".into(), AddColorComponent::Harmony));
     return list;
 }
 
@@ -1,9 +1,23 @@
 use crate::color_item;
 use eframe::egui;
+use image::GenericImageView;
+
+// longest side of the image shown to pick from, sampling reads the full image
+const EYEDROPPER_TEXTURE: u32 = 1024;
+// pixels on each side of the sampled one in the loupe
+const LOUPE_RADIUS: i64 = 5;
+// size of one magnified pixel in the loupe
+const LOUPE_CELL: f32 = 10f32;
+// sides of the square the eyedropper averages over
+const BRUSHES: [u32; 4] = [1, 3, 5, 9];
 
 pub struct Picker {
     name: String,
     color: egui::Color32,
+    // image shown to pick from, with the fingerprint it was made from
+    texture: Option<(u64, egui::TextureHandle)>,
+    // side of the square averaged by the eyedropper, 1 for the single pixel
+    brush: u32,
 }
 
 impl Picker {
@@ -11,27 +25,132 @@ impl Picker {
         Self {
             name: crate::utils::get_random_name(8),
             color: egui::Color32::BLACK,
+            texture: None,
+            brush: 1,
+        }
+    }
+    // the image fit into the tab, clicking a pixel returns its color
+    fn eyedropper_ui(&mut self, ui: &mut egui::Ui, buffer: &image::DynamicImage) -> Option<image::Rgba<u8>> {
+        let print = super::extract::fingerprint(buffer);
+        if self.texture.as_ref().map(|(of, _)| *of) != Some(print) {
+            let small = buffer.thumbnail(EYEDROPPER_TEXTURE, EYEDROPPER_TEXTURE).to_rgba8();
+            let size = [small.width() as _, small.height() as _];
+            let image = egui::ColorImage::from_rgba_unmultiplied(size, small.as_flat_samples().as_slice());
+            let texture = ui.ctx().load_texture("picker_eyedropper", image, egui::TextureOptions::default());
+            self.texture = Some((print, texture));
+        }
+        let Some((_, texture)) = &self.texture else {
+            return None;
+        };
+        // the tab scrolls, so the height is bounded by the width instead of what is left
+        let width = ui.available_width();
+        let size = egui::vec2(width, width.min(ui.available_height()));
+        let response = ui.add(
+            egui::Image::from_texture(egui::load::SizedTexture::new(
+                texture.id(),
+                [buffer.width() as f32, buffer.height() as f32],
+            ))
+            .fit_to_exact_size(size)
+            .sense(egui::Sense::click()),
+        );
+        let rect = response.rect;
+        let hovered = response
+            .hover_pos()
+            .and_then(|pos| image_pixel(rect, pos, buffer.width(), buffer.height()));
+        if let Some((x, y)) = hovered {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+            response.clone().on_hover_ui_at_pointer(|ui| loupe_ui(ui, buffer, x, y, self.brush));
+        }
+        if response.clicked() {
+            let (x, y) = hovered?;
+            return Some(sample(buffer, x, y, self.brush));
+        }
+        return None;
+    }
+}
+
+// pixel of an image of `width` x `height` drawn scaled into `rect` under `pos`
+fn image_pixel(rect: egui::Rect, pos: egui::Pos2, width: u32, height: u32) -> Option<(u32, u32)> {
+    if width == 0 || height == 0 || !rect.is_positive() || !rect.contains(pos) {
+        return None;
+    }
+    let v = (pos - rect.min) / rect.size();
+    let x = ((v.x * width as f32) as u32).min(width - 1);
+    let y = ((v.y * height as f32) as u32).min(height - 1);
+    return Some((x, y));
+}
+
+// mean of the brush x brush square around (x, y), cut off at the image border.
+// averaged in linear light, plain sRGB values would darken the mix
+fn sample(buffer: &image::DynamicImage, x: u32, y: u32, brush: u32) -> image::Rgba<u8> {
+    let radius = (brush / 2) as i64;
+    let mut sum = [0f64; 4];
+    let mut count = 0f64;
+    for py in y as i64 - radius..=y as i64 + radius {
+        for px in x as i64 - radius..=x as i64 + radius {
+            if px < 0 || py < 0 || px >= buffer.width() as i64 || py >= buffer.height() as i64 {
+                continue;
+            }
+            let image::Rgba([r, g, b, a]) = buffer.get_pixel(px as u32, py as u32);
+            for (i, channel) in [r, g, b].into_iter().enumerate() {
+                sum[i] += crate::utils::lch::to_linear(channel as f64 / 255f64);
+            }
+            sum[3] += a as f64;
+            count += 1f64;
         }
     }
+    let channel = |i: usize| (crate::utils::lch::from_linear(sum[i] / count) * 255f64).round().clamp(0f64, 255f64) as u8;
+    return image::Rgba([channel(0), channel(1), channel(2), (sum[3] / count).round() as u8]);
+}
+
+// magnified neighborhood of (x, y), read from the image itself so it stays sharp
+fn loupe_ui(ui: &mut egui::Ui, buffer: &image::DynamicImage, x: u32, y: u32, brush: u32) {
+    let cells = (LOUPE_RADIUS * 2 + 1) as f32;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(cells, cells) * LOUPE_CELL, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0f32, egui::Color32::from_gray(32));
+    for dy in -LOUPE_RADIUS..=LOUPE_RADIUS {
+        for dx in -LOUPE_RADIUS..=LOUPE_RADIUS {
+            let (px, py) = (x as i64 + dx, y as i64 + dy);
+            if px < 0 || py < 0 || px >= buffer.width() as i64 || py >= buffer.height() as i64 {
+                continue;
+            }
+            let image::Rgba([r, g, b, a]) = buffer.get_pixel(px as u32, py as u32);
+            let min = rect.min + egui::vec2((dx + LOUPE_RADIUS) as f32, (dy + LOUPE_RADIUS) as f32) * LOUPE_CELL;
+            let cell = egui::Rect::from_min_size(min, egui::vec2(LOUPE_CELL, LOUPE_CELL));
+            painter.rect_filled(cell, 0f32, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+        }
+    }
+    // outline what the brush averages
+    let radius = (brush / 2) as f32;
+    let center = egui::Rect::from_min_size(
+        rect.min + egui::vec2(LOUPE_RADIUS as f32 - radius, LOUPE_RADIUS as f32 - radius) * LOUPE_CELL,
+        egui::vec2(brush as f32, brush as f32) * LOUPE_CELL,
+    );
+    painter.rect_stroke(center, 0f32, egui::Stroke::new(1f32, egui::Color32::WHITE));
+    let image::Rgba([r, g, b, _]) = sample(buffer, x, y, brush);
+    ui.label(format!("#{:02x}{:02x}{:02x} at ({},{})", r, g, b, x, y));
 }
 
 impl super::AddColor for Picker {
     fn get_name(&self) -> String {
         return "\u{eae6} Color Picker".into();
     }
-    fn paint_ui(&mut self, ui: &mut egui::Ui, _buffer:&image::DynamicImage) -> Option<Vec<color_item::ColorItem>> {
+    fn paint_ui(&mut self, ui: &mut egui::Ui, buffer:&image::DynamicImage, _colors: &Vec<color_item::ColorItem>) -> Option<Vec<color_item::ColorItem>> {
         let mut ret = None;
         ui.horizontal(|ui| {
             ui.label("\u{f1050} Name:");
-            ui.text_edit_singleline(&mut self.name);
+            if let Some([r, g, b]) = color_item::name_edit(ui, &mut self.name) {
+                self.color = egui::Color32::from_rgb(r, g, b);
+            }
         });
         if ui.button("\u{ea60} Add").clicked() {
-            ret = Some(vec![color_item::ColorItem {
-                name: self.name.clone(),
-                r: self.color.r(),
-                g: self.color.g(),
-                b: self.color.b(),
-            }]);
+            ret = Some(vec![color_item::ColorItem::new(
+                &self.name,
+                self.color.r(),
+                self.color.g(),
+                self.color.b(),
+            )]);
             self.name = crate::utils::get_random_name(8);
         }
         egui::color_picker::color_picker_color32(
@@ -39,6 +158,69 @@ impl super::AddColor for Picker {
             &mut self.color,
             egui::color_picker::Alpha::Opaque,
         );
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("\u{f020a} Eyedropper:");
+            egui::ComboBox::from_id_salt("picker_brush")
+                .selected_text(format!("{0}x{0} px", self.brush))
+                .show_ui(ui, |ui| {
+                    for brush in BRUSHES {
+                        ui.selectable_value(&mut self.brush, brush, format!("{0}x{0} px", brush));
+                    }
+                })
+                .response
+                .on_hover_text("average the square around the cursor, smooths out noise in photos");
+        });
+        ui.label("click the image to add the color under the cursor");
+        if let Some(image::Rgba([r, g, b, a])) = self.eyedropper_ui(ui, buffer) {
+            self.color = egui::Color32::from_rgb(r, g, b);
+            ret = Some(vec![color_item::ColorItem::new(&self.name, r, g, b).with_alpha(a)]);
+            self.name = crate::utils::get_random_name(8);
+        }
         return ret;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_pixel() {
+        // a 200x100 image drawn at half size from (10, 20)
+        let rect = egui::Rect::from_min_size(egui::pos2(10f32, 20f32), egui::vec2(100f32, 50f32));
+        assert_eq!(image_pixel(rect, egui::pos2(10f32, 20f32), 200, 100), Some((0, 0)));
+        assert_eq!(image_pixel(rect, egui::pos2(60f32, 45f32), 200, 100), Some((100, 50)));
+        assert_eq!(image_pixel(rect, egui::pos2(60.4, 45.4), 200, 100), Some((100, 50)));
+        // the far edge still lands on the last pixel
+        assert_eq!(image_pixel(rect, egui::pos2(110f32, 70f32), 200, 100), Some((199, 99)));
+        assert_eq!(image_pixel(rect, egui::pos2(9f32, 30f32), 200, 100), None);
+        assert_eq!(image_pixel(rect, egui::pos2(50f32, 71f32), 200, 100), None);
+        // scaled up, every pixel covers several points
+        let rect = egui::Rect::from_min_size(egui::pos2(0f32, 0f32), egui::vec2(40f32, 40f32));
+        assert_eq!(image_pixel(rect, egui::pos2(9.9, 10.1), 4, 4), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_sample() {
+        let checkerboard = image::DynamicImage::from(image::RgbImage::from_fn(16, 16, |x, y| {
+            if (x + y) % 2 == 0 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        }));
+        // a single pixel is taken as is
+        assert_eq!(sample(&checkerboard, 4, 4, 1), image::Rgba([255, 0, 0, 255]));
+        assert_eq!(sample(&checkerboard, 4, 5, 1), image::Rgba([0, 0, 255, 255]));
+        // red and blue average to purple, brighter than the 128 of plain sRGB averaging
+        let image::Rgba([r, g, b, a]) = sample(&checkerboard, 8, 8, 9);
+        assert_eq!((g, a), (0, 255));
+        assert!((180..=195).contains(&r), "{}", r);
+        assert!((180..=195).contains(&b), "{}", b);
+        // cut off at the border, the corner averages the 2x2 pixels inside
+        let image::Rgba([r, _, b, _]) = sample(&checkerboard, 0, 0, 3);
+        assert_eq!(r, b);
+        assert!(r > 128);
+    }
+}
@@ -6,20 +6,56 @@ const MARGIN: f32 = 10f32;
 pub struct Preset {
     colorlist: Vec<(String, Vec<color_item::ColorItem>)>,
     msg: Option<String>,
+    // set when the presets folder itself is absent, which is not an error
+    missing_dir: Option<std::path::PathBuf>,
     selected_preset: usize,
     search_text: String,
     selected_index: usize,
+    search_all: bool,
+    use_regex: bool,
+    case_insensitive: bool,
+    // last regex pattern with what it compiled to, reused while the pattern stays the same
+    regex: Option<(String, Result<regex::Regex, String>)>,
+    // name the current colors are saved under as a new preset
+    save_name: String,
+    // failed save, reported once as a toast
+    error: Option<String>,
+}
+
+// how the search text is matched against color names, built once per frame
+enum Search {
+    // nothing typed, every color shows without highlight
+    Empty,
+    Fuzzy(String),
+    Regex(regex::Regex),
+    // a pattern that does not compile matches nothing
+    Invalid,
+}
+
+// characters that cannot be part of a file name on some platform
+const INVALID_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+// file name of a preset called `name`, none when it cannot be one
+fn preset_file_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.contains(INVALID_NAME_CHARS) {
+        return None;
+    }
+    return Some(format!("{}.json", name));
 }
 
 impl Preset {
-    pub fn load_colorlist() -> Result<Vec<(String, Vec<color_item::ColorItem>)>, String> {
-        //read all json files in exepath/preset
+    pub fn preset_dir() -> Result<std::path::PathBuf, String> {
         let exe_path = std::env::current_exe()
             .or(Err("Error Get EXE Path".to_string()))?
             .parent()
             .ok_or("No Parent Dir".to_string())?
             .to_path_buf();
-        let preset_path = exe_path.join("presets");
+        return Ok(exe_path.join("presets"));
+    }
+    pub fn load_colorlist() -> Result<Vec<(String, Vec<color_item::ColorItem>)>, String> {
+        //read all json files in exepath/preset
+        let preset_path = Self::preset_dir()?;
         let mut colorlist = Vec::new();
         for entry in std::fs::read_dir(preset_path).or(Err("Error Read Directory".to_string()))? {
             let entry = entry.or(Err("Error Read Entry".to_string()))?;
@@ -44,35 +80,157 @@ impl Preset {
         return Ok(colorlist);
     }
     pub fn new() -> Self {
-        let (colorlist, msg) = match Self::load_colorlist() {
-            Ok(colorlist) => (colorlist, None),
-            Err(msg) => {
-                println!("Error: {}", msg);
-                (Vec::new(), Some(msg))
+        let missing_dir = match Self::preset_dir() {
+            Ok(dir) if !dir.exists() => Some(dir),
+            _ => None,
+        };
+        let (colorlist, msg) = if missing_dir.is_some() {
+            (Vec::new(), None)
+        } else {
+            match Self::load_colorlist() {
+                Ok(colorlist) => (colorlist, None),
+                Err(msg) => {
+                    println!("Error: {}", msg);
+                    (Vec::new(), Some(msg))
+                }
             }
         };
 
         Self {
             colorlist,
             msg,
+            missing_dir,
             selected_preset: 0,
             search_text: "".into(),
             selected_index: 0,
+            search_all: false,
+            use_regex: false,
+            case_insensitive: true,
+            regex: None,
+            save_name: String::new(),
+            error: None,
+        }
+    }
+    // write `colors` as presets/<name>.json, creating the folder if needed
+    fn save_preset(name: &str, colors: &[color_item::ColorItem]) -> Result<(), String> {
+        let file_name = preset_file_name(name).ok_or(format!("\"{}\" is not a valid preset name", name))?;
+        let dir = Self::preset_dir()?;
+        let path = dir.join(file_name);
+        if path.exists() {
+            return Err(format!("A preset named \"{}\" already exists", name.trim()));
         }
+        let json = serde_json::to_string_pretty(colors).map_err(|e| e.to_string())?;
+        let written = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, json));
+        return written.map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                format!("Cannot save the preset, the presets folder is read-only: {}", dir.display())
+            }
+            _ => format!("Cannot save the preset to {}: {}", path.display(), e),
+        });
     }
-    pub fn show_color(
+    // name field and button that store the current color list as a preset
+    fn save_ui(&mut self, ui: &mut egui::Ui, colors: &Vec<color_item::ColorItem>) {
+        ui.horizontal(|ui| {
+            ui.label("\u{f0193} Save As:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.save_name)
+                    .hint_text("preset name")
+                    .desired_width(120f32),
+            );
+            let valid = !colors.is_empty() && preset_file_name(&self.save_name).is_some();
+            if ui
+                .add_enabled(valid, egui::Button::new("\u{f0193} Save"))
+                .on_hover_text("save the current colors as a new preset")
+                .on_disabled_hover_text("needs a name and at least one color")
+                .clicked()
+            {
+                let name = self.save_name.trim().to_string();
+                match Self::save_preset(&name, colors) {
+                    Ok(_) => {
+                        // reload so the new preset shows up, and show it
+                        *self = Self::new();
+                        if let Some(index) = self.colorlist.iter().position(|(n, _)| *n == name) {
+                            self.selected_preset = index;
+                        }
+                    }
+                    Err(msg) => self.error = Some(msg),
+                }
+            }
+        });
+        ui.separator();
+    }
+    // the search for the current text, the regex is compiled only when the pattern changed
+    fn search(&mut self) -> Search {
+        if self.search_text.is_empty() {
+            return Search::Empty;
+        }
+        if !self.use_regex {
+            return Search::Fuzzy(self.search_text.clone());
+        }
+        let pattern = if self.case_insensitive {
+            format!("(?i){}", self.search_text)
+        } else {
+            self.search_text.clone()
+        };
+        if self.regex.as_ref().is_none_or(|(cached, _)| *cached != pattern) {
+            let compiled = regex::Regex::new(&pattern).map_err(|e| e.to_string());
+            self.regex = Some((pattern, compiled));
+        }
+        return match &self.regex {
+            Some((_, Ok(regex))) => Search::Regex(regex.clone()),
+            _ => Search::Invalid,
+        };
+    }
+    /// score and matched byte ranges of `text`, none when it does not match
+    fn find_match(text: &str, search: &Search) -> Option<(i32, Vec<std::ops::Range<usize>>)> {
+        return match search {
+            Search::Empty | Search::Invalid => None,
+            Search::Fuzzy(select) => {
+                let (score, positions) = crate::utils::fuzzy::fuzzy_match(select, text)?;
+                Some((score, crate::utils::fuzzy::to_byte_ranges(text, &positions)))
+            }
+            Search::Regex(regex) => {
+                let mat = regex.find(text)?;
+                Some((0, vec![mat.start()..mat.end()]))
+            }
+        };
+    }
+    /// (score, preset, color) of every match in every preset, best first,
+    /// ties keep the order of the presets and their colors
+    fn global_matches(
+        colorlist: &[(String, Vec<color_item::ColorItem>)],
+        search: &Search,
+    ) -> Vec<(i32, usize, usize)> {
+        let mut matches = Vec::new();
+        for (preset, (_, colors)) in colorlist.iter().enumerate() {
+            for (index, color) in colors.iter().enumerate() {
+                if let Some((score, _)) = Self::find_match(&color.name, search) {
+                    matches.push((score, preset, index));
+                }
+            }
+        }
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        return matches;
+    }
+    fn show_color(
         ui: &mut egui::Ui,
         color: &color_item::ColorItem,
-        select: &String,
-    ) -> (bool, bool, egui::Response) {
-        // return (is_selected, is_clicked, response for scroll)
+        search: &Search,
+        source: Option<&str>,
+    ) -> (bool, Option<i32>, egui::Response) {
+        // return (is_clicked, match score, response for scroll)
         let mut add = false;
-        let mut selected = false;
+        let mut score = None;
         let response = ui
             .vertical(|ui| {
                 let response = ui
                     .horizontal(|ui| {
-                        let (rect, response) = ui.allocate_exact_size(
+                        // the preset a result of the global search comes from
+                        if let Some(source) = source {
+                            ui.label(egui::RichText::new(format!("\u{eb9c} {}", source)).weak());
+                            ui.separator();
+                        }
+                        let (rect, _) = ui.allocate_exact_size(
                             egui::vec2(20f32, ui.text_style_height(&egui::TextStyle::Body)),
                             egui::Sense {
                                 click: false,
@@ -81,87 +239,80 @@ impl Preset {
                             },
                         );
                         let painter = ui.painter();
-                        painter.rect(
+                        color_item::paint_swatch(
+                            painter,
                             rect,
-                            0f32,
+                            color_item::swatch_rounding(),
                             color.to_color32(),
                             egui::Stroke::new(0.5f32, egui::Color32::WHITE),
                         );
                         // detect selected text and highlight it into yellow
                         let text = color.name.clone();
-                        if select.is_empty() {
-                            ui.label(egui::RichText::new(text));
-                        } else {
-                            if let Ok(reg) = regex::Regex::new(select) {
-                                if let Some(mat) = reg.find(&text) {
-                                    let match_start = mat.start();
-                                    let match_end = mat.end();
-                                    let len = text.len();
-                                    let style = ui.style();
-                                    let mut job = egui::text::LayoutJob::default();
-                                    egui::RichText::new(&text[0..match_start])
-                                        .color(egui::Color32::YELLOW)
-                                        .append_to(
-                                            &mut job,
-                                            style,
-                                            egui::FontSelection::Default,
-                                            egui::Align::Center,
-                                        );
-                                    egui::RichText::new(&text[match_start..match_end])
-                                        .color(egui::Color32::GREEN)
-                                        .append_to(
-                                            &mut job,
-                                            style,
-                                            egui::FontSelection::Default,
-                                            egui::Align::Center,
-                                        );
-                                    egui::RichText::new(&text[match_end..len])
-                                        .color(egui::Color32::YELLOW)
-                                        .append_to(
-                                            &mut job,
-                                            style,
-                                            egui::FontSelection::Default,
-                                            egui::Align::Center,
-                                        );
-                                    ui.label(job);
-                                    selected = true;
-                                } else {
-                                    ui.label(text);
+                        match Self::find_match(&text, search) {
+                            Some((match_score, ranges)) => {
+                                let style = ui.style();
+                                let mut job = egui::text::LayoutJob::default();
+                                let mut append = |part: &str, color: egui::Color32| {
+                                    egui::RichText::new(part).color(color).append_to(
+                                        &mut job,
+                                        style,
+                                        egui::FontSelection::Default,
+                                        egui::Align::Center,
+                                    );
+                                };
+                                // matched parts green, the rest yellow
+                                let mut end = 0;
+                                for range in ranges {
+                                    append(&text[end..range.start], egui::Color32::YELLOW);
+                                    append(&text[range.clone()], egui::Color32::GREEN);
+                                    end = range.end;
                                 }
-                            } else {
+                                append(&text[end..], egui::Color32::YELLOW);
+                                ui.label(job);
+                                score = Some(match_score);
+                            }
+                            _ => {
                                 ui.label(text);
                             }
                         }
                         ui.separator();
-                        ui.label(
+                        let hex = ui.label(
                             egui::RichText::new(&color.get_hex())
                                 .color(color.get_full_value_color32()),
                         );
-                        return response;
+                        color_item::nearest_name_tooltip(hex, color);
                     })
-                    .inner;
-                ui.horizontal(|ui| {
-                    add = ui.button("\u{ea60} Add").clicked();
-                    if ui.button("\u{ebcc} Hex").clicked() {
-                        ui.output_mut(|o| {
-                            o.copied_text = color.get_hex();
-                        });
-                    }
-                    if ui.button("\u{ebcc} RGB").clicked() {
-                        ui.output_mut(|o| {
-                            o.copied_text = format!("{},{},{}", color.r, color.g, color.b);
-                        });
-                    }
-                    if ui.button("\u{ebcc} Name").clicked() {
-                        ui.output_mut(|o| {
-                            o.copied_text = color.name.clone();
-                        });
-                    }
-                });
+                    .response
+                    .interact(egui::Sense::click());
+                add = ui.button("\u{ea60} Add").clicked();
                 return response;
             })
             .inner;
-        return (add, selected, response);
+        // the rest of the actions live in a right click menu on the row
+        response.context_menu(|ui| {
+            if ui.button("\u{ea60} Add").clicked() {
+                add = true;
+                ui.close_menu();
+            }
+            ui.separator();
+            let mut copied = None;
+            if ui.button("\u{ebcc} Copy Hex").clicked() {
+                copied = Some(color.get_hex());
+            }
+            if ui.button("\u{ebcc} Copy RGB").clicked() {
+                copied = Some(format!("{},{},{}", color.r, color.g, color.b));
+            }
+            if ui.button("\u{ebcc} Copy Name").clicked() {
+                copied = Some(color.name.clone());
+            }
+            if let Some(text) = copied {
+                ui.output_mut(|o| {
+                    o.copied_text = text;
+                });
+                ui.close_menu();
+            }
+        });
+        return (add, score, response);
     }
 }
 
@@ -169,12 +320,38 @@ impl super::AddColor for Preset {
     fn get_name(&self) -> String {
         return "\u{eae6} Preset Color".into();
     }
-    fn paint_ui(&mut self, ui: &mut egui::Ui, _buffer:&image::DynamicImage) -> Option<Vec<color_item::ColorItem>> {
+    fn take_error(&mut self) -> Option<String> {
+        return self.error.take();
+    }
+    fn paint_ui(&mut self, ui: &mut egui::Ui, _buffer:&image::DynamicImage, colors: &Vec<color_item::ColorItem>) -> Option<Vec<color_item::ColorItem>> {
         let mut focused = false;
+        self.save_ui(ui, colors);
+        if let Some(dir) = &self.missing_dir {
+            ui.label("\u{f071} The presets folder does not exist.");
+            ui.label(
+                "Create a 'presets' folder next to the executable and drop .json palettes in it.",
+            );
+            ui.label(egui::RichText::new(dir.display().to_string()).weak());
+            if ui.button("\u{ea80} Create Folder").clicked() {
+                match std::fs::create_dir_all(dir) {
+                    Ok(_) => *self = Self::new(),
+                    Err(e) => self.msg = Some(e.to_string()),
+                }
+            }
+            if let Some(msg) = &self.msg {
+                ui.label(format!("Error: {}", msg));
+            }
+            return None;
+        }
         if self.colorlist.is_empty() {
             ui.label("No preset color found.");
             if let Some(msg) = &self.msg {
                 ui.label(format!("Error: {}", msg));
+            } else if let Ok(dir) = Self::preset_dir() {
+                ui.label(format!("Drop .json palettes into {}", dir.display()));
+            }
+            if ui.button("\u{f0450} Reload").clicked() {
+                *self = Self::new();
             }
             return None;
         }
@@ -209,7 +386,42 @@ impl super::AddColor for Preset {
                 self.selected_index = 0;
                 focused = true;
             }
+            if ui
+                .checkbox(&mut self.use_regex, "Regex")
+                .on_hover_text("off: fuzzy matching, \"drkbl\" finds \"dark blue\"")
+                .changed()
+            {
+                self.selected_index = 0;
+                focused = true;
+            }
+            if ui
+                .add_enabled(
+                    self.use_regex,
+                    egui::Checkbox::new(&mut self.case_insensitive, "Ignore Case"),
+                )
+                .changed()
+            {
+                self.selected_index = 0;
+                focused = true;
+            }
+            if ui
+                .checkbox(&mut self.search_all, "All Presets")
+                .on_hover_text("search the color names of every preset")
+                .changed()
+            {
+                self.selected_index = 0;
+                focused = true;
+            }
         });
+        let search = self.search();
+        if let Some((_, Err(msg))) = &self.regex {
+            if self.use_regex && !self.search_text.is_empty() {
+                // the last line says what is wrong, the rest points at where
+                let summary = msg.lines().last().unwrap_or_default();
+                ui.colored_label(egui::Color32::LIGHT_RED, format!("\u{f071} {}", summary))
+                    .on_hover_text(egui::RichText::new(msg).monospace());
+            }
+        }
         ui.separator();
         let mut colorvec = Vec::new();
         let mut cursor = ui.cursor();
@@ -227,27 +439,64 @@ impl super::AddColor for Preset {
                     let mut selected_vec = Vec::new();
                     let mut size = ui.available_size();
                     size.y = 10f32;
-                    ui.add_sized(size, egui::Label::new("\u{eb17} Colors"));
-                    for i in 0..self.colorlist[self.selected_preset].1.len() {
-                        let color = &self.colorlist[self.selected_preset].1[i];
-                        let (add, selected, resp) = Self::show_color(ui, color, &self.search_text);
-                        if add {
-                            colorvec.push(color.clone());
+                    if self.search_all {
+                        if self.search_text.is_empty() {
+                            ui.label("Type to search every preset.");
+                        }
+                        // one list over every preset, stepping through it crosses presets
+                        let matches = Self::global_matches(&self.colorlist, &search);
+                        if !self.search_text.is_empty() {
+                            let presets = self.colorlist.iter().enumerate().filter(|(i, _)| {
+                                matches.iter().any(|&(_, preset, _)| preset == *i)
+                            });
+                            ui.add_sized(
+                                size,
+                                egui::Label::new(format!(
+                                    "\u{eb17} {} match(es) in {} preset(s)",
+                                    matches.len(),
+                                    presets.count()
+                                )),
+                            );
+                        }
+                        for (_, preset, index) in matches {
+                            let (preset_name, colors) = &self.colorlist[preset];
+                            let color = &colors[index];
+                            let (add, score, resp) =
+                                Self::show_color(ui, color, &search, Some(preset_name));
+                            if add {
+                                colorvec.push(color.clone());
+                            }
+                            if let Some(score) = score {
+                                selected_vec.push((score, resp));
+                            }
                         }
-                        if selected {
-                            selected_vec.push(resp);
+                    } else {
+                        ui.add_sized(size, egui::Label::new("\u{eb17} Colors"));
+                        for i in 0..self.colorlist[self.selected_preset].1.len() {
+                            let color = &self.colorlist[self.selected_preset].1[i];
+                            let (add, score, resp) = Self::show_color(ui, color, &search, None);
+                            if add {
+                                colorvec.push(color.clone());
+                            }
+                            if let Some(score) = score {
+                                selected_vec.push((score, resp));
+                            }
                         }
+                        // stepping through results visits the best matches first
+                        selected_vec.sort_by(|a, b| b.0.cmp(&a.0));
                     }
                     if !selected_vec.is_empty() {
                         if self.selected_index >= selected_vec.len() {
                             self.selected_index = selected_vec.len() - 1;
                             if focused {
                                 selected_vec[selected_vec.len() - 1]
+                                    .1
                                     .scroll_to_me(Some(egui::Align::Center));
                             }
                         } else {
                             if focused {
                                 selected_vec[self.selected_index]
+                                    .1
                                     .scroll_to_me(Some(egui::Align::Center));
                             }
                         }
@@ -262,3 +511,68 @@ impl super::AddColor for Preset {
         return Some(colorvec);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_file_name() {
+        assert_eq!(preset_file_name(" Sunset "), Some("Sunset.json".into()));
+        assert_eq!(preset_file_name("a b-c"), Some("a b-c.json".into()));
+        assert_eq!(preset_file_name("   "), None);
+        assert_eq!(preset_file_name("../evil"), None);
+        assert_eq!(preset_file_name("dir/name"), None);
+        assert_eq!(preset_file_name("c:name"), None);
+        assert_eq!(preset_file_name(".hidden"), None);
+    }
+
+    #[test]
+    fn test_global_matches() {
+        let colorlist = vec![
+            (
+                "warm".to_string(),
+                vec![
+                    color_item::ColorItem::new("dark red", 139, 0, 0),
+                    color_item::ColorItem::new("orange", 255, 165, 0),
+                ],
+            ),
+            (
+                "cool".to_string(),
+                vec![
+                    color_item::ColorItem::new("red violet", 199, 21, 133),
+                    color_item::ColorItem::new("red", 255, 0, 0),
+                ],
+            ),
+        ];
+        let search = Search::Regex(regex::Regex::new("red").unwrap());
+        // equal scores keep the preset order
+        let matches = Preset::global_matches(&colorlist, &search);
+        assert_eq!(matches, vec![(0, 0, 0), (0, 1, 0), (0, 1, 1)]);
+        // names starting with the word rank above the earlier preset
+        let search = Search::Fuzzy("red".into());
+        let matches = Preset::global_matches(&colorlist, &search);
+        let found: Vec<(usize, usize)> = matches.iter().map(|&(_, p, i)| (p, i)).collect();
+        assert_eq!(found, vec![(1, 0), (1, 1), (0, 0)]);
+        assert!(Preset::global_matches(&colorlist, &Search::Empty).is_empty());
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let mut preset = Preset::new();
+        preset.use_regex = true;
+        preset.search_text = "^dark".into();
+        let search = preset.search();
+        assert!(Preset::find_match("Dark Blue", &search).is_some());
+        preset.case_insensitive = false;
+        let search = preset.search();
+        assert!(Preset::find_match("Dark Blue", &search).is_none());
+        assert_eq!(Preset::find_match("darkred", &search).unwrap().1, vec![0..4]);
+        assert_eq!(preset.regex.as_ref().map(|(pattern, _)| pattern.as_str()), Some("^dark"));
+        preset.search_text = "(".into();
+        let search = preset.search();
+        assert!(matches!(search, Search::Invalid));
+        assert!(Preset::find_match("(", &search).is_none());
+        assert!(matches!(preset.regex, Some((_, Err(_)))));
+    }
+}
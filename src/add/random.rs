@@ -0,0 +1,185 @@
+use crate::color_item;
+use eframe::egui;
+use rand::{Rng, SeedableRng};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Analogous,
+    Complementary,
+    Triadic,
+    SplitComplementary,
+    Tetradic,
+}
+
+const SCHEMES: [Scheme; 5] = [
+    Scheme::Analogous,
+    Scheme::Complementary,
+    Scheme::Triadic,
+    Scheme::SplitComplementary,
+    Scheme::Tetradic,
+];
+
+// how far a color may wander from its harmony hue
+const HUE_JITTER: f32 = 12.0;
+
+impl Scheme {
+    fn get_name(&self) -> &'static str {
+        match self {
+            Scheme::Analogous => "Analogous",
+            Scheme::Complementary => "Complementary",
+            Scheme::Triadic => "Triadic",
+            Scheme::SplitComplementary => "Split Complementary",
+            Scheme::Tetradic => "Tetradic",
+        }
+    }
+    // hue offsets from the base hue, colors cycle through them
+    fn offsets(&self) -> &'static [f32] {
+        match self {
+            Scheme::Analogous => &[0.0, 30.0, -30.0],
+            Scheme::Complementary => &[0.0, 180.0],
+            Scheme::Triadic => &[0.0, 120.0, 240.0],
+            Scheme::SplitComplementary => &[0.0, 150.0, 210.0],
+            Scheme::Tetradic => &[0.0, 90.0, 180.0, 270.0],
+        }
+    }
+}
+
+pub struct Random {
+    count: usize,
+    seed: u64,
+    // none picks a scheme from the seed as well
+    scheme: Option<Scheme>,
+    saturation: (f32, f32),
+    value: (f32, f32),
+}
+
+impl Random {
+    pub fn new() -> Self {
+        Self {
+            count: 5,
+            seed: rand::thread_rng().gen_range(0..1_000_000),
+            scheme: None,
+            saturation: (0.4, 0.9),
+            value: (0.5, 0.95),
+        }
+    }
+    fn generate(&self) -> Vec<color_item::ColorItem> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(self.seed);
+        let scheme = match self.scheme {
+            Some(scheme) => scheme,
+            None => SCHEMES[rng.gen_range(0..SCHEMES.len())],
+        };
+        let base = rng.gen_range(0.0..360.0);
+        let offsets = scheme.offsets();
+        let range = |rng: &mut rand::rngs::StdRng, (min, max): (f32, f32)| {
+            if min >= max {
+                return min;
+            }
+            return rng.gen_range(min..=max);
+        };
+        let mut colors = Vec::new();
+        for i in 0..self.count {
+            let jitter = rng.gen_range(-HUE_JITTER..=HUE_JITTER);
+            let hue = (base + offsets[i % offsets.len()] + jitter).rem_euclid(360.0);
+            let s = range(&mut rng, self.saturation);
+            let v = range(&mut rng, self.value);
+            colors.push(color_item::ColorItem::from_hsv(
+                hue,
+                s,
+                v,
+                &format!("random-{}", i + 1),
+            ));
+        }
+        return colors;
+    }
+    fn range_edit(ui: &mut egui::Ui, label: &str, range: &mut (f32, f32)) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(
+                egui::DragValue::new(&mut range.0)
+                    .range(0f32..=1f32)
+                    .speed(0.01)
+                    .fixed_decimals(2),
+            );
+            ui.label("-");
+            ui.add(
+                egui::DragValue::new(&mut range.1)
+                    .range(0f32..=1f32)
+                    .speed(0.01)
+                    .fixed_decimals(2),
+            );
+        });
+        if range.0 > range.1 {
+            range.1 = range.0;
+        }
+    }
+}
+
+impl super::AddColor for Random {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Random Palette".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _buffer: &image::DynamicImage,
+        _colors: &Vec<color_item::ColorItem>,
+    ) -> Option<Vec<color_item::ColorItem>> {
+        let mut ret = None;
+        ui.horizontal(|ui| {
+            ui.label("\u{eb04} Count:");
+            ui.add(
+                egui::DragValue::new(&mut self.count)
+                    .speed(0.2)
+                    .range(1..=64),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0e96} Seed:");
+            ui.add(egui::DragValue::new(&mut self.seed).speed(1.0));
+            if ui.button("\u{f049d}").on_hover_text("random seed").clicked() {
+                self.seed = rand::thread_rng().gen_range(0..1_000_000);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{e9d9} Scheme:");
+            egui::ComboBox::from_id_salt("random_scheme")
+                .selected_text(self.scheme.map_or("Random", |s| s.get_name()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.scheme, None, "Random");
+                    for scheme in SCHEMES {
+                        ui.selectable_value(&mut self.scheme, Some(scheme), scheme.get_name());
+                    }
+                });
+        });
+        Self::range_edit(ui, "\u{f0e80} Saturation:", &mut self.saturation);
+        Self::range_edit(ui, "\u{f00e0} Value:", &mut self.value);
+        ui.separator();
+        // same seed, same palette: the preview is exactly what gets added
+        let colors = self.generate();
+        ui.horizontal_wrapped(|ui| {
+            for color in colors.iter() {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(24f32, 24f32), egui::Sense::hover());
+                color_item::paint_swatch(
+                    ui.painter(),
+                    rect,
+                    color_item::swatch_rounding().max(2f32),
+                    color.to_color32(),
+                    egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+                );
+                response.on_hover_text(color.get_hex());
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("\u{ea60} Add").clicked() {
+                ret = Some(colors.clone());
+            }
+            if ui.button("\u{f049d} Add & Reroll").clicked() {
+                ret = Some(colors.clone());
+                self.seed = rand::thread_rng().gen_range(0..1_000_000);
+            }
+        });
+        return ret;
+    }
+}
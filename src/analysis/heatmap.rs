@@ -0,0 +1,119 @@
+use eframe::egui;
+
+use crate::color_item;
+use crate::utils::auto_palette::color_struct::Color;
+use crate::utils::auto_palette::delta_e::DeltaE;
+use crate::utils::auto_palette::rgb::RGB;
+
+const CELL_SIZE: f32 = 18f32;
+// distances at or above this are drawn at full brightness
+const MAX_SHADE_DELTA_E: f64 = 50.0;
+// same just-noticeable threshold the palette extraction merges with
+pub const NEAR_DUPLICATE_DELTA_E: f64 = 2.3;
+
+/// pairwise CIEDE2000 distances, symmetric with a zero diagonal
+pub fn delta_e_matrix(colors: &[color_item::ColorItem]) -> Vec<Vec<f64>> {
+    let labs: Vec<Color<f64>> = colors
+        .iter()
+        .map(|c| Color::from(&RGB::new(c.r, c.g, c.b)))
+        .collect();
+    let mut matrix = vec![vec![0f64; labs.len()]; labs.len()];
+    for i in 0..labs.len() {
+        for j in (i + 1)..labs.len() {
+            let d = labs[i].difference(&labs[j], &DeltaE::CIE2000);
+            matrix[i][j] = d;
+            matrix[j][i] = d;
+        }
+    }
+    return matrix;
+}
+
+fn shade(delta_e: f64) -> egui::Color32 {
+    let t = (delta_e / MAX_SHADE_DELTA_E).min(1.0);
+    let v = (t * 255.0) as u8;
+    return egui::Color32::from_rgb(v, v, v);
+}
+
+/// `matrix` is the `delta_e_matrix` of `colors`
+pub fn draw_heatmap(ui: &mut egui::Ui, colors: &[color_item::ColorItem], matrix: &[Vec<f64>]) {
+    if colors.len() < 2 {
+        ui.label("\u{f08a4} Need at least 2 colors.");
+        return;
+    }
+    let n = colors.len();
+    egui::ScrollArea::both()
+        .id_salt("delta_e_heatmap")
+        .show(ui, |ui| {
+            // one extra row and column hold the swatch labels
+            let side = CELL_SIZE * (n + 1) as f32;
+            let (response, painter) =
+                ui.allocate_painter(egui::vec2(side, side), egui::Sense::hover());
+            let origin = response.rect.min;
+            let cell = |row: usize, col: usize| {
+                egui::Rect::from_min_size(
+                    origin + egui::vec2(col as f32 * CELL_SIZE, row as f32 * CELL_SIZE),
+                    egui::vec2(CELL_SIZE, CELL_SIZE),
+                )
+            };
+            // only the rows and columns scrolled into view are painted
+            let visible = painter.clip_rect();
+            let span = |min: f32, max: f32, start: f32| {
+                let first = ((min - start) / CELL_SIZE).floor().max(0f32) as usize;
+                let last = ((max - start) / CELL_SIZE).ceil().max(0f32) as usize;
+                return first.max(1)..last.min(n + 1);
+            };
+            let rows = span(visible.min.y, visible.max.y, origin.y);
+            let cols = span(visible.min.x, visible.max.x, origin.x);
+            for col in cols.clone() {
+                painter.rect_filled(
+                    cell(0, col).shrink(1f32),
+                    2f32,
+                    colors[col - 1].to_color32(),
+                );
+            }
+            for row in rows.clone() {
+                painter.rect_filled(
+                    cell(row, 0).shrink(1f32),
+                    2f32,
+                    colors[row - 1].to_color32(),
+                );
+            }
+            for row in rows {
+                for col in cols.clone() {
+                    let (i, j) = (row - 1, col - 1);
+                    let rect = cell(row, col);
+                    painter.rect_filled(rect, 0f32, shade(matrix[i][j]));
+                    if i != j && matrix[i][j] < NEAR_DUPLICATE_DELTA_E {
+                        painter.rect_stroke(
+                            rect.shrink(1f32),
+                            0f32,
+                            egui::Stroke::new(1.5f32, egui::Color32::RED),
+                        );
+                    }
+                }
+            }
+            if let Some(pos) = response.hover_pos() {
+                let col = ((pos.x - origin.x) / CELL_SIZE) as usize;
+                let row = ((pos.y - origin.y) / CELL_SIZE) as usize;
+                if row >= 1 && col >= 1 && row <= n && col <= n {
+                    let (a, b) = (&colors[row - 1], &colors[col - 1]);
+                    painter.rect_stroke(
+                        cell(row, col),
+                        0f32,
+                        egui::Stroke::new(1.5f32, egui::Color32::YELLOW),
+                    );
+                    response.on_hover_ui_at_pointer(|ui| {
+                        ui.label(format!("{} / {}", a.name, b.name));
+                        ui.label(format!("\u{0394}E {:.2}", matrix[row - 1][col - 1]));
+                    });
+                }
+            }
+        });
+    ui.label(
+        egui::RichText::new(format!(
+            "dark: similar, bright: distant, red outline: \u{0394}E < {}",
+            NEAR_DUPLICATE_DELTA_E
+        ))
+        .weak(),
+    );
+}
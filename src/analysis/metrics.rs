@@ -0,0 +1,162 @@
+use crate::color_item;
+use crate::utils::auto_palette::color_struct::Color;
+use crate::utils::auto_palette::delta_e::DeltaE;
+use crate::utils::auto_palette::rgb::RGB;
+use crate::utils::lch;
+
+/// mean CIEDE2000 distance over all pairs, 0 for fewer than 2 colors
+pub fn mean_delta_e(colors: &[color_item::ColorItem]) -> f64 {
+    if colors.len() < 2 {
+        return 0.0;
+    }
+    let matrix = super::heatmap::delta_e_matrix(colors);
+    let mut sum = 0.0;
+    for i in 0..colors.len() {
+        for j in (i + 1)..colors.len() {
+            sum += matrix[i][j];
+        }
+    }
+    let pairs = colors.len() * (colors.len() - 1) / 2;
+    return sum / pairs as f64;
+}
+
+/// indices of colors closer than `threshold` CIEDE2000 to an earlier color that is kept,
+/// in ascending order. alpha is ignored
+pub fn near_duplicates(colors: &[color_item::ColorItem], threshold: f64) -> Vec<usize> {
+    let mut kept: Vec<Color<f64>> = Vec::new();
+    let mut removed = Vec::new();
+    for (i, c) in colors.iter().enumerate() {
+        let color: Color<f64> = Color::from(&RGB::new(c.r, c.g, c.b));
+        if kept.iter().any(|k| k.difference(&color, &DeltaE::CIE2000) < threshold) {
+            removed.push(i);
+        } else {
+            kept.push(color);
+        }
+    }
+    return removed;
+}
+
+/// mean CIE76 distance between the pixels of two images of the same size,
+/// `None` when the sizes differ or the images are empty
+pub fn image_delta_e(original: &image::RgbImage, other: &image::RgbImage) -> Option<f64> {
+    if original.dimensions() != other.dimensions() || original.width() == 0 || original.height() == 0 {
+        return None;
+    }
+    let mut sum = 0.0;
+    for (a, b) in original.pixels().zip(other.pixels()) {
+        if a == b {
+            continue;
+        }
+        let (l1, a1, b1) = lch::rgb_to_lab(a.0[0], a.0[1], a.0[2]);
+        let (l2, a2, b2) = lch::rgb_to_lab(b.0[0], b.0[1], b.0[2]);
+        sum += ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt();
+    }
+    return Some(sum / (original.width() as f64 * original.height() as f64));
+}
+
+/// area of the convex hull the colors span in the a*b* plane
+pub fn ab_hull_area(colors: &[color_item::ColorItem]) -> f64 {
+    let points: Vec<(f64, f64)> = colors
+        .iter()
+        .map(|c| {
+            let lab = Color::<f64>::from(&RGB::new(c.r, c.g, c.b)).to_lab();
+            (lab.a, lab.b)
+        })
+        .collect();
+    return convex_hull_area(&points);
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    return (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+}
+
+// monotone chain hull, then the shoelace formula
+fn convex_hull_area(points: &[(f64, f64)]) -> f64 {
+    if points.len() < 3 {
+        return 0.0;
+    }
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    let mut hull: Vec<(f64, f64)> = Vec::new();
+    for pass in 0..2 {
+        let start = hull.len();
+        for &p in points.iter() {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // the last point of each half is the first of the other
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    let mut area = 0.0;
+    for i in 0..hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        area += a.0 * b.1 - b.0 * a.1;
+    }
+    return area.abs() / 2.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(r: u8, g: u8, b: u8) -> color_item::ColorItem {
+        return color_item::ColorItem::new("", r, g, b);
+    }
+
+    #[test]
+    fn test_mean_delta_e() {
+        assert_eq!(mean_delta_e(&[]), 0.0);
+        assert_eq!(mean_delta_e(&[item(10, 20, 30)]), 0.0);
+        assert!(mean_delta_e(&[item(10, 20, 30), item(10, 20, 30)]).abs() < 1e-9);
+        assert!(mean_delta_e(&[item(0, 0, 0), item(255, 255, 255)]) > 90.0);
+    }
+
+    #[test]
+    fn test_near_duplicates() {
+        // the second is a ∆E well under 1 from the first, the third is far from both
+        let colors = [item(200, 40, 40), item(201, 40, 40), item(40, 40, 200)];
+        assert_eq!(near_duplicates(&colors, 2.3), vec![1]);
+        assert!(near_duplicates(&colors, 0.1).is_empty());
+        assert_eq!(near_duplicates(&colors, 1000.0), vec![1, 2]);
+        assert!(near_duplicates(&[], 2.3).is_empty());
+    }
+
+    #[test]
+    fn test_image_delta_e() {
+        let gray = image::RgbImage::from_pixel(2, 2, image::Rgb([128, 128, 128]));
+        assert_eq!(image_delta_e(&gray, &gray), Some(0.0));
+        assert_eq!(image_delta_e(&gray, &image::RgbImage::new(2, 1)), None);
+        assert_eq!(image_delta_e(&image::RgbImage::new(0, 0), &image::RgbImage::new(0, 0)), None);
+        // one of four pixels off by black to white averages to a quarter of L* 100
+        let mut changed = image::RgbImage::from_pixel(2, 2, image::Rgb([0, 0, 0]));
+        let black = changed.clone();
+        changed.put_pixel(1, 1, image::Rgb([255, 255, 255]));
+        let mean = image_delta_e(&black, &changed).unwrap();
+        assert!((mean - 25.0).abs() < 0.1, "got {}", mean);
+    }
+
+    #[test]
+    fn test_convex_hull_area() {
+        // interior and duplicate points do not change the hull
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0), (0.0, 0.0)];
+        assert!((convex_hull_area(&square) - 4.0).abs() < 1e-9);
+        assert_eq!(convex_hull_area(&[(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]), 0.0);
+        assert_eq!(convex_hull_area(&[(0.0, 0.0), (1.0, 1.0)]), 0.0);
+    }
+
+    #[test]
+    fn test_ab_hull_area() {
+        // grays sit on the neutral axis, primaries span a real area
+        let grays = [item(0, 0, 0), item(128, 128, 128), item(255, 255, 255)];
+        assert!(ab_hull_area(&grays) < 1.0);
+        let primaries = [item(255, 0, 0), item(0, 255, 0), item(0, 0, 255)];
+        assert!(ab_hull_area(&primaries) > 1000.0);
+    }
+}
@@ -0,0 +1,5 @@
+// read-only views of the color list, heavy ones are cached by the caller until the colors change
+
+pub mod heatmap;
+pub mod metrics;
+pub mod scatter;
@@ -0,0 +1,65 @@
+use eframe::egui;
+
+use crate::color_item;
+
+const MARGIN: f32 = 20f32;
+const POINT_RADIUS: f32 = 6f32;
+const HOVER_DISTANCE: f32 = 10f32;
+
+// position of a color on the wheel, hue is the angle and saturation the radius
+fn polar_pos(center: egui::Pos2, radius: f32, color: &color_item::ColorItem) -> egui::Pos2 {
+    let angle = color.get_h().to_radians();
+    let r = radius * color.get_s();
+    // screen y grows downwards, flip it so hue runs counterclockwise
+    return center + egui::vec2(angle.cos() * r, -angle.sin() * r);
+}
+
+pub fn draw_scatter(ui: &mut egui::Ui, colors: &Vec<color_item::ColorItem>) {
+    let size = ui.available_size();
+    let side = (size.x.min(size.y) - MARGIN).max(MARGIN);
+    let (response, painter) = ui.allocate_painter(egui::vec2(side, side), egui::Sense::hover());
+    let center = response.rect.center();
+    let radius = side / 2f32 - POINT_RADIUS;
+    let grid = egui::Stroke::new(1f32, ui.visuals().weak_text_color());
+    painter.circle_stroke(center, radius, grid);
+    for ring in [0.25f32, 0.5, 0.75] {
+        painter.circle_stroke(center, radius * ring, egui::Stroke::new(0.5f32, grid.color));
+    }
+    // hue markers on the rim
+    for step in 0..12 {
+        let hue = step as f32 * 30f32;
+        let marker = color_item::ColorItem::from_hsv(hue, 1f32, 1f32, "");
+        let pos = polar_pos(center, radius, &marker);
+        painter.line_segment([center, pos], egui::Stroke::new(0.5f32, grid.color));
+        painter.circle_filled(pos, 3f32, marker.to_color32());
+    }
+    let hover_pos = response.hover_pos();
+    let mut hovered: Option<(usize, f32)> = None;
+    for (i, color) in colors.iter().enumerate() {
+        let pos = polar_pos(center, radius, color);
+        painter.circle(
+            pos,
+            POINT_RADIUS,
+            color.to_color32(),
+            egui::Stroke::new(1f32, egui::Color32::WHITE),
+        );
+        if let Some(hover_pos) = hover_pos {
+            let distance = pos.distance(hover_pos);
+            if distance < HOVER_DISTANCE && hovered.is_none_or(|(_, d)| distance < d) {
+                hovered = Some((i, distance));
+            }
+        }
+    }
+    if let Some((i, _)) = hovered {
+        let color = &colors[i];
+        painter.circle_stroke(
+            polar_pos(center, radius, color),
+            POINT_RADIUS + 2f32,
+            egui::Stroke::new(2f32, egui::Color32::YELLOW),
+        );
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.label(&color.name);
+            ui.label(egui::RichText::new(color.get_hex()).color(color.get_full_value_color32()));
+        });
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use crate::add::BatchSettings;
+
+/// palettes of every image in a folder, extracted on worker threads
+pub struct Batch {
+    total: usize,
+    // files that are not images, left alone
+    pub skipped: usize,
+    pub written: usize,
+    pub failures: Vec<(PathBuf, String)>,
+    rx: mpsc::Receiver<(PathBuf, Result<PathBuf, String>)>,
+    cancel: Arc<AtomicBool>,
+}
+
+/// the files in `dir` that image can decode by their extension, sorted, and how many others there are
+pub fn image_files(dir: &Path) -> Result<(Vec<PathBuf>, usize), String> {
+    let mut images = Vec::new();
+    let mut others = 0;
+    for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if image::ImageFormat::from_path(&path).is_ok() {
+            images.push(path);
+        } else {
+            others += 1;
+        }
+    }
+    images.sort();
+    return Ok((images, others));
+}
+
+/// where the palette of an image goes, `photo.jpg` to `photo.jpg.json` beside it,
+/// keeping the extension so `photo.jpg` and `photo.png` do not share one
+pub fn palette_path(image: &Path) -> PathBuf {
+    let mut path = image.as_os_str().to_os_string();
+    path.push(".json");
+    return PathBuf::from(path);
+}
+
+// decode, extract and write one image
+fn extract_file(
+    path: &Path,
+    settings: &BatchSettings,
+    cancel: &AtomicBool,
+) -> Result<PathBuf, String> {
+    let out = palette_path(path);
+    // never overwrite, the file may be a palette saved by hand or one of an earlier run
+    if out.exists() {
+        return Err(format!("{} already exists", out.display()));
+    }
+    let img = image::open(path).map_err(|e| e.to_string())?;
+    let basename = path
+        .file_stem()
+        .map_or("image".into(), |s| s.to_string_lossy());
+    let colors = settings.extract(img, &basename, cancel);
+    if cancel.load(Ordering::Relaxed) {
+        return Err("Cancelled".into());
+    }
+    let json = serde_json::to_string(&colors).map_err(|e| e.to_string())?;
+    // create_new, something may have appeared there during the extraction
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&out)
+        .map_err(|e| format!("{}: {}", out.display(), e))?;
+    std::io::Write::write_all(&mut file, json.as_bytes()).map_err(|e| e.to_string())?;
+    return Ok(out);
+}
+
+impl Batch {
+    /// start extracting every image in `dir`, one worker per core
+    pub fn start(dir: &Path, settings: BatchSettings) -> Result<Self, String> {
+        let (images, skipped) = image_files(dir)?;
+        let total = images.len();
+        let queue = Arc::new(Mutex::new(VecDeque::from(images)));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+        let workers = thread::available_parallelism()
+            .map_or(4, |n| n.get())
+            .min(total.max(1));
+        for _ in 0..workers {
+            let queue = queue.clone();
+            let cancel = cancel.clone();
+            let settings = settings.clone();
+            let tx = tx.clone();
+            thread::spawn(move || loop {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                // a worker that panicked mid pop left the queue as it was
+                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                let Some(path) = next else {
+                    return;
+                };
+                let result = extract_file(&path, &settings, &cancel);
+                if tx.send((path, result)).is_err() {
+                    return;
+                }
+            });
+        }
+        return Ok(Self {
+            total,
+            skipped,
+            written: 0,
+            failures: Vec::new(),
+            rx,
+            cancel,
+        });
+    }
+    /// take the results that arrived since the last call
+    pub fn poll(&mut self) {
+        while let Ok((path, result)) = self.rx.try_recv() {
+            match result {
+                Ok(_) => self.written += 1,
+                Err(e) => self.failures.push((path, e)),
+            }
+        }
+    }
+    pub fn done(&self) -> usize {
+        return self.written + self.failures.len();
+    }
+    pub fn total(&self) -> usize {
+        return self.total;
+    }
+    /// every image has a result, or the run was cancelled
+    pub fn is_finished(&self) -> bool {
+        return self.done() >= self.total || self.cancel.load(Ordering::Relaxed);
+    }
+    /// stop after the images that are being extracted
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    /// one line on how the run went
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "Batch extract: {} palette(s) written, {} failed",
+            self.written,
+            self.failures.len()
+        );
+        if self.skipped > 0 {
+            text.push_str(&format!(", {} non-image file(s) skipped", self.skipped));
+        }
+        if self.done() < self.total {
+            text.push_str(&format!(", {} not reached", self.total - self.done()));
+        }
+        return text;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch() {
+        let dir = std::env::temp_dir().join(format!("colorlook-test-{}-batch", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let teal = image::RgbImage::from_pixel(16, 16, image::Rgb([0, 128, 128]));
+        teal.save(dir.join("teal.png")).unwrap();
+        std::fs::write(dir.join("broken.png"), b"not a png").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"hello").unwrap();
+
+        let (images, others) = image_files(&dir).unwrap();
+        assert_eq!(images, vec![dir.join("broken.png"), dir.join("teal.png")]);
+        assert_eq!(others, 1);
+        assert_eq!(
+            palette_path(&dir.join("teal.png")),
+            dir.join("teal.png.json")
+        );
+
+        let mut batch = Batch::start(&dir, BatchSettings::new()).unwrap();
+        for _ in 0..600 {
+            batch.poll();
+            if batch.is_finished() {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(50));
+        }
+        let json = std::fs::read_to_string(dir.join("teal.png.json"));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!((batch.done(), batch.total()), (2, 2));
+        assert_eq!(batch.written, 1);
+        assert_eq!(batch.failures.len(), 1);
+        assert_eq!(batch.failures[0].0, dir.join("broken.png"));
+        assert!(batch.summary().contains("1 non-image file(s) skipped"));
+        let colors: Vec<crate::color_item::ColorItem> =
+            serde_json::from_str(&json.unwrap()).unwrap();
+        assert!(!colors.is_empty());
+        assert!(colors
+            .iter()
+            .all(|c| c.get_hex() == "#008080" && c.name.starts_with("teal")));
+    }
+
+    #[test]
+    fn test_batch_same_stem() {
+        let dir =
+            std::env::temp_dir().join(format!("colorlook-test-{}-batch-stem", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        image::RgbImage::from_pixel(16, 16, image::Rgb([0, 128, 128]))
+            .save(dir.join("photo.png"))
+            .unwrap();
+        image::RgbImage::from_pixel(16, 16, image::Rgb([255, 0, 0]))
+            .save(dir.join("photo.bmp"))
+            .unwrap();
+        // a palette already there is left alone and reported
+        image::RgbImage::from_pixel(16, 16, image::Rgb([0, 0, 255]))
+            .save(dir.join("kept.png"))
+            .unwrap();
+        std::fs::write(dir.join("kept.png.json"), b"[]").unwrap();
+
+        let mut batch = Batch::start(&dir, BatchSettings::new()).unwrap();
+        for _ in 0..600 {
+            batch.poll();
+            if batch.is_finished() {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(50));
+        }
+        let read = |name: &str| {
+            let json = std::fs::read_to_string(dir.join(name)).unwrap();
+            return serde_json::from_str::<Vec<crate::color_item::ColorItem>>(&json).unwrap();
+        };
+        let (png, bmp, kept) = (
+            read("photo.png.json"),
+            read("photo.bmp.json"),
+            read("kept.png.json"),
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!((batch.done(), batch.total()), (3, 3));
+        assert_eq!(batch.written, 2);
+        assert_eq!(batch.failures.len(), 1);
+        assert_eq!(batch.failures[0].0, dir.join("kept.png"));
+        assert!(!png.is_empty() && png.iter().all(|c| c.get_hex() == "#008080"));
+        assert!(!bmp.is_empty() && bmp.iter().all(|c| c.get_hex() == "#ff0000"));
+        assert!(kept.is_empty());
+    }
+}
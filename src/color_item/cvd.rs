@@ -0,0 +1,98 @@
+// color vision deficiency simulation after Viénot, Brettel and Mollon (1999).
+// the lost cone response is replaced by a projection onto the plane of colors
+// a dichromat still tells apart, done in LMS space on linear sRGB.
+
+use super::ColorItem;
+use crate::utils::lch::{from_linear, to_linear};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CvdKind {
+    // no L cones, red-blind
+    Protanopia,
+    // no M cones, green-blind
+    Deuteranopia,
+    // no S cones, blue-blind
+    Tritanopia,
+}
+
+impl CvdKind {
+    pub const ALL: [CvdKind; 3] = [CvdKind::Protanopia, CvdKind::Deuteranopia, CvdKind::Tritanopia];
+    pub fn name(&self) -> &'static str {
+        return match self {
+            CvdKind::Protanopia => "Protanopia",
+            CvdKind::Deuteranopia => "Deuteranopia",
+            CvdKind::Tritanopia => "Tritanopia",
+        };
+    }
+}
+
+// linear sRGB to Smith-Pokorny LMS, scaled so white has L = M = S = 1
+const LMS_FROM_RGB: [[f64; 3]; 3] = [
+    [0.31399022, 0.63951294, 0.04649755],
+    [0.15537241, 0.75789446, 0.08670142],
+    [0.01775239, 0.10944209, 0.87256922],
+];
+const RGB_FROM_LMS: [[f64; 3]; 3] = [
+    [5.47221206, -4.6419601, 0.16963708],
+    [-1.1252419, 2.29317094, -0.1678952],
+    [0.02980165, -0.19318073, 1.16364789],
+];
+
+fn multiply(matrix: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    return matrix.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2]);
+}
+
+/// how `color` looks to a dichromat of the given kind, alpha and name are kept
+pub fn simulate_cvd(color: &ColorItem, kind: CvdKind) -> ColorItem {
+    let rgb = [color.r, color.g, color.b].map(|c| to_linear(c as f64 / 255.0));
+    let [l, m, s] = multiply(&LMS_FROM_RGB, rgb);
+    // the missing response from the two that remain, keeps black, white and grays
+    let lms = match kind {
+        CvdKind::Protanopia => [1.05118294 * m - 0.05116099 * s, m, s],
+        CvdKind::Deuteranopia => [l, 0.9513092 * l + 0.04866992 * s, s],
+        CvdKind::Tritanopia => [l, m, -0.86744736 * l + 1.86727089 * m],
+    };
+    let [r, g, b] = multiply(&RGB_FROM_LMS, lms)
+        .map(|c| (from_linear(c.clamp(0.0, 1.0)) * 255.0).round() as u8);
+    return ColorItem::new(&color.name, r, g, b).with_alpha(color.a);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protanopia_red() {
+        // pure red loses most of its brightness and turns a dark olive yellow
+        let red = simulate_cvd(&ColorItem::new("red", 255, 0, 0), CvdKind::Protanopia);
+        assert!((red.r as i16 - red.g as i16).abs() <= 4);
+        assert!(red.b < 16);
+        assert!(red.r > 80 && red.r < 160);
+        assert_eq!(red.name, "red");
+    }
+
+    #[test]
+    fn test_deuteranopia_red_green() {
+        // red and green both become yellowish and much harder to tell apart
+        let red = simulate_cvd(&ColorItem::new("", 255, 0, 0), CvdKind::Deuteranopia);
+        let green = simulate_cvd(&ColorItem::new("", 0, 255, 0), CvdKind::Deuteranopia);
+        for color in [&red, &green] {
+            assert!(color.r > color.b && color.g > color.b);
+        }
+        assert!(red.get_lab_hue() < 120.0 && green.get_lab_hue() < 120.0);
+    }
+
+    #[test]
+    fn test_grays_unchanged() {
+        for kind in CvdKind::ALL {
+            for gray in [0u8, 60, 128, 200, 255] {
+                let color = ColorItem::new("", gray, gray, gray).with_alpha(90);
+                let simulated = simulate_cvd(&color, kind);
+                for channel in [simulated.r, simulated.g, simulated.b] {
+                    assert!((channel as i16 - gray as i16).abs() <= 1, "{:?} {}", kind, gray);
+                }
+                assert_eq!(simulated.a, 90);
+            }
+        }
+    }
+}
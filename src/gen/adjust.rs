@@ -0,0 +1,165 @@
+use crate::color_item;
+use eframe::egui;
+
+const CURVE_SIZE: f32 = 128f32;
+
+#[derive(Clone, Copy, PartialEq)]
+struct ToneParams {
+    brightness: f32,
+    contrast: f32,
+    gamma: f32,
+}
+
+impl Default for ToneParams {
+    fn default() -> Self {
+        Self {
+            brightness: 0f32,
+            contrast: 1f32,
+            gamma: 1f32,
+        }
+    }
+}
+
+/// output of a channel value after gamma, then contrast around the middle, then brightness
+fn tone_curve(input: u8, params: &ToneParams) -> u8 {
+    let mut x = input as f32 / 255f32;
+    x = x.powf(1f32 / params.gamma.max(0.01));
+    x = (x - 0.5) * params.contrast + 0.5;
+    x += params.brightness;
+    return (x.clamp(0f32, 1f32) * 255f32).round() as u8;
+}
+
+/// the curve sampled at every input value
+fn tone_lut(params: &ToneParams) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (i, value) in lut.iter_mut().enumerate() {
+        *value = tone_curve(i as u8, params);
+    }
+    return lut;
+}
+
+pub struct Adjust {
+    params: ToneParams,
+}
+
+impl Adjust {
+    pub fn new() -> Self {
+        Self {
+            params: ToneParams::default(),
+        }
+    }
+    // input -> output plot of the current settings, the diagonal is no change
+    fn curve_preview(ui: &mut egui::Ui, lut: &[u8; 256]) {
+        egui::Frame::canvas(ui.style()).show(ui, |ui| {
+            let (rect, _) =
+                ui.allocate_exact_size(egui::Vec2::splat(CURVE_SIZE), egui::Sense::hover());
+            let painter = ui.painter();
+            let to_screen = |input: f32, output: f32| {
+                egui::pos2(
+                    rect.left() + input / 255f32 * rect.width(),
+                    rect.bottom() - output / 255f32 * rect.height(),
+                )
+            };
+            painter.line_segment(
+                [to_screen(0f32, 0f32), to_screen(255f32, 255f32)],
+                egui::Stroke::new(1f32, egui::Color32::DARK_GRAY),
+            );
+            let points = lut
+                .iter()
+                .enumerate()
+                .map(|(i, value)| to_screen(i as f32, *value as f32))
+                .collect();
+            painter.add(egui::Shape::line(
+                points,
+                egui::Stroke::new(1.5f32, egui::Color32::WHITE),
+            ));
+        });
+    }
+}
+
+impl super::Generate for Adjust {
+    fn get_name(&self) -> String {
+        return "\u{f00e0} Adjust".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _colors: &Vec<color_item::ColorItem>,
+        buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        ui.label("Adjust the tones of the current image.");
+        let lut = tone_lut(&self.params);
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("\u{f00e0} Brightness:");
+                    ui.add(egui::Slider::new(&mut self.params.brightness, -1f32..=1f32));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("\u{f0ad5} Contrast:");
+                    ui.add(egui::Slider::new(&mut self.params.contrast, 0f32..=3f32));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("\u{f0d1e} Gamma:");
+                    ui.add(
+                        egui::Slider::new(&mut self.params.gamma, 0.1f32..=5f32)
+                            .logarithmic(true),
+                    );
+                });
+                if ui.button("\u{f0450} Reset").clicked() {
+                    self.params = ToneParams::default();
+                }
+            });
+            Self::curve_preview(ui, &lut);
+        });
+        if ui.button("\u{f0674} Apply").clicked() {
+            let mut img = buffer.to_rgba8();
+            for pixel in img.pixels_mut() {
+                for channel in pixel.0.iter_mut().take(3) {
+                    *channel = lut[*channel as usize];
+                }
+            }
+            return Some(image::DynamicImage::ImageRgba8(img));
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_curve_identity() {
+        let params = ToneParams::default();
+        for i in 0..=255u8 {
+            assert_eq!(tone_curve(i, &params), i);
+        }
+    }
+
+    #[test]
+    fn test_tone_curve() {
+        // gamma above 1 lifts the midtones and keeps the ends
+        let gamma = ToneParams {
+            gamma: 2.2,
+            ..Default::default()
+        };
+        assert_eq!(tone_curve(0, &gamma), 0);
+        assert_eq!(tone_curve(255, &gamma), 255);
+        assert!(tone_curve(128, &gamma) > 128);
+
+        // contrast pivots around the middle, brightness shifts and clamps
+        let contrast = ToneParams {
+            contrast: 2.0,
+            ..Default::default()
+        };
+        assert!(tone_curve(64, &contrast) < 64);
+        assert!(tone_curve(192, &contrast) > 192);
+        let bright = ToneParams {
+            brightness: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(tone_curve(200, &bright), 255);
+        assert!(tone_lut(&bright).windows(2).all(|w| w[0] <= w[1]));
+    }
+}
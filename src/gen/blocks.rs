@@ -6,6 +6,9 @@ pub struct Blocks {
     height: u32,
     x_num: u32,
     y_num: u32,
+    // pixels of border between neighbouring cells
+    gap: u32,
+    border_color: color_item::ColorItem,
 }
 
 impl Blocks {
@@ -15,17 +18,50 @@ impl Blocks {
             height: 512,
             x_num: 8,
             y_num: 8,
+            gap: 0,
+            border_color: color_item::ColorItem::new("border", 0, 0, 0),
         }
     }
 }
 
 #[derive(Clone)]
 struct BlocksGenerator {
-    data: Vec<Vec<(u8,u8,u8)>>,
+    data: Vec<Vec<[u8; 4]>>,
     width: u32,
     height: u32,
     x_num: u32,
     y_num: u32,
+    gap: u32,
+    border: [u8; 4],
+}
+
+// whether a pixel is in the gap around an inner boundary of its cell,
+// the cell before a boundary takes the smaller half of the gap
+fn in_gap(pos: u32, size: u32, cells: u32, gap: u32) -> bool {
+    if gap == 0 {
+        return false;
+    }
+    let index = pos * cells / size;
+    // first pixel of a cell, the inverse of the index above
+    let start = |i: u32| (i * size + cells - 1) / cells;
+    if index > 0 && pos - start(index) < gap - gap / 2 {
+        return true;
+    }
+    return index + 1 < cells && start(index + 1) - pos <= gap / 2;
+}
+
+// pixels start..end of a cell that are not gap, the same split as `in_gap`
+fn cell_span(index: u32, size: u32, cells: u32, gap: u32) -> (u32, u32) {
+    let start = |i: u32| (i * size + cells - 1) / cells;
+    let mut from = start(index);
+    let mut to = start(index + 1).min(size);
+    if index > 0 {
+        from += gap - gap / 2;
+    }
+    if index + 1 < cells {
+        to = to.saturating_sub(gap / 2);
+    }
+    return (from, to.max(from));
 }
 
 impl BlocksGenerator {
@@ -35,6 +71,8 @@ impl BlocksGenerator {
         height: u32,
         x_num: u32,
         y_num: u32,
+        gap: u32,
+        border: &color_item::ColorItem,
     ) -> Self {
         let mut data = Vec::new();
         for i in 0..y_num {
@@ -42,8 +80,8 @@ impl BlocksGenerator {
             for j in 0..x_num {
                 let index = (i * x_num + j) as usize;
                 row.push(match colors.get(index) {
-                    Some(color) => (color.r, color.g, color.b),
-                    None => (0,0,0)
+                    Some(color) => [color.r, color.g, color.b, color.a],
+                    None => [0, 0, 0, 255]
                 });
             }
             data.push(row);
@@ -54,23 +92,70 @@ impl BlocksGenerator {
             height,
             x_num,
             y_num,
+            gap,
+            border: [border.r, border.g, border.b, border.a],
         }
     }
-    fn get_color(&self, x: u32, y: u32) -> (u8, u8, u8) {
+    fn get_color(&self, x: u32, y: u32) -> [u8; 4] {
+        if in_gap(x, self.width, self.x_num, self.gap) || in_gap(y, self.height, self.y_num, self.gap) {
+            return self.border;
+        }
         let x_index = x * self.x_num / self.width;
         let y_index = y * self.y_num / self.height;
         return self.data[y_index as usize][x_index as usize];
     }
+    // the border behind the gaps, then one rect per cell
+    fn svg(&self) -> String {
+        let mut body = String::new();
+        if self.gap > 0 {
+            body.push_str(&format!(
+                "<rect width=\"{}\" height=\"{}\" {}/>\n",
+                self.width,
+                self.height,
+                super::svg_fill(self.border)
+            ));
+        }
+        for (i, row) in self.data.iter().enumerate() {
+            let (y0, y1) = cell_span(i as u32, self.height, self.y_num, self.gap);
+            for (j, color) in row.iter().enumerate() {
+                let (x0, x1) = cell_span(j as u32, self.width, self.x_num, self.gap);
+                if x1 > x0 && y1 > y0 {
+                    body.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>\n",
+                        x0,
+                        y0,
+                        x1 - x0,
+                        y1 - y0,
+                        super::svg_fill(*color)
+                    ));
+                }
+            }
+        }
+        return super::svg_document(self.width, self.height, &body);
+    }
 }
 
 impl super::Generate for Blocks {
     fn get_name(&self) -> String {
         return "\u{eae6} Blocks".into();
     }
+    fn svg(&self, colors: &Vec<color_item::ColorItem>) -> Option<String> {
+        let gen = BlocksGenerator::new(
+            colors.clone(),
+            self.width,
+            self.height,
+            self.x_num,
+            self.y_num,
+            self.gap,
+            &self.border_color,
+        );
+        return Some(gen.svg());
+    }
     fn paint_ui(
         &mut self,
         ui: &mut egui::Ui,
         colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
     ) -> Option<image::DynamicImage> {
         ui.horizontal(|ui| {
             ui.label("\u{f019e} Width:");
@@ -100,6 +185,21 @@ impl super::Generate for Blocks {
                     .range(1..=16384),
             );
         });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0a5a} Gap:");
+            ui.add(
+                egui::DragValue::new(&mut self.gap)
+                    .speed(0.2)
+                    .range(0..=256)
+                    .suffix(" px"),
+            );
+            ui.label("Border:");
+            let border = &self.border_color;
+            let mut rgb = [border.r, border.g, border.b];
+            if egui::color_picker::color_edit_button_srgb(ui, &mut rgb).changed() {
+                self.border_color = color_item::ColorItem::new("border", rgb[0], rgb[1], rgb[2]);
+            }
+        });
         if ui.button("\u{f0674} Generate").clicked() {
             let gen = BlocksGenerator::new(
                 colors.clone(),
@@ -107,13 +207,89 @@ impl super::Generate for Blocks {
                 self.height,
                 self.x_num,
                 self.y_num,
+                self.gap,
+                &self.border_color,
             );
             let buffer = image::RgbImage::from_fn(self.width, self.height, |x,y|{
-                let color = gen.get_color(x, y);
-                image::Rgb([color.0, color.1, color.2])
+                image::Rgb(crate::utils::over_checker(x, y, gen.get_color(x, y)))
             });
             return Some(image::DynamicImage::ImageRgb8(buffer));
         }
         return None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> Vec<color_item::ColorItem> {
+        return (0..4u8)
+            .map(|i| color_item::ColorItem::new(&i.to_string(), i * 60, 255 - i * 60, 100))
+            .collect();
+    }
+
+    #[test]
+    fn test_no_gap() {
+        let border = color_item::ColorItem::new("border", 1, 2, 3);
+        let gen = BlocksGenerator::new(palette(), 30, 20, 2, 2, 0, &border);
+        // the cell a pixel falls in, as before there were gaps
+        for y in 0..20 {
+            for x in 0..30 {
+                let index = (y * 2 / 20 * 2 + x * 2 / 30) as usize;
+                let color = &palette()[index];
+                assert_eq!(gen.get_color(x, y), [color.r, color.g, color.b, 255]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gap() {
+        let border = color_item::ColorItem::new("border", 1, 2, 3);
+        let gen = BlocksGenerator::new(palette(), 30, 20, 2, 2, 4, &border);
+        let border = [1, 2, 3, 255];
+        // the boundary between the columns is at x = 15, two pixels on each side
+        assert_eq!(gen.get_color(13, 5), border);
+        assert_eq!(gen.get_color(16, 5), border);
+        assert_ne!(gen.get_color(12, 5), border);
+        assert_ne!(gen.get_color(17, 5), border);
+        // the rows meet at y = 10
+        assert_eq!(gen.get_color(5, 9), border);
+        assert_ne!(gen.get_color(5, 7), border);
+        // the outer edges have no border
+        assert_ne!(gen.get_color(0, 0), border);
+        assert_ne!(gen.get_color(29, 19), border);
+
+        // an odd gap still covers exactly that many pixels
+        let gen = BlocksGenerator::new(palette(), 30, 20, 2, 2, 3, &color_item::ColorItem::new("b", 1, 2, 3));
+        let columns = (0..30).filter(|&x| gen.get_color(x, 5) == border).count();
+        assert_eq!(columns, 3);
+    }
+
+    #[test]
+    fn test_cell_span() {
+        for (size, cells, gap) in [(30, 2, 0), (30, 2, 4), (31, 3, 3), (20, 7, 1), (8, 8, 2)] {
+            for pos in 0..size {
+                let inside = (0..cells).any(|i| {
+                    let (from, to) = cell_span(i, size, cells, gap);
+                    return (from..to).contains(&pos);
+                });
+                assert_eq!(inside, !in_gap(pos, size, cells, gap), "{} of {}/{}/{}", pos, size, cells, gap);
+            }
+        }
+    }
+
+    #[test]
+    fn test_svg() {
+        let border = color_item::ColorItem::new("border", 1, 2, 3);
+        let elements = |gap| {
+            let svg = BlocksGenerator::new(palette(), 30, 20, 2, 2, gap, &border).svg();
+            return crate::gen::tests::parse_svg(&svg).unwrap();
+        };
+        // one rect per cell, and the border behind them when there are gaps
+        assert_eq!(elements(0), vec!["svg", "rect", "rect", "rect", "rect"]);
+        assert_eq!(elements(4).len(), 6);
+        let svg = BlocksGenerator::new(palette(), 30, 20, 2, 2, 4, &border).svg();
+        assert!(svg.contains("<rect x=\"17\" y=\"0\" width=\"13\" height=\"8\" fill=\"#3cc364\"/>"));
+    }
+}
@@ -1,22 +1,26 @@
 use crate::color_item;
 use eframe::egui::{self, RichText};
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 
 
 pub struct Circle {
     positions: Vec<f32>,
+    interpolation: super::Interpolation,
     width: u32,
     height: u32,
     // manage thread
     hthread: Option<thread::JoinHandle<()>>,
     channel: Option<mpsc::Receiver<image::DynamicImage>>,
+    // set to stop the running thread
+    cancel: Arc<AtomicBool>,
     // manage drag
 }
 
 #[derive(Clone)]
 struct CircleGenerator {
-    data: Vec<(f32, color_item::ColorItem)>,
+    stops: super::Stops,
     width: u32,
     height: u32,
 }
@@ -27,14 +31,10 @@ impl CircleGenerator {
         positions: Vec<f32>,
         width: u32,
         height: u32,
+        interpolation: super::Interpolation,
     ) -> Self {
-        let mut data = Vec::new();
-        for i in 0..colors.len() {
-            data.push((positions[i], colors[i].clone()));
-        }
-        data.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
         Self {
-            data,
+            stops: super::Stops::new(colors, positions, interpolation),
             width,
             height,
         }
@@ -43,23 +43,7 @@ impl CircleGenerator {
         let dist = self.get_dist(x, y);
         let maxdist = self.get_dist_max();
         let dist_divided = dist / maxdist;
-        for i in 1..self.data.len() {
-            if dist_divided <= self.data[i].0 {
-                let color2 = self.data[i - 1].1.clone();
-                let color1 = self.data[i].1.clone();
-                let color1_divided =
-                    (dist_divided - self.data[i - 1].0) / (self.data[i].0 - self.data[i - 1].0);
-                let color2_divided = 1.0 - color1_divided;
-                return (
-                    (color1.r as f32 * color1_divided + color2.r as f32 * color2_divided) as u8,
-                    (color1.g as f32 * color1_divided + color2.g as f32 * color2_divided) as u8,
-                    (color1.b as f32 * color1_divided + color2.b as f32 * color2_divided) as u8,
-                );
-            } else {
-                continue;
-            }
-        }
-        return (0, 0, 0);
+        return self.stops.color_at(dist_divided);
     }
     fn get_dist(&self, x: u32, y: u32) -> f32 {
         let x = x as f32 - self.width as f32 / 2f32;
@@ -75,10 +59,12 @@ impl Circle {
     pub fn new() -> Self {
         Self {
             positions: Vec::new(),
+            interpolation: super::Interpolation::RGB,
             width: 512,
             height: 512,
             hthread: None,
             channel: None,
+            cancel: Arc::new(AtomicBool::new(false)),
         }
     }
     fn display_color(ui: &mut egui::Ui, position: &mut f32, color: &color_item::ColorItem) {
@@ -92,9 +78,10 @@ impl Circle {
                 },
             );
             let painter = ui.painter();
-            painter.rect(
+            color_item::paint_swatch(
+                painter,
                 rect,
-                0f32,
+                color_item::swatch_rounding(),
                 color.to_color32(),
                 egui::Stroke::new(0.5f32, egui::Color32::WHITE),
             );
@@ -115,6 +102,7 @@ impl super::Generate for Circle {
         &mut self,
         ui: &mut egui::Ui,
         colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
     ) -> Option<image::DynamicImage> {
         if colors.len() < 2 {
             ui.label("Need at least 2 colors.");
@@ -130,6 +118,7 @@ impl super::Generate for Circle {
         let positions_len = self.positions.len();
         self.positions[0] = 0.0;
         self.positions[positions_len - 1] = 1.0;
+        super::interpolation_ui(ui, &mut self.interpolation);
         ui.horizontal(|ui| {
             ui.label("\u{f019e} Width:");
             ui.add(
@@ -150,8 +139,11 @@ impl super::Generate for Circle {
                 let thread_positions = self.positions.clone();
                 let thread_width = self.width.clone();
                 let thread_height = self.height.clone();
+                let thread_interpolation = self.interpolation;
                 let (tx, rx) = mpsc::channel();
                 self.channel = Some(rx);
+                self.cancel = Arc::new(AtomicBool::new(false));
+                let cancel = self.cancel.clone();
                 self.hthread = Some(thread::spawn(move || {
                     // many colors
                     // sort first
@@ -160,17 +152,27 @@ impl super::Generate for Circle {
                         thread_positions,
                         thread_width,
                         thread_height,
+                        thread_interpolation,
                     );
 
-                    let buffer = image::RgbImage::from_fn(thread_width, thread_height, |x, y| {
-                        let (r, g, b) = gen.get_color(x, y);
-                        image::Rgb([r, g, b])
-                    });
-                    tx.send(image::DynamicImage::ImageRgb8(buffer)).unwrap();
+                    // a cancelled run drops the channel without sending
+                    if let Some(buffer) = super::render_cancellable(
+                        thread_width,
+                        thread_height,
+                        &cancel,
+                        |x, y| gen.get_color(x, y),
+                    ) {
+                        let _ = tx.send(image::DynamicImage::ImageRgb8(buffer));
+                    }
                 }));
             }
             if self.hthread.is_some() {
                 ui.spinner();
+                if ui.button("\u{f0156} Cancel").clicked() {
+                    self.cancel.store(true, Ordering::Relaxed);
+                    self.hthread = None;
+                    self.channel = None;
+                }
             }
         });
         let width = 192f32;
@@ -0,0 +1,318 @@
+use crate::color_item;
+use eframe::egui::{self, RichText};
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+pub struct Conic {
+    positions: Vec<f32>,
+    // degrees, 0 points right and angles grow clockwise
+    start: f32,
+    // center relative to the image center, fractions of width and height
+    offset: (f32, f32),
+    interpolation: super::Interpolation,
+    width: u32,
+    height: u32,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<image::DynamicImage>>,
+    // set to stop the running thread
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Clone)]
+struct ConicGenerator {
+    stops: super::Stops,
+    center: (f32, f32),
+    // radians
+    start: f32,
+}
+
+impl ConicGenerator {
+    fn new(
+        colors: Vec<color_item::ColorItem>,
+        positions: Vec<f32>,
+        start: f32,
+        offset: (f32, f32),
+        width: u32,
+        height: u32,
+        interpolation: super::Interpolation,
+    ) -> Self {
+        Self {
+            stops: super::Stops::new(colors, positions, interpolation),
+            center: (
+                width as f32 * (0.5 + offset.0),
+                height as f32 * (0.5 + offset.1),
+            ),
+            start,
+        }
+    }
+    fn get_color(&self, x: u32, y: u32) -> (u8, u8, u8) {
+        // pixel centers, so the angle does not lean toward the top-left
+        let angle = (y as f32 + 0.5 - self.center.1).atan2(x as f32 + 0.5 - self.center.0);
+        let turn = (angle - self.start).rem_euclid(TAU) / TAU;
+        return self.stops.color_at(turn);
+    }
+}
+
+impl Conic {
+    pub fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            start: 0f32,
+            offset: (0f32, 0f32),
+            interpolation: super::Interpolation::RGB,
+            width: 512,
+            height: 512,
+            hthread: None,
+            channel: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    fn display_color(ui: &mut egui::Ui, position: &mut f32, color: &color_item::ColorItem) {
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(
+                egui::vec2(5f32, ui.text_style_height(&egui::TextStyle::Body)),
+                egui::Sense {
+                    click: false,
+                    drag: false,
+                    focusable: false,
+                },
+            );
+            let painter = ui.painter();
+            color_item::paint_swatch(
+                painter,
+                rect,
+                color_item::swatch_rounding(),
+                color.to_color32(),
+                egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+            );
+            ui.add(egui::Slider::new(position, 0.0..=1.0).fixed_decimals(2));
+            ui.label(
+                RichText::new(crate::utils::resized_str(&color.name, 12))
+                    .color(color.get_full_value_color32()),
+            );
+        });
+    }
+}
+
+impl super::Generate for Conic {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Conic".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.len() < 2 {
+            ui.label("Need at least 2 colors.");
+            return None;
+        }
+        if self.positions.len() != colors.len() {
+            self.positions.clear();
+            for i in 0..colors.len() {
+                let pos = i as f32 / (colors.len() - 1) as f32;
+                self.positions.push(pos);
+            }
+        }
+        let positions_len = self.positions.len();
+        self.positions[0] = 0.0;
+        self.positions[positions_len - 1] = 1.0;
+        ui.horizontal(|ui| {
+            ui.label("\u{f0937} Start Angle:");
+            ui.add(egui::Slider::new(&mut self.start, 0.0..=360.0).suffix("°"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0453} Center:");
+            ui.add(
+                egui::DragValue::new(&mut self.offset.0)
+                    .range(-0.5..=0.5)
+                    .speed(0.005)
+                    .fixed_decimals(2)
+                    .prefix("x: "),
+            );
+            ui.add(
+                egui::DragValue::new(&mut self.offset.1)
+                    .range(-0.5..=0.5)
+                    .speed(0.005)
+                    .fixed_decimals(2)
+                    .prefix("y: "),
+            );
+            if ui.small_button("\u{f0450} Reset").clicked() {
+                self.offset = (0f32, 0f32);
+            }
+        });
+        super::interpolation_ui(ui, &mut self.interpolation);
+        ui.horizontal(|ui| {
+            ui.label("\u{f019e} Width:");
+            ui.add(
+                egui::DragValue::new(&mut self.width)
+                    .speed(1.0)
+                    .range(1..=16384),
+            );
+            ui.label("\u{f019e} Height:");
+            ui.add(
+                egui::DragValue::new(&mut self.height)
+                    .speed(1.0)
+                    .range(1..=16384),
+            );
+        });
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() {
+                let gen = ConicGenerator::new(
+                    colors.clone(),
+                    self.positions.clone(),
+                    self.start.to_radians(),
+                    self.offset,
+                    self.width,
+                    self.height,
+                    self.interpolation,
+                );
+                let (width, height) = (self.width, self.height);
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.cancel = Arc::new(AtomicBool::new(false));
+                let cancel = self.cancel.clone();
+                self.hthread = Some(thread::spawn(move || {
+                    // a cancelled run drops the channel without sending
+                    if let Some(buffer) =
+                        super::render_cancellable(width, height, &cancel, |x, y| gen.get_color(x, y))
+                    {
+                        let _ = tx.send(image::DynamicImage::ImageRgb8(buffer));
+                    }
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+                if ui.button("\u{f0156} Cancel").clicked() {
+                    self.cancel.store(true, Ordering::Relaxed);
+                    self.hthread = None;
+                    self.channel = None;
+                }
+            }
+        });
+        let width = 192f32;
+        let highlight = egui::Frame::canvas(ui.style()).show(ui, |ui| {
+            let (rect, response) =
+                ui.allocate_exact_size(egui::Vec2::splat(width), egui::Sense::hover());
+            let painter = ui.painter();
+            // the image scaled into the canvas, the center where it will be
+            let scale = width * 0.8 / self.width.max(self.height) as f32;
+            let image_rect = egui::Rect::from_center_size(
+                rect.center(),
+                egui::vec2(self.width as f32 * scale, self.height as f32 * scale),
+            );
+            let center = egui::pos2(
+                image_rect.left() + image_rect.width() * (0.5 + self.offset.0),
+                image_rect.top() + image_rect.height() * (0.5 + self.offset.1),
+            );
+            painter.rect_stroke(
+                image_rect,
+                0.0,
+                egui::Stroke::new(2f32, egui::Color32::LIGHT_GRAY),
+            );
+            let start = self.start.to_radians();
+            let ray_angle = |pos: f32| start + pos * TAU;
+            // detect selected
+            let mut highlight = None;
+            if let Some(pos) = response.hover_pos() {
+                let hover = (pos.y - center.y).atan2(pos.x - center.x);
+                for i in 0..positions_len {
+                    let diff = (hover - ray_angle(self.positions[i])).rem_euclid(TAU);
+                    if diff.min(TAU - diff) < 0.08 {
+                        highlight = Some(i);
+                        break;
+                    }
+                }
+            }
+            // a ray per stop, turning with the start angle
+            let length = width / 2f32;
+            for i in 0..colors.len() {
+                let color = &colors[i];
+                let stroke = if Some(i) == highlight {
+                    egui::Stroke::new(6f32, color.get_full_value_color32())
+                } else {
+                    egui::Stroke::new(2f32, color.to_color32())
+                };
+                let angle = ray_angle(self.positions[i]);
+                let end = center + egui::vec2(angle.cos(), angle.sin()) * length;
+                painter.line_segment([center, end], stroke);
+            }
+            painter.circle_filled(center, 3f32, egui::Color32::WHITE);
+            return highlight;
+        });
+        if let Some(hl) = highlight.inner {
+            ui.label(
+                RichText::new(crate::utils::resized_str(&colors[hl].name, 24))
+                    .color(colors[hl].get_full_value_color32()),
+            );
+        } else {
+            ui.label("None");
+        }
+
+        ui.separator();
+
+        ui.label("\u{f0835} Positions:");
+        for i in 0..colors.len() {
+            Self::display_color(ui, &mut self.positions[i], &colors[i]);
+        }
+
+        if let Some(hth) = &self.hthread {
+            if hth.is_finished() {
+                if let Some(rx) = self.channel.take() {
+                    return rx.recv().ok();
+                }
+                self.hthread = None;
+                self.channel = None;
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn black_to_white(start: f32, offset: (f32, f32)) -> ConicGenerator {
+        let black = color_item::ColorItem::new("black", 0, 0, 0);
+        let white = color_item::ColorItem::new("white", 255, 255, 255);
+        return ConicGenerator::new(
+            vec![black, white],
+            vec![0.0, 1.0],
+            start.to_radians(),
+            offset,
+            100,
+            100,
+            super::super::Interpolation::RGB,
+        );
+    }
+
+    #[test]
+    fn test_angles() {
+        let gen = black_to_white(0.0, (0.0, 0.0));
+        // right of the center is the start, then clockwise: below, left, above
+        let right = gen.get_color(99, 50).0;
+        let below = gen.get_color(50, 99).0;
+        let left = gen.get_color(0, 50).0;
+        let above = gen.get_color(50, 0).0;
+        assert!(right < 10);
+        assert!((60..=70).contains(&below));
+        assert!((122..=132).contains(&left));
+        assert!((185..=196).contains(&above));
+
+        // a quarter turn moves the start below the center
+        let gen = black_to_white(90.0, (0.0, 0.0));
+        assert!(gen.get_color(45, 99).0 < 10);
+    }
+
+    #[test]
+    fn test_offset() {
+        // with the center moved to the right edge, the whole image is on its left
+        let gen = black_to_white(0.0, (0.5, 0.0));
+        assert!((122..=132).contains(&gen.get_color(10, 50).0));
+    }
+}
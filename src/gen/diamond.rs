@@ -0,0 +1,273 @@
+use crate::color_item;
+use eframe::egui::{self, RichText};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+pub struct Diamond {
+    positions: Vec<f32>,
+    interpolation: super::Interpolation,
+    width: u32,
+    height: u32,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<image::DynamicImage>>,
+    // set to stop the running thread
+    cancel: Arc<AtomicBool>,
+    // manage drag
+}
+
+#[derive(Clone)]
+struct DiamondGenerator {
+    stops: super::Stops,
+    width: u32,
+    height: u32,
+}
+
+impl DiamondGenerator {
+    fn new(
+        colors: Vec<color_item::ColorItem>,
+        positions: Vec<f32>,
+        width: u32,
+        height: u32,
+        interpolation: super::Interpolation,
+    ) -> Self {
+        Self {
+            stops: super::Stops::new(colors, positions, interpolation),
+            width,
+            height,
+        }
+    }
+    fn get_color(&self, x: u32, y: u32) -> (u8, u8, u8) {
+        let dist = self.get_dist(x, y);
+        let maxdist = self.get_dist_max();
+        let dist_divided = dist / maxdist;
+        return self.stops.color_at(dist_divided);
+    }
+    // manhattan distance, the lines of equal distance are diamonds
+    fn get_dist(&self, x: u32, y: u32) -> f32 {
+        let x = x as f32 - self.width as f32 / 2f32;
+        let y = y as f32 - self.height as f32 / 2f32;
+        return x.abs() + y.abs();
+    }
+    fn get_dist_max(&self) -> f32 {
+        return (self.width + self.height) as f32 / 2f32;
+    }
+}
+
+impl Diamond {
+    pub fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            interpolation: super::Interpolation::RGB,
+            width: 512,
+            height: 512,
+            hthread: None,
+            channel: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    fn display_color(ui: &mut egui::Ui, position: &mut f32, color: &color_item::ColorItem) {
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(
+                egui::vec2(5f32, ui.text_style_height(&egui::TextStyle::Body)),
+                egui::Sense {
+                    click: false,
+                    drag: false,
+                    focusable: false,
+                },
+            );
+            let painter = ui.painter();
+            color_item::paint_swatch(
+                painter,
+                rect,
+                color_item::swatch_rounding(),
+                color.to_color32(),
+                egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+            );
+            ui.add(egui::Slider::new(position, 0.0..=1.0).fixed_decimals(2));
+            ui.label(
+                RichText::new(crate::utils::resized_str(&color.name, 12))
+                    .color(color.get_full_value_color32()),
+            );
+        });
+    }
+}
+
+impl super::Generate for Diamond {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Diamond".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.len() < 2 {
+            ui.label("Need at least 2 colors.");
+            return None;
+        }
+        if self.positions.len() != colors.len() {
+            self.positions.clear();
+            for i in 0..colors.len() {
+                let pos = i as f32 / (colors.len() - 1) as f32;
+                self.positions.push(pos);
+            }
+        }
+        let positions_len = self.positions.len();
+        self.positions[0] = 0.0;
+        self.positions[positions_len - 1] = 1.0;
+        super::interpolation_ui(ui, &mut self.interpolation);
+        ui.horizontal(|ui| {
+            ui.label("\u{f019e} Width:");
+            ui.add(
+                egui::DragValue::new(&mut self.width)
+                    .speed(1.0)
+                    .range(1..=16384),
+            );
+            ui.label("\u{f019e} Height:");
+            ui.add(
+                egui::DragValue::new(&mut self.height)
+                    .speed(1.0)
+                    .range(1..=16384),
+            );
+        });
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() {
+                let thread_colors = colors.clone();
+                let thread_positions = self.positions.clone();
+                let thread_width = self.width.clone();
+                let thread_height = self.height.clone();
+                let thread_interpolation = self.interpolation;
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.cancel = Arc::new(AtomicBool::new(false));
+                let cancel = self.cancel.clone();
+                self.hthread = Some(thread::spawn(move || {
+                    // many colors
+                    // sort first
+                    let gen = DiamondGenerator::new(
+                        thread_colors,
+                        thread_positions,
+                        thread_width,
+                        thread_height,
+                        thread_interpolation,
+                    );
+
+                    // a cancelled run drops the channel without sending
+                    if let Some(buffer) = super::render_cancellable(
+                        thread_width,
+                        thread_height,
+                        &cancel,
+                        |x, y| gen.get_color(x, y),
+                    ) {
+                        let _ = tx.send(image::DynamicImage::ImageRgb8(buffer));
+                    }
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+                if ui.button("\u{f0156} Cancel").clicked() {
+                    self.cancel.store(true, Ordering::Relaxed);
+                    self.hthread = None;
+                    self.channel = None;
+                }
+            }
+        });
+        let width = 192f32;
+        let highlight = egui::Frame::canvas(ui.style()).show(ui, |ui| {
+            let (rect, response) =
+                ui.allocate_exact_size(egui::Vec2::splat(width), egui::Sense::hover());
+            let center_pos = rect.center();
+            let painter = ui.painter();
+            // detect selected
+            let mut highlight = None;
+            if let Some(pos) = response.hover_pos() {
+                let pos2center = (pos.x - center_pos.x).abs() + (pos.y - center_pos.y).abs();
+                for i in 0..positions_len {
+                    if (pos2center - width * self.positions[i] / 2f32).abs() < 4f32 {
+                        highlight = Some(i);
+                        break;
+                    }
+                }
+            }
+            // show preview, the corners of a square image are the farthest points
+            let rect_pos = egui::Rect::from_center_size(center_pos, egui::Vec2::splat(width / 2f32));
+            let rect_stroke = egui::Stroke::new(2f32, egui::Color32::LIGHT_GRAY);
+            painter.rect_stroke(rect_pos, 0.0, rect_stroke);
+            for i in 0..colors.len() {
+                let color = &colors[i];
+                let pos = self.positions[i];
+                let stroke = if Some(i) == highlight {
+                    egui::Stroke::new(6f32, color.get_full_value_color32())
+                } else {
+                    egui::Stroke::new(2f32, color.to_color32())
+                };
+                let radius = width * pos / 2f32;
+                if radius < stroke.width {
+                    painter.circle_filled(center_pos, stroke.width / 2f32, stroke.color);
+                } else {
+                    let points = vec![
+                        center_pos + egui::vec2(radius, 0f32),
+                        center_pos + egui::vec2(0f32, radius),
+                        center_pos - egui::vec2(radius, 0f32),
+                        center_pos - egui::vec2(0f32, radius),
+                    ];
+                    painter.add(egui::Shape::closed_line(points, stroke));
+                }
+            }
+            return highlight;
+        });
+        if let Some(hl) = highlight.inner {
+            ui.label(
+                RichText::new(crate::utils::resized_str(&colors[hl].name, 24))
+                    .color(colors[hl].get_full_value_color32()),
+            );
+        } else {
+            ui.label("None");
+        }
+
+        ui.separator();
+
+        ui.label("\u{f0835} Positions:");
+        for i in 0..colors.len() {
+            Self::display_color(ui, &mut self.positions[i], &colors[i]);
+        }
+
+        if let Some(hth) = &self.hthread {
+            if hth.is_finished() {
+                if let Some(rx) = self.channel.take() {
+                    return rx.recv().ok();
+                }
+                self.hthread = None;
+                self.channel = None;
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manhattan_distance() {
+        let black = color_item::ColorItem::new("black", 0, 0, 0);
+        let white = color_item::ColorItem::new("white", 255, 255, 255);
+        let gen = DiamondGenerator::new(
+            vec![black, white],
+            vec![0.0, 1.0],
+            100,
+            100,
+            super::super::Interpolation::RGB,
+        );
+        assert_eq!(gen.get_color(50, 50), (0, 0, 0));
+        // the same sum of offsets is the same color, wherever it points
+        assert_eq!(gen.get_color(70, 50), gen.get_color(60, 40));
+        assert_eq!(gen.get_color(70, 50), gen.get_color(40, 60));
+        // the corner is the far end
+        assert_eq!(gen.get_color(0, 0), (255, 255, 255));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::color_item;
+use crate::utils::lch;
+use eframe::egui::{self, RichText};
+use std::sync::mpsc;
+use std::thread;
+
+pub struct Duotone {
+    shadow: usize,
+    highlight: usize,
+    contrast: f32,
+    midpoint: f32,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<image::DynamicImage>>,
+}
+
+/// color for every luminance 0-255, mixed from shadow to highlight in Lab
+fn duotone_lut(
+    shadow: &color_item::ColorItem,
+    highlight: &color_item::ColorItem,
+    contrast: f32,
+    midpoint: f32,
+) -> Vec<[u8; 3]> {
+    let dark = shadow.get_lab();
+    let light = highlight.get_lab();
+    return (0..256)
+        .map(|i| {
+            let y = i as f32 / 255f32;
+            let t = ((y - midpoint) * contrast + 0.5).clamp(0f32, 1f32) as f64;
+            let (r, g, b) = lch::lab_to_rgb(
+                dark.0 + (light.0 - dark.0) * t,
+                dark.1 + (light.1 - dark.1) * t,
+                dark.2 + (light.2 - dark.2) * t,
+            );
+            [r, g, b]
+        })
+        .collect();
+}
+
+impl Duotone {
+    pub fn new() -> Self {
+        Self {
+            shadow: 0,
+            highlight: 1,
+            contrast: 1f32,
+            midpoint: 0.5,
+            hthread: None,
+            channel: None,
+        }
+    }
+    fn color_combo(
+        ui: &mut egui::Ui,
+        label: &str,
+        selected: &mut usize,
+        colors: &Vec<color_item::ColorItem>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            egui::ComboBox::from_id_salt(label)
+                .selected_text(
+                    RichText::new(crate::utils::resized_str(&colors[*selected].name, 16))
+                        .color(colors[*selected].get_full_value_color32()),
+                )
+                .show_ui(ui, |ui| {
+                    for (i, color) in colors.iter().enumerate() {
+                        ui.selectable_value(
+                            selected,
+                            i,
+                            RichText::new(crate::utils::resized_str(&color.name, 16))
+                                .color(color.get_full_value_color32()),
+                        );
+                    }
+                });
+        });
+    }
+}
+
+impl super::Generate for Duotone {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Duotone".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.len() < 2 {
+            ui.label("Need at least 2 colors.");
+            return None;
+        }
+        self.shadow = self.shadow.min(colors.len() - 1);
+        self.highlight = self.highlight.min(colors.len() - 1);
+        ui.label("Map the luminance of the current image between two colors.");
+        Self::color_combo(ui, "\u{f0599} Shadow:", &mut self.shadow, colors);
+        Self::color_combo(ui, "\u{f0598} Highlight:", &mut self.highlight, colors);
+        ui.horizontal(|ui| {
+            ui.label("\u{f0ad5} Contrast:");
+            ui.add(egui::Slider::new(&mut self.contrast, 0.1f32..=4f32));
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0d1e} Midpoint:");
+            ui.add(egui::Slider::new(&mut self.midpoint, 0f32..=1f32));
+        });
+        let lut = duotone_lut(
+            &colors[self.shadow],
+            &colors[self.highlight],
+            self.contrast,
+            self.midpoint,
+        );
+        // the mapping as a strip from black to white input
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(192f32, 12f32), egui::Sense::hover());
+        let step = rect.width() / lut.len() as f32;
+        for (i, [r, g, b]) in lut.iter().enumerate() {
+            let x = rect.left() + i as f32 * step;
+            ui.painter().rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(x, rect.top()),
+                    egui::pos2(x + step + 0.5, rect.bottom()),
+                ),
+                0f32,
+                egui::Color32::from_rgb(*r, *g, *b),
+            );
+        }
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() && self.hthread.is_none() {
+                let img = buffer.clone();
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.hthread = Some(thread::spawn(move || {
+                    let mut buffer = img.to_rgba8();
+                    for pixel in buffer.pixels_mut() {
+                        let [r, g, b, _] = pixel.0;
+                        let [r, g, b] = lut[crate::utils::luma(r, g, b) as usize];
+                        pixel.0[0] = r;
+                        pixel.0[1] = g;
+                        pixel.0[2] = b;
+                    }
+                    tx.send(image::DynamicImage::ImageRgba8(buffer)).unwrap();
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(hth) = &self.hthread {
+            if hth.is_finished() {
+                let img = self.channel.take().and_then(|rx| rx.recv().ok());
+                self.hthread = None;
+                return img;
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duotone_lut() {
+        let navy = color_item::ColorItem::from_hex("#1b2a6b", "navy").unwrap();
+        let peach = color_item::ColorItem::from_hex("#ffc49b", "peach").unwrap();
+        let lut = duotone_lut(&navy, &peach, 1f32, 0.5);
+        assert_eq!(lut.len(), 256);
+        // the ends are the two colors, give or take rounding
+        let close = |a: [u8; 3], c: &color_item::ColorItem| {
+            (a[0] as i32 - c.r as i32).abs() <= 1
+                && (a[1] as i32 - c.g as i32).abs() <= 1
+                && (a[2] as i32 - c.b as i32).abs() <= 1
+        };
+        assert!(close(lut[0], &navy));
+        assert!(close(lut[255], &peach));
+        // lightness rises along the way
+        let l = |c: [u8; 3]| lch::rgb_to_lab(c[0], c[1], c[2]).0;
+        assert!(l(lut[64]) < l(lut[128]) && l(lut[128]) < l(lut[192]));
+
+        // high contrast pushes everything to the ends
+        let hard = duotone_lut(&navy, &peach, 4f32, 0.5);
+        assert!(close(hard[32], &navy));
+        assert!(close(hard[224], &peach));
+    }
+}
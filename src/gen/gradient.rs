@@ -0,0 +1,116 @@
+use crate::color_item;
+use crate::utils::lch;
+
+/// color space the stops of a gradient are mixed in
+#[derive(Clone, Copy, PartialEq)]
+pub enum Space {
+    Rgb,
+    Lab,
+}
+
+impl Space {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Space::Rgb => "RGB",
+            Space::Lab => "Lab",
+        }
+    }
+}
+
+/// colors at positions in 0..1, sampled by mixing the two stops around a position
+#[derive(Clone)]
+pub struct Gradient {
+    // (position, channels in `space`), sorted by position
+    stops: Vec<(f32, (f64, f64, f64))>,
+    space: Space,
+}
+
+impl Gradient {
+    pub fn new(colors: &[color_item::ColorItem], positions: &[f32], space: Space) -> Self {
+        let mut stops: Vec<(f32, (f64, f64, f64))> = colors
+            .iter()
+            .zip(positions.iter())
+            .map(|(color, position)| {
+                let channels = match space {
+                    Space::Rgb => (color.r as f64, color.g as f64, color.b as f64),
+                    Space::Lab => color.get_lab(),
+                };
+                (*position, channels)
+            })
+            .collect();
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Self { stops, space }
+    }
+    /// the colors spread at equal distances from 0 to 1
+    pub fn evenly(colors: &[color_item::ColorItem], space: Space) -> Self {
+        let last = (colors.len().max(2) - 1) as f32;
+        let positions: Vec<f32> = (0..colors.len()).map(|i| i as f32 / last).collect();
+        return Self::new(colors, &positions, space);
+    }
+    /// color at `t`, positions outside the stops take the nearest end
+    pub fn sample(&self, t: f32) -> (u8, u8, u8) {
+        let Some(first) = self.stops.first() else {
+            return (0, 0, 0);
+        };
+        let mut channels = first.1;
+        if t > first.0 {
+            channels = self.stops[self.stops.len() - 1].1;
+            for pair in self.stops.windows(2) {
+                let ((p0, c0), (p1, c1)) = (pair[0], pair[1]);
+                if t <= p1 {
+                    let f = if p1 > p0 { ((t - p0) / (p1 - p0)) as f64 } else { 1f64 };
+                    channels = (
+                        c0.0 + (c1.0 - c0.0) * f,
+                        c0.1 + (c1.1 - c0.1) * f,
+                        c0.2 + (c1.2 - c0.2) * f,
+                    );
+                    break;
+                }
+            }
+        }
+        match self.space {
+            Space::Rgb => {
+                let to_u8 = |c: f64| c.round().clamp(0f64, 255f64) as u8;
+                return (to_u8(channels.0), to_u8(channels.1), to_u8(channels.2));
+            }
+            Space::Lab => return lch::lab_to_rgb(channels.0, channels.1, channels.2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops() -> Vec<color_item::ColorItem> {
+        return vec![
+            color_item::ColorItem::from_hex("#000000", "black").unwrap(),
+            color_item::ColorItem::from_hex("#ff0000", "red").unwrap(),
+            color_item::ColorItem::from_hex("#ffffff", "white").unwrap(),
+        ];
+    }
+
+    #[test]
+    fn test_sample_rgb() {
+        let gradient = Gradient::evenly(&stops(), Space::Rgb);
+        assert_eq!(gradient.sample(0.0), (0, 0, 0));
+        assert_eq!(gradient.sample(0.5), (255, 0, 0));
+        assert_eq!(gradient.sample(1.0), (255, 255, 255));
+        assert_eq!(gradient.sample(0.25), (128, 0, 0));
+        // outside the stops clamps to the ends instead of going black
+        assert_eq!(gradient.sample(-1.0), (0, 0, 0));
+        assert_eq!(gradient.sample(2.0), (255, 255, 255));
+    }
+
+    #[test]
+    fn test_sample_lab() {
+        let gradient = Gradient::evenly(&stops(), Space::Lab);
+        let (r, g, b) = gradient.sample(0.5);
+        assert!(r >= 254 && g <= 1 && b <= 1);
+        // Lab keeps the midpoint between black and red lighter than RGB does
+        let rgb = Gradient::evenly(&stops(), Space::Rgb).sample(0.25);
+        let lab = gradient.sample(0.25);
+        let lightness = |c: (u8, u8, u8)| lch::rgb_to_lab(c.0, c.1, c.2).0;
+        assert!(lightness(lab) > lightness(rgb));
+    }
+}
@@ -0,0 +1,99 @@
+use super::gradient::{Gradient, Space};
+use crate::color_item;
+use eframe::egui;
+use std::sync::mpsc;
+use std::thread;
+
+pub struct GradientMap {
+    space: Space,
+    // dark pixels take the last color instead of the first
+    reverse: bool,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<image::DynamicImage>>,
+}
+
+impl GradientMap {
+    pub fn new() -> Self {
+        Self {
+            space: Space::Lab,
+            reverse: false,
+            hthread: None,
+            channel: None,
+        }
+    }
+}
+
+impl super::Generate for GradientMap {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Gradient Map".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.len() < 2 {
+            ui.label("Need at least 2 colors.");
+            return None;
+        }
+        ui.label("Map the luminance of the current image through the palette, first color for shadows.");
+        ui.horizontal(|ui| {
+            ui.label("\u{e9d9} Mix In:");
+            for space in [Space::Rgb, Space::Lab] {
+                ui.selectable_value(&mut self.space, space, space.get_name());
+            }
+            ui.checkbox(&mut self.reverse, "\u{eaf5} Reverse");
+        });
+        let mut stops = colors.clone();
+        if self.reverse {
+            stops.reverse();
+        }
+        let gradient = Gradient::evenly(&stops, self.space);
+        let lut: Vec<(u8, u8, u8)> = (0..256).map(|i| gradient.sample(i as f32 / 255f32)).collect();
+        // the mapping as a strip from black to white input
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(192f32, 12f32), egui::Sense::hover());
+        let step = rect.width() / lut.len() as f32;
+        for (i, (r, g, b)) in lut.iter().enumerate() {
+            let x = rect.left() + i as f32 * step;
+            ui.painter().rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(x, rect.top()),
+                    egui::pos2(x + step + 0.5, rect.bottom()),
+                ),
+                0f32,
+                egui::Color32::from_rgb(*r, *g, *b),
+            );
+        }
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() && self.hthread.is_none() {
+                let img = buffer.clone();
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.hthread = Some(thread::spawn(move || {
+                    let mut buffer = img.to_rgba8();
+                    for pixel in buffer.pixels_mut() {
+                        let [r, g, b, _] = pixel.0;
+                        let (r, g, b) = lut[crate::utils::luma(r, g, b) as usize];
+                        pixel.0[0] = r;
+                        pixel.0[1] = g;
+                        pixel.0[2] = b;
+                    }
+                    tx.send(image::DynamicImage::ImageRgba8(buffer)).unwrap();
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(hth) = &self.hthread {
+            if hth.is_finished() {
+                let img = self.channel.take().and_then(|rx| rx.recv().ok());
+                self.hthread = None;
+                return img;
+            }
+        }
+        return None;
+    }
+}
@@ -1,24 +1,32 @@
 use crate::color_item;
 use eframe::egui::{self, RichText};
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 
+// stops per pair of colors that stand in for Lab blending, SVG only blends in sRGB
+const LAB_SVG_STEPS: usize = 8;
 
 pub struct Line {
     positions: Vec<f32>,
     angel: f32,
+    snap: bool,
+    interpolation: super::Interpolation,
     width: u32,
     height: u32,
     // manage thread
     hthread: Option<thread::JoinHandle<()>>,
     channel: Option<mpsc::Receiver<image::DynamicImage>>,
+    // set to stop the running thread
+    cancel: Arc<AtomicBool>,
     // manage drag
 }
 
 #[derive(Clone)]
 struct LineGenerator {
-    data: Vec<(f32, color_item::ColorItem)>,
+    stops: super::Stops,
     angel: f32,
+    linemin: f32,
     linemax: f32,
 }
 
@@ -29,39 +37,63 @@ impl LineGenerator {
         angel: f32,
         width: u32,
         height: u32,
+        interpolation: super::Interpolation,
     ) -> Self {
-        let mut data = Vec::new();
-        for i in 0..colors.len() {
-            data.push((positions[i], colors[i].clone()));
-        }
-        data.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-        let linemax = (width as f32) * angel.sin() + (height as f32) * angel.cos();
+        let stops = super::Stops::new(colors, positions, interpolation);
+        // the gradient spans the projections of the four corners, so any direction
+        // maps the image onto 0..1 no matter which corner comes first
+        let project = |x: f32, y: f32| x * angel.sin() + y * angel.cos();
+        let corners = [
+            project(0.0, 0.0),
+            project(width as f32, 0.0),
+            project(0.0, height as f32),
+            project(width as f32, height as f32),
+        ];
+        let linemin = corners.iter().cloned().fold(f32::INFINITY, f32::min);
+        let linemax = corners.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
         Self {
-            data,
+            stops,
             angel,
+            linemin,
             linemax,
         }
     }
     fn get_color(&self, x: u32, y: u32) -> (u8, u8, u8) {
         let line = (x as f32) * self.angel.sin() + (y as f32) * self.angel.cos();
-        let line_divided = line / self.linemax;
-        for i in 1..self.data.len() {
-            if line_divided <= self.data[i].0 {
-                let color2 = self.data[i - 1].1.clone();
-                let color1 = self.data[i].1.clone();
-                let color1_divided =
-                    (line_divided - self.data[i - 1].0) / (self.data[i].0 - self.data[i - 1].0);
-                let color2_divided = 1.0 - color1_divided;
-                return (
-                    (color1.r as f32 * color1_divided + color2.r as f32 * color2_divided) as u8,
-                    (color1.g as f32 * color1_divided + color2.g as f32 * color2_divided) as u8,
-                    (color1.b as f32 * color1_divided + color2.b as f32 * color2_divided) as u8,
-                );
-            } else {
-                continue;
+        let line_divided = (line - self.linemin) / (self.linemax - self.linemin);
+        return self.stops.color_at(line_divided);
+    }
+    // a linearGradient from the point where the line is at its minimum to where it is at its maximum
+    fn svg(&self, width: u32, height: u32) -> String {
+        let (sin, cos) = (self.angel.sin(), self.angel.cos());
+        let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+        let center = cx * sin + cy * cos;
+        let point = |line: f32| (cx + sin * (line - center), cy + cos * (line - center));
+        let (x1, y1) = point(self.linemin);
+        let (x2, y2) = point(self.linemax);
+        let stop = |offset: f32, (r, g, b): (u8, u8, u8)| {
+            format!(
+                "<stop offset=\"{:.4}\" stop-color=\"#{:02x}{:02x}{:02x}\"/>\n",
+                offset, r, g, b
+            )
+        };
+        let mut stops = String::new();
+        let data = &self.stops.data;
+        for (i, (pos, color, _)) in data.iter().enumerate() {
+            if i > 0 && self.stops.interpolation == super::Interpolation::Lab {
+                let previous = data[i - 1].0;
+                for step in 1..LAB_SVG_STEPS {
+                    let t = previous + (pos - previous) * step as f32 / LAB_SVG_STEPS as f32;
+                    stops.push_str(&stop(t, self.stops.color_at(t)));
+                }
             }
+            stops.push_str(&stop(*pos, (color.r, color.g, color.b)));
         }
-        return (0, 0, 0);
+        let body = format!(
+            "<defs>\n<linearGradient id=\"line\" gradientUnits=\"userSpaceOnUse\" x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\">\n{}</linearGradient>\n</defs>\n<rect width=\"{}\" height=\"{}\" fill=\"url(#line)\"/>\n",
+            x1, y1, x2, y2, stops, width, height
+        );
+        return super::svg_document(width, height, &body);
     }
 }
 
@@ -70,10 +102,13 @@ impl Line {
         Self {
             positions: Vec::new(),
             angel: 0.0,
+            snap: false,
+            interpolation: super::Interpolation::RGB,
             width: 512,
             height: 512,
             hthread: None,
             channel: None,
+            cancel: Arc::new(AtomicBool::new(false)),
         }
     }
     fn display_color(ui: &mut egui::Ui, position: &mut f32, color: &color_item::ColorItem) {
@@ -87,9 +122,10 @@ impl Line {
                 },
             );
             let painter = ui.painter();
-            painter.rect(
+            color_item::paint_swatch(
+                painter,
                 rect,
-                0f32,
+                color_item::swatch_rounding(),
                 color.to_color32(),
                 egui::Stroke::new(0.5f32, egui::Color32::WHITE),
             );
@@ -106,10 +142,35 @@ impl super::Generate for Line {
     fn get_name(&self) -> String {
         return "\u{eae6} Line".into();
     }
+    fn css_angle(&self) -> Option<f32> {
+        // 0 runs top to bottom here, CSS turns clockwise from bottom to top
+        return Some((180.0 - self.angel).rem_euclid(360.0));
+    }
+    fn svg(&self, colors: &Vec<color_item::ColorItem>) -> Option<String> {
+        if colors.len() < 2 {
+            return None;
+        }
+        // the positions follow the list once the tab has been drawn with it
+        let positions = if self.positions.len() == colors.len() {
+            self.positions.clone()
+        } else {
+            (0..colors.len()).map(|i| i as f32 / (colors.len() - 1) as f32).collect()
+        };
+        let gen = LineGenerator::new(
+            colors.clone(),
+            positions,
+            self.angel.to_radians(),
+            self.width,
+            self.height,
+            self.interpolation,
+        );
+        return Some(gen.svg(self.width, self.height));
+    }
     fn paint_ui(
         &mut self,
         ui: &mut egui::Ui,
         colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
     ) -> Option<image::DynamicImage> {
         if colors.len() < 2 {
             ui.label("Need at least 2 colors.");
@@ -127,8 +188,23 @@ impl super::Generate for Line {
         self.positions[positions_len - 1] = 1.0;
         ui.horizontal(|ui| {
             ui.label("\u{f0937} Angle:");
-            ui.add(egui::Slider::new(&mut self.angel, 0.0..=90.0).text("°"));
+            let slider = ui.add(egui::Slider::new(&mut self.angel, 0.0..=360.0).show_value(false));
+            let value = ui.add(
+                egui::DragValue::new(&mut self.angel)
+                    .range(0.0..=360.0)
+                    .speed(0.1)
+                    .max_decimals(2)
+                    .suffix("°"),
+            );
+            ui.checkbox(&mut self.snap, "Snap 45°")
+                .on_hover_text("or hold shift while dragging");
+            // snap to multiples of 45
+            let shift = ui.input(|i| i.modifiers.shift);
+            if (self.snap || shift) && (slider.changed() || value.changed()) {
+                self.angel = (self.angel / 45.0).round() * 45.0;
+            }
         });
+        super::interpolation_ui(ui, &mut self.interpolation);
         ui.horizontal(|ui| {
             ui.label("\u{f019e} Width:");
             ui.add(
@@ -150,8 +226,11 @@ impl super::Generate for Line {
                 let thread_angel = self.angel.to_radians();
                 let thread_width = self.width.clone();
                 let thread_height = self.height.clone();
+                let thread_interpolation = self.interpolation;
                 let (tx, rx) = mpsc::channel();
                 self.channel = Some(rx);
+                self.cancel = Arc::new(AtomicBool::new(false));
+                let cancel = self.cancel.clone();
                 self.hthread = Some(thread::spawn(move || {
                     // many colors
                     // sort first
@@ -161,17 +240,27 @@ impl super::Generate for Line {
                         thread_angel,
                         thread_width,
                         thread_height,
+                        thread_interpolation,
                     );
 
-                    let buffer = image::RgbImage::from_fn(thread_width, thread_height, |x, y| {
-                        let (r, g, b) = gen.get_color(x, y);
-                        image::Rgb([r, g, b])
-                    });
-                    tx.send(image::DynamicImage::ImageRgb8(buffer)).unwrap();
+                    // a cancelled run drops the channel without sending
+                    if let Some(buffer) = super::render_cancellable(
+                        thread_width,
+                        thread_height,
+                        &cancel,
+                        |x, y| gen.get_color(x, y),
+                    ) {
+                        let _ = tx.send(image::DynamicImage::ImageRgb8(buffer));
+                    }
                 }));
             }
             if self.hthread.is_some() {
                 ui.spinner();
+                if ui.button("\u{f0156} Cancel").clicked() {
+                    self.cancel.store(true, Ordering::Relaxed);
+                    self.hthread = None;
+                    self.channel = None;
+                }
             }
         });
         let width = 192f32;
@@ -235,3 +324,66 @@ impl super::Generate for Line {
         return None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn black_to_white(angle: f32) -> LineGenerator {
+        let black = color_item::ColorItem::from_hex("#000000", "black").unwrap();
+        let white = color_item::ColorItem::from_hex("#ffffff", "white").unwrap();
+        return LineGenerator::new(
+            vec![black, white],
+            vec![0.0, 1.0],
+            angle.to_radians(),
+            10,
+            10,
+            crate::gen::Interpolation::RGB,
+        );
+    }
+
+    #[test]
+    fn test_angle_135() {
+        // dark in the bottom-left, bright in the top-right
+        let gen = black_to_white(135.0);
+        assert!(gen.get_color(0, 9).0 < 30);
+        assert!(gen.get_color(9, 0).0 > 225);
+        assert!(gen.get_color(0, 9).0 < gen.get_color(5, 5).0);
+        assert!(gen.get_color(5, 5).0 < gen.get_color(9, 0).0);
+    }
+
+    #[test]
+    fn test_angle_225() {
+        // bright in the top-left, dark in the bottom-right
+        let gen = black_to_white(225.0);
+        assert!(gen.get_color(0, 0).0 > 225);
+        assert!(gen.get_color(9, 9).0 < 30);
+        assert!(gen.get_color(9, 9).0 < gen.get_color(5, 5).0);
+        assert!(gen.get_color(5, 5).0 < gen.get_color(0, 0).0);
+    }
+
+    #[test]
+    fn test_svg() {
+        let colors: Vec<color_item::ColorItem> = ["#ff0000", "#00ff00", "#0000ff"]
+            .iter()
+            .map(|hex| color_item::ColorItem::from_hex(hex, "c").unwrap())
+            .collect();
+        let mut line = Line::new();
+        assert_eq!(super::super::Generate::svg(&line, &colors[..1].to_vec()), None);
+        line.positions = vec![0.0, 0.25, 1.0];
+        let svg = super::super::Generate::svg(&line, &colors).unwrap();
+        let elements = crate::gen::tests::parse_svg(&svg).unwrap();
+        assert_eq!(elements.iter().filter(|name| *name == "stop").count(), 3);
+        assert_eq!(elements[..3], ["svg", "defs", "linearGradient"]);
+        assert!(svg.contains("<stop offset=\"0.2500\" stop-color=\"#00ff00\"/>"));
+        // angle 0 runs from the top edge to the bottom edge
+        assert!(svg.contains("x1=\"256.00\" y1=\"0.00\" x2=\"256.00\" y2=\"512.00\""));
+
+        // Lab is approximated by stops in between
+        line.interpolation = crate::gen::Interpolation::Lab;
+        let svg = super::super::Generate::svg(&line, &colors).unwrap();
+        let elements = crate::gen::tests::parse_svg(&svg).unwrap();
+        let stops = elements.iter().filter(|name| *name == "stop").count();
+        assert_eq!(stops, 3 + 2 * (LAB_SVG_STEPS - 1));
+    }
+}
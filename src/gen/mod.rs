@@ -2,11 +2,23 @@ use eframe::egui;
 use image;
 
 use crate::color_item;
+use crate::utils::auto_palette::color_struct::Color;
+use crate::utils::auto_palette::rgb::RGB;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 mod line;
 mod circle;
+mod conic;
+mod diamond;
 mod mono;
 mod blocks;
+mod plasma;
+mod quantize;
+mod adjust;
+mod duotone;
+mod gradient;
+mod gradient_map;
+mod replace_hue;
 
 lazy_static::lazy_static! {
     pub static ref NAMELIST: Vec<(String, GenerateComponent)> = get_component_namelist();
@@ -17,24 +29,211 @@ pub trait Generate {
         &mut self,
         ui: &mut egui::Ui,
         colors: &Vec<color_item::ColorItem>,
+        buffer: &image::DynamicImage,
     ) -> Option<image::DynamicImage>;
     fn get_name(&self) -> String;
+    /// key/value pairs describing the last generated image, saved into the PNG
+    fn metadata(&self) -> Vec<(String, String)> {
+        return Vec::new();
+    }
+    /// direction of a linear gradient in CSS degrees, for components that draw one
+    fn css_angle(&self) -> Option<f32> {
+        return None;
+    }
+    /// the image the current settings generate as an SVG document,
+    /// for components whose shapes a vector image can hold
+    fn svg(&self, _colors: &Vec<color_item::ColorItem>) -> Option<String> {
+        return None;
+    }
+    /// the result is one flat color by design, so it is not worth a warning
+    fn uniform_by_design(&self) -> bool {
+        return false;
+    }
 }
 
 #[derive(Clone, Copy)]
 pub enum GenerateComponent {
     Line,
     Circle,
+    Conic,
+    Diamond,
     Mono,
     Blocks,
+    Plasma,
+    Quantize,
+    Adjust,
+    Duotone,
+    GradientMap,
+    ReplaceHue,
+}
+
+// color space the gradient generators blend their stops in
+#[derive(Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    RGB,
+    Lab,
+}
+
+/// color stops of a gradient sorted by position, with the Lab of every color worked out once
+#[derive(Clone)]
+pub struct Stops {
+    data: Vec<(f32, color_item::ColorItem, Color<f64>)>,
+    interpolation: Interpolation,
+}
+
+impl Stops {
+    pub fn new(
+        colors: Vec<color_item::ColorItem>,
+        positions: Vec<f32>,
+        interpolation: Interpolation,
+    ) -> Self {
+        let mut data: Vec<(f32, color_item::ColorItem, Color<f64>)> = colors
+            .into_iter()
+            .zip(positions)
+            .map(|(color, pos)| {
+                let lab = Color::from(&RGB::new(color.r, color.g, color.b));
+                (pos, color, lab)
+            })
+            .collect();
+        data.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        return Self {
+            data,
+            interpolation,
+        };
+    }
+    /// color at `t` in 0..1 between the two stops around it, black outside of them
+    pub fn color_at(&self, t: f32) -> (u8, u8, u8) {
+        for i in 1..self.data.len() {
+            if t <= self.data[i].0 {
+                let (pos2, color2, lab2) = &self.data[i - 1];
+                let (pos1, color1, lab1) = &self.data[i];
+                let color1_divided = (t - pos2) / (pos1 - pos2);
+                let color2_divided = 1.0 - color1_divided;
+                return match self.interpolation {
+                    Interpolation::RGB => (
+                        (color1.r as f32 * color1_divided + color2.r as f32 * color2_divided) as u8,
+                        (color1.g as f32 * color1_divided + color2.g as f32 * color2_divided) as u8,
+                        (color1.b as f32 * color1_divided + color2.b as f32 * color2_divided) as u8,
+                    ),
+                    Interpolation::Lab => {
+                        let rgb = lab2.mix(lab1, color1_divided.clamp(0.0, 1.0) as f64).to_rgb();
+                        (rgb.r(), rgb.g(), rgb.b())
+                    }
+                };
+            }
+        }
+        return (0, 0, 0);
+    }
+}
+
+pub fn interpolation_ui(ui: &mut egui::Ui, interpolation: &mut Interpolation) {
+    ui.horizontal(|ui| {
+        ui.label("\u{f0e80} Interpolation:");
+        ui.selectable_value(interpolation, Interpolation::RGB, "RGB");
+        ui.selectable_value(interpolation, Interpolation::Lab, "Lab")
+            .on_hover_text("blend through CIE Lab, no muddy midpoints");
+    });
+}
+
+/// `fill="#rrggbb"`, with `fill-opacity` when the color is not opaque
+pub fn svg_fill(rgba: [u8; 4]) -> String {
+    let [r, g, b, a] = rgba;
+    if a == 255 {
+        return format!("fill=\"#{:02x}{:02x}{:02x}\"", r, g, b);
+    }
+    return format!(
+        "fill=\"#{:02x}{:02x}{:02x}\" fill-opacity=\"{:.3}\"",
+        r,
+        g,
+        b,
+        a as f32 / 255.0
+    );
+}
+
+/// a standalone SVG document of `width` x `height` around `body`
+pub fn svg_document(width: u32, height: u32, body: &str) -> String {
+    return format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n{2}</svg>\n",
+        width, height, body
+    );
+}
+
+// height of the gradient strip in the Colors tab
+const STRIP_HEIGHT: f32 = 24f32;
+
+/// the colors evenly spaced in sequence, sampled at `steps` points from the first to the last
+pub fn sequence_colors(
+    colors: &[color_item::ColorItem],
+    interpolation: Interpolation,
+    steps: usize,
+) -> Vec<(u8, u8, u8)> {
+    if colors.len() == 1 {
+        let color = &colors[0];
+        return vec![(color.r, color.g, color.b); steps];
+    }
+    let last = colors.len().saturating_sub(1).max(1) as f32;
+    let positions = (0..colors.len()).map(|i| i as f32 / last).collect();
+    let stops = Stops::new(colors.to_vec(), positions, interpolation);
+    let span = steps.saturating_sub(1).max(1) as f32;
+    return (0..steps).map(|i| stops.color_at(i as f32 / span)).collect();
+}
+
+/// a strip across the available width blending the colors in sequence, like a Line gradient
+pub fn gradient_strip(ui: &mut egui::Ui, colors: &[color_item::ColorItem], interpolation: Interpolation) {
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), STRIP_HEIGHT),
+        egui::Sense::hover(),
+    );
+    if colors.is_empty() || !ui.is_rect_visible(rect) {
+        return;
+    }
+    // one sample per point is as fine as the screen shows it
+    let steps = rect.width().ceil().max(1f32) as usize;
+    let painter = ui.painter_at(rect);
+    for (i, (r, g, b)) in sequence_colors(colors, interpolation, steps).into_iter().enumerate() {
+        let x = rect.min.x + i as f32;
+        let column = egui::Rect::from_min_max(egui::pos2(x, rect.min.y), egui::pos2(x + 1f32, rect.max.y));
+        painter.rect_filled(column, 0f32, egui::Color32::from_rgb(r, g, b));
+    }
+}
+
+/// fill an image row by row, none as soon as `cancel` is set
+pub fn render_cancellable<F>(
+    width: u32,
+    height: u32,
+    cancel: &AtomicBool,
+    pixel: F,
+) -> Option<image::RgbImage>
+where
+    F: Fn(u32, u32) -> (u8, u8, u8),
+{
+    let mut buffer = image::RgbImage::new(width, height);
+    for y in 0..height {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        for x in 0..width {
+            let (r, g, b) = pixel(x, y);
+            buffer.put_pixel(x, y, image::Rgb([r, g, b]));
+        }
+    }
+    return Some(buffer);
 }
 
 pub fn get_component(component: GenerateComponent) -> Box<dyn Generate> {
     match component {
         GenerateComponent::Line => Box::new(line::Line::new()),
         GenerateComponent::Circle => Box::new(circle::Circle::new()),
+        GenerateComponent::Conic => Box::new(conic::Conic::new()),
+        GenerateComponent::Diamond => Box::new(diamond::Diamond::new()),
         GenerateComponent::Mono => Box::new(mono::Mono::new()),
         GenerateComponent::Blocks => Box::new(blocks::Blocks::new()),
+        GenerateComponent::Plasma => Box::new(plasma::Plasma::new()),
+        GenerateComponent::Quantize => Box::new(quantize::Quantize::new()),
+        GenerateComponent::Adjust => Box::new(adjust::Adjust::new()),
+        GenerateComponent::Duotone => Box::new(duotone::Duotone::new()),
+        GenerateComponent::GradientMap => Box::new(gradient_map::GradientMap::new()),
+        GenerateComponent::ReplaceHue => Box::new(replace_hue::ReplaceHue::new()),
     }
 }
 
@@ -42,7 +241,123 @@ pub fn get_component_namelist() -> Vec<(String, GenerateComponent)> {
     let mut list = Vec::new();
     list.push(("\u{f012a} Line".into(), GenerateComponent::Line));
     list.push(("\u{f0e96} Circle".into(), GenerateComponent::Circle));
+    list.push(("\u{f0bd0} Conic".into(), GenerateComponent::Conic));
+    list.push(("\u{f01c8} Diamond".into(), GenerateComponent::Diamond));
     list.push(("\u{eae6} Mono".into(), GenerateComponent::Mono));
     list.push(("\u{f0763} Blocks".into(), GenerateComponent::Blocks));
+    list.push(("\u{f0238} Plasma".into(), GenerateComponent::Plasma));
+    list.push(("\u{f0b0d} Quantize".into(), GenerateComponent::Quantize));
+    list.push(("\u{f00e0} Adjust".into(), GenerateComponent::Adjust));
+    list.push(("\u{f0599} Duotone".into(), GenerateComponent::Duotone));
+    list.push(("\u{f0e80} Gradient Map".into(), GenerateComponent::GradientMap));
+    list.push(("\u{f0766} Replace Hue".into(), GenerateComponent::ReplaceHue));
     return list;
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// names of the elements of a well-formed SVG document in document order,
+    /// an error for unbalanced tags or a root that is not `svg`
+    pub fn parse_svg(svg: &str) -> Result<Vec<String>, String> {
+        let mut open: Vec<String> = Vec::new();
+        let mut names = Vec::new();
+        let mut rest = svg.trim();
+        while let Some(start) = rest.find('<') {
+            if !rest[..start].trim().is_empty() && open.is_empty() {
+                return Err("text outside the root".into());
+            }
+            let end = rest[start..].find('>').ok_or("unclosed tag")? + start;
+            let tag = &rest[start + 1..end];
+            if let Some(name) = tag.strip_prefix('/') {
+                if open.pop().as_deref() != Some(name.trim()) {
+                    return Err(format!("unexpected </{}>", name));
+                }
+            } else {
+                let name: String = tag.chars().take_while(|c| c.is_alphanumeric()).collect();
+                if name.is_empty() || tag.matches('"').count() % 2 != 0 {
+                    return Err(format!("malformed <{}>", tag));
+                }
+                if names.is_empty() && name != "svg" {
+                    return Err("the root is not svg".into());
+                }
+                if open.is_empty() && !names.is_empty() {
+                    return Err("more than one root".into());
+                }
+                names.push(name.clone());
+                if !tag.ends_with('/') {
+                    open.push(name);
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        if !open.is_empty() || !rest.trim().is_empty() || names.is_empty() {
+            return Err("unclosed elements".into());
+        }
+        return Ok(names);
+    }
+
+    #[test]
+    fn test_svg_document() {
+        let svg = svg_document(4, 2, &format!("<rect width=\"4\" height=\"2\" {}/>\n", svg_fill([255, 0, 16, 255])));
+        assert_eq!(parse_svg(&svg), Ok(vec!["svg".to_string(), "rect".to_string()]));
+        assert!(svg.contains("fill=\"#ff0010\""));
+        assert!(svg_fill([0, 0, 0, 51]).contains("fill-opacity=\"0.200\""));
+        assert!(parse_svg("<svg><rect></svg>").is_err());
+        assert!(parse_svg("<rect/>").is_err());
+    }
+
+    #[test]
+    fn test_stops_midpoint() {
+        let black = color_item::ColorItem::new("black", 0, 0, 0);
+        let white = color_item::ColorItem::new("white", 255, 255, 255);
+        let stops = |interpolation| {
+            Stops::new(vec![white.clone(), black.clone()], vec![1.0, 0.0], interpolation)
+        };
+        let rgb = stops(Interpolation::RGB);
+        let lab = stops(Interpolation::Lab);
+        // the ends are the same either way
+        assert_eq!(rgb.color_at(0.0), (0, 0, 0));
+        assert_eq!(lab.color_at(0.0), (0, 0, 0));
+        assert_eq!(rgb.color_at(1.0), (255, 255, 255));
+        assert_eq!(lab.color_at(1.0), (255, 255, 255));
+        // halfway in RGB is half the channel, halfway in Lab is L* 50, a darker gray
+        assert_eq!(rgb.color_at(0.5), (127, 127, 127));
+        let (r, g, b) = lab.color_at(0.5);
+        assert_eq!((r, r), (g, b));
+        assert!((117..=121).contains(&r));
+    }
+
+    #[test]
+    fn test_sequence_colors() {
+        let red = color_item::ColorItem::new("red", 255, 0, 0);
+        let green = color_item::ColorItem::new("green", 0, 255, 0);
+        let blue = color_item::ColorItem::new("blue", 0, 0, 255);
+        let colors = vec![red.clone(), green, blue];
+        // within rounding of the Lab round trip
+        let close = |a: (u8, u8, u8), b: (u8, u8, u8)| {
+            a.0.abs_diff(b.0) <= 1 && a.1.abs_diff(b.1) <= 1 && a.2.abs_diff(b.2) <= 1
+        };
+        for interpolation in [Interpolation::RGB, Interpolation::Lab] {
+            let strip = sequence_colors(&colors, interpolation, 5);
+            assert_eq!(strip.len(), 5);
+            // every color is hit at its even share of the strip, in order
+            assert!(close(strip[0], (255, 0, 0)));
+            assert!(close(strip[2], (0, 255, 0)));
+            assert!(close(strip[4], (0, 0, 255)));
+        }
+        assert_eq!(sequence_colors(&colors, Interpolation::RGB, 5)[1], (127, 127, 0));
+        assert_eq!(sequence_colors(&[red], Interpolation::Lab, 3), vec![(255, 0, 0); 3]);
+        assert!(sequence_colors(&[], Interpolation::RGB, 3).iter().all(|c| *c == (0, 0, 0)));
+    }
+
+    #[test]
+    fn test_render_cancellable() {
+        let cancel = AtomicBool::new(false);
+        let buffer = render_cancellable(3, 2, &cancel, |x, y| (x as u8, y as u8, 7)).unwrap();
+        assert_eq!(buffer.get_pixel(2, 1).0, [2, 1, 7]);
+        cancel.store(true, Ordering::Relaxed);
+        assert!(render_cancellable(3, 2, &cancel, |_, _| (0, 0, 0)).is_none());
+    }
+}
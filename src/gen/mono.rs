@@ -5,6 +5,8 @@ use egui::RichText;
 pub struct Mono {
     width: u32,
     height: u32,
+    // the color painted last, exported as SVG
+    painted: Option<color_item::ColorItem>,
 }
 
 impl Mono {
@@ -12,6 +14,7 @@ impl Mono {
         Self {
             width: 512,
             height: 512,
+            painted: None,
         }
     }
     fn display_color(ui: &mut egui::Ui, color: &color_item::ColorItem) -> bool {
@@ -26,9 +29,10 @@ impl Mono {
                 },
             );
             let painter = ui.painter();
-            painter.rect(
+            color_item::paint_swatch(
+                painter,
                 rect,
-                0f32,
+                color_item::swatch_rounding(),
                 color.to_color32(),
                 egui::Stroke::new(0.5f32, egui::Color32::WHITE),
             );
@@ -46,10 +50,24 @@ impl super::Generate for Mono {
     fn get_name(&self) -> String {
         return "\u{eae6} Mono".into();
     }
+    fn uniform_by_design(&self) -> bool {
+        return true;
+    }
+    fn svg(&self, _colors: &Vec<color_item::ColorItem>) -> Option<String> {
+        let color = self.painted.as_ref()?;
+        let rect = format!(
+            "<rect width=\"{}\" height=\"{}\" {}/>\n",
+            self.width,
+            self.height,
+            super::svg_fill([color.r, color.g, color.b, color.a])
+        );
+        return Some(super::svg_document(self.width, self.height, &rect));
+    }
     fn paint_ui(
         &mut self,
         ui: &mut egui::Ui,
         colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
     ) -> Option<image::DynamicImage> {
         ui.horizontal(|ui| {
             ui.label("\u{f019e} Width:");
@@ -76,11 +94,11 @@ impl super::Generate for Mono {
 
         match wait4gen {
             Some(color) => {
-                let buffer = image::RgbImage::from_pixel(
-                    self.width,
-                    self.height,
-                    image::Rgb([color.r, color.g, color.b]),
-                );
+                let rgba = [color.r, color.g, color.b, color.a];
+                self.painted = Some(color);
+                let buffer = image::RgbImage::from_fn(self.width, self.height, |x, y| {
+                    image::Rgb(crate::utils::over_checker(x, y, rgba))
+                });
                 let img = image::DynamicImage::ImageRgb8(buffer);
                 return Some(img);
             }
@@ -88,3 +106,20 @@ impl super::Generate for Mono {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::Generate;
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let mut mono = Mono::new();
+        assert_eq!(mono.svg(&Vec::new()), None);
+        mono.painted = Some(color_item::ColorItem::new("teal", 0, 128, 128));
+        let svg = mono.svg(&Vec::new()).unwrap();
+        let elements = super::super::tests::parse_svg(&svg).unwrap();
+        assert_eq!(elements, vec!["svg", "rect"]);
+        assert!(svg.contains("<rect width=\"512\" height=\"512\" fill=\"#008080\"/>"));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::color_item;
+use eframe::egui;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::TAU;
+use std::sync::mpsc;
+use std::thread;
+
+const WAVES: usize = 4;
+
+pub struct Plasma {
+    seed: u64,
+    scale: f32,
+    width: u32,
+    height: u32,
+    // seed of the last finished image, written into the saved PNG
+    generated_seed: Option<u64>,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<(u64, image::DynamicImage)>>,
+}
+
+struct PlasmaGenerator {
+    colors: Vec<color_item::ColorItem>,
+    // (direction, frequency, phase) of each plane wave
+    waves: Vec<(f32, f32, f32)>,
+    center: (f32, f32),
+    scale: f32,
+}
+
+impl PlasmaGenerator {
+    fn new(colors: Vec<color_item::ColorItem>, seed: u64, scale: f32) -> Self {
+        // everything random comes from the seed, so a seed always gives the same image
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let waves = (0..WAVES)
+            .map(|_| {
+                (
+                    rng.gen_range(0.0..TAU),
+                    rng.gen_range(0.5..1.5),
+                    rng.gen_range(0.0..TAU),
+                )
+            })
+            .collect();
+        let center = (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+        Self {
+            colors,
+            waves,
+            center,
+            scale,
+        }
+    }
+    fn get_color(&self, u: f32, v: f32) -> (u8, u8, u8) {
+        let mut value = 0f32;
+        for (direction, frequency, phase) in self.waves.iter() {
+            let along = u * direction.cos() + v * direction.sin();
+            value += (along * self.scale * frequency * TAU + phase).sin();
+        }
+        let distance = ((u - self.center.0).powi(2) + (v - self.center.1).powi(2)).sqrt();
+        value += (distance * self.scale * TAU).sin();
+        // back to 0..1 across the palette
+        let t = (value / (WAVES + 1) as f32 + 1f32) / 2f32;
+        let pos = t.clamp(0f32, 1f32) * (self.colors.len() - 1) as f32;
+        let i = (pos.floor() as usize).min(self.colors.len() - 2);
+        let f = pos - i as f32;
+        let (a, b) = (&self.colors[i], &self.colors[i + 1]);
+        return (
+            (a.r as f32 * (1f32 - f) + b.r as f32 * f) as u8,
+            (a.g as f32 * (1f32 - f) + b.g as f32 * f) as u8,
+            (a.b as f32 * (1f32 - f) + b.b as f32 * f) as u8,
+        );
+    }
+}
+
+impl Plasma {
+    pub fn new() -> Self {
+        Self {
+            seed: rand::thread_rng().gen_range(0..1_000_000),
+            scale: 2.0,
+            width: 512,
+            height: 512,
+            generated_seed: None,
+            hthread: None,
+            channel: None,
+        }
+    }
+}
+
+impl super::Generate for Plasma {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Plasma".into();
+    }
+    fn metadata(&self) -> Vec<(String, String)> {
+        match self.generated_seed {
+            Some(seed) => return vec![("seed".into(), seed.to_string())],
+            None => return Vec::new(),
+        }
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        _buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.len() < 2 {
+            ui.label("Need at least 2 colors.");
+            return None;
+        }
+        ui.horizontal(|ui| {
+            ui.label("\u{f0e96} Seed:");
+            ui.add(egui::DragValue::new(&mut self.seed).speed(1.0));
+            if ui.button("\u{f049d}").on_hover_text("random seed").clicked() {
+                self.seed = rand::thread_rng().gen_range(0..1_000_000);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0937} Scale:");
+            ui.add(egui::Slider::new(&mut self.scale, 0.5..=8.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f019e} Width:");
+            ui.add(
+                egui::DragValue::new(&mut self.width)
+                    .speed(1.0)
+                    .range(1..=16384),
+            );
+            ui.label("\u{f019e} Height:");
+            ui.add(
+                egui::DragValue::new(&mut self.height)
+                    .speed(1.0)
+                    .range(1..=16384),
+            );
+        });
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() && self.hthread.is_none() {
+                let thread_colors = colors.clone();
+                let thread_seed = self.seed;
+                let thread_scale = self.scale;
+                let thread_width = self.width;
+                let thread_height = self.height;
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.hthread = Some(thread::spawn(move || {
+                    let gen = PlasmaGenerator::new(thread_colors, thread_seed, thread_scale);
+                    let buffer = image::RgbImage::from_fn(thread_width, thread_height, |x, y| {
+                        let (r, g, b) = gen.get_color(
+                            x as f32 / thread_width as f32,
+                            y as f32 / thread_height as f32,
+                        );
+                        image::Rgb([r, g, b])
+                    });
+                    tx.send((thread_seed, image::DynamicImage::ImageRgb8(buffer)))
+                        .unwrap();
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(rx) = &self.channel {
+            if let Ok((seed, img)) = rx.try_recv() {
+                self.hthread = None;
+                self.channel = None;
+                self.generated_seed = Some(seed);
+                return Some(img);
+            }
+        }
+        return None;
+    }
+}
@@ -0,0 +1,176 @@
+use crate::color_item;
+use crate::utils::auto_palette::{DistanceMetric, KDTreeSearch, NeighborSearch, Point3};
+use eframe::egui;
+use std::sync::mpsc;
+use std::thread;
+
+pub struct Quantize {
+    // number of nearest palette colors blended per pixel, 1 snaps to the nearest
+    neighbors: usize,
+    // mean ΔE between the last input and its quantized result
+    accuracy: Option<f64>,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<(image::DynamicImage, Option<f64>)>>,
+}
+
+struct QuantizeGenerator {
+    points: Vec<Point3<f64>>,
+    colors: Vec<(f64, f64, f64)>,
+    neighbors: usize,
+}
+
+impl QuantizeGenerator {
+    fn new(colors: &[color_item::ColorItem], neighbors: usize) -> Self {
+        let points = colors
+            .iter()
+            .map(|color| {
+                let (l, a, b) = color.get_lab();
+                Point3(l, a, b)
+            })
+            .collect();
+        let colors = colors
+            .iter()
+            .map(|color| (color.r as f64, color.g as f64, color.b as f64))
+            .collect();
+        Self {
+            points,
+            colors,
+            neighbors: neighbors.max(1),
+        }
+    }
+    fn recolor(&self, img: &image::DynamicImage) -> image::RgbaImage {
+        let search = KDTreeSearch::new(&self.points, &DistanceMetric::Euclidean);
+        let mut buffer = img.to_rgba8();
+        for pixel in buffer.pixels_mut() {
+            let [r, g, b, _] = pixel.0;
+            let (l, a, lab_b) = crate::utils::lch::rgb_to_lab(r, g, b);
+            let neighbors = search.search(&Point3(l, a, lab_b), self.neighbors);
+            // inverse distance weighting, an exact match takes the color as is
+            let mut sum = (0f64, 0f64, 0f64);
+            let mut total = 0f64;
+            for neighbor in neighbors.iter() {
+                let weight = 1f64 / neighbor.distance.max(1e-6);
+                let color = self.colors[neighbor.index];
+                sum.0 += color.0 * weight;
+                sum.1 += color.1 * weight;
+                sum.2 += color.2 * weight;
+                total += weight;
+            }
+            if total > 0f64 {
+                pixel.0[0] = (sum.0 / total).round() as u8;
+                pixel.0[1] = (sum.1 / total).round() as u8;
+                pixel.0[2] = (sum.2 / total).round() as u8;
+            }
+        }
+        return buffer;
+    }
+}
+
+impl Quantize {
+    pub fn new() -> Self {
+        Self {
+            neighbors: 1,
+            accuracy: None,
+            hthread: None,
+            channel: None,
+        }
+    }
+}
+
+impl super::Generate for Quantize {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Quantize".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.is_empty() {
+            ui.label("Need at least 1 color.");
+            return None;
+        }
+        ui.label("Recolor the current image with the palette.");
+        ui.horizontal(|ui| {
+            ui.label("\u{f0b0d} Neighbors:");
+            ui.add(egui::Slider::new(&mut self.neighbors, 1..=colors.len().max(1).min(16)))
+                .on_hover_text("1 snaps each pixel to the nearest color, more blends softer");
+        });
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() && self.hthread.is_none() {
+                let gen = QuantizeGenerator::new(colors, self.neighbors);
+                let img = buffer.clone();
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.accuracy = None;
+                self.hthread = Some(thread::spawn(move || {
+                    let result = image::DynamicImage::ImageRgba8(gen.recolor(&img));
+                    let accuracy = crate::analysis::metrics::image_delta_e(
+                        &img.to_rgb8(),
+                        &result.to_rgb8(),
+                    );
+                    tx.send((result, accuracy)).unwrap();
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(accuracy) = self.accuracy {
+            ui.label(format!("\u{f0127} Mean \u{0394}E to the original: {:.2}", accuracy))
+                .on_hover_text("below 2.3 the difference is hard to see, higher means the palette loses detail");
+        }
+        if let Some(hth) = &self.hthread {
+            if hth.is_finished() {
+                let result = self.channel.take().and_then(|rx| rx.recv().ok());
+                self.hthread = None;
+                return result.map(|(img, accuracy)| {
+                    self.accuracy = accuracy;
+                    img
+                });
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> Vec<color_item::ColorItem> {
+        return vec![
+            color_item::ColorItem::from_hex("#000000", "black").unwrap(),
+            color_item::ColorItem::from_hex("#ffffff", "white").unwrap(),
+        ];
+    }
+
+    #[test]
+    fn test_hard_quantize() {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                image::Rgb([40, 40, 40])
+            } else {
+                image::Rgb([220, 220, 220])
+            }
+        }));
+        let out = QuantizeGenerator::new(&palette(), 1).recolor(&img);
+        assert_eq!(out.get_pixel(0, 0).0, [0, 0, 0, 255]);
+        assert_eq!(out.get_pixel(1, 0).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_soft_quantize() {
+        // a mid gray sits between both colors and blends them
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            1,
+            1,
+            image::Rgb([119, 119, 119]),
+        ));
+        let out = QuantizeGenerator::new(&palette(), 2).recolor(&img);
+        let value = out.get_pixel(0, 0).0[0];
+        assert!(value > 64 && value < 192, "got {}", value);
+    }
+}
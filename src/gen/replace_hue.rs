@@ -0,0 +1,237 @@
+use crate::color_item;
+use crate::utils::lch;
+use eframe::egui::{self, RichText};
+use std::sync::mpsc;
+use std::thread;
+
+#[derive(Clone, Copy)]
+struct HueParams {
+    // degrees 0-360
+    source: f32,
+    tolerance: f32,
+    // pixels less saturated than this are left alone, grays have no hue
+    min_saturation: f32,
+    // target color as (C, h) in LCh
+    target: (f64, f64),
+}
+
+/// HSV hue in degrees and saturation 0-1
+fn hue_saturation(r: u8, g: u8, b: u8) -> (f32, f32) {
+    let (r, g, b) = (r as f32 / 255f32, g as f32 / 255f32, b as f32 / 255f32);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta <= 0f32 {
+        return (0f32, 0f32);
+    }
+    let hue = if max == r {
+        60f32 * ((g - b) / delta).rem_euclid(6f32)
+    } else if max == g {
+        60f32 * ((b - r) / delta + 2f32)
+    } else {
+        60f32 * ((r - g) / delta + 4f32)
+    };
+    return (hue, delta / max);
+}
+
+/// pixel moved toward the target hue and chroma with its lightness kept,
+/// fully inside the tolerance and fading out at its edge
+fn replace_hue(r: u8, g: u8, b: u8, params: &HueParams) -> (u8, u8, u8) {
+    let (hue, saturation) = hue_saturation(r, g, b);
+    if saturation < params.min_saturation {
+        return (r, g, b);
+    }
+    let distance = (hue - params.source).rem_euclid(360f32);
+    let distance = distance.min(360f32 - distance);
+    if distance > params.tolerance {
+        return (r, g, b);
+    }
+    // full strength in the inner half of the range
+    let weight = if params.tolerance <= 0f32 {
+        1f64
+    } else {
+        (2f64 * (1f64 - (distance / params.tolerance) as f64)).min(1f64)
+    };
+    let (l, a, lab_b) = lch::rgb_to_lab(r, g, b);
+    let (_, ta, tb) = lch::lch_to_lab(l, params.target.0, params.target.1);
+    let (mut na, mut nb) = (a + (ta - a) * weight, lab_b + (tb - lab_b) * weight);
+    // give up chroma rather than lightness when the target does not fit at this lightness
+    if !lch::in_gamut(l, na, nb) {
+        let (mut low, mut high) = (0f64, 1f64);
+        for _ in 0..16 {
+            let mid = (low + high) / 2f64;
+            if lch::in_gamut(l, na * mid, nb * mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        na *= low;
+        nb *= low;
+    }
+    return lch::lab_to_rgb(l, na, nb);
+}
+
+pub struct ReplaceHue {
+    source: f32,
+    tolerance: f32,
+    min_saturation: f32,
+    target: usize,
+    // manage thread
+    hthread: Option<thread::JoinHandle<()>>,
+    channel: Option<mpsc::Receiver<image::DynamicImage>>,
+}
+
+impl ReplaceHue {
+    pub fn new() -> Self {
+        Self {
+            source: 0f32,
+            tolerance: 20f32,
+            min_saturation: 0.15,
+            target: 0,
+            hthread: None,
+            channel: None,
+        }
+    }
+    // hue slider with the hue itself as its track
+    fn hue_strip(ui: &mut egui::Ui, source: f32, tolerance: f32) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(192f32, 8f32), egui::Sense::hover());
+        let steps = 96;
+        let step = rect.width() / steps as f32;
+        for i in 0..steps {
+            let hue = i as f32 / steps as f32 * 360f32;
+            let color = color_item::ColorItem::from_hsv(hue, 1f32, 1f32, "");
+            let distance = (hue - source).rem_euclid(360f32);
+            let inside = distance.min(360f32 - distance) <= tolerance;
+            let x = rect.left() + i as f32 * step;
+            let cell = egui::Rect::from_min_max(
+                egui::pos2(x, rect.top()),
+                egui::pos2(x + step + 0.5, rect.bottom()),
+            );
+            let fill = if inside {
+                color.to_color32()
+            } else {
+                color.to_color32().gamma_multiply(0.25)
+            };
+            ui.painter().rect_filled(cell, 0f32, fill);
+        }
+    }
+}
+
+impl super::Generate for ReplaceHue {
+    fn get_name(&self) -> String {
+        return "\u{eae6} Replace Hue".into();
+    }
+    fn paint_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &Vec<color_item::ColorItem>,
+        buffer: &image::DynamicImage,
+    ) -> Option<image::DynamicImage> {
+        if colors.is_empty() {
+            ui.label("Need at least 1 color.");
+            return None;
+        }
+        self.target = self.target.min(colors.len() - 1);
+        ui.label("Recolor one hue range of the current image, lightness is kept.");
+        ui.horizontal(|ui| {
+            ui.label("\u{f0e80} Hue:");
+            ui.add(egui::Slider::new(&mut self.source, 0f32..=360f32).suffix("°"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0d1e} Tolerance:");
+            ui.add(egui::Slider::new(&mut self.tolerance, 0f32..=180f32).suffix("°"));
+        });
+        Self::hue_strip(ui, self.source, self.tolerance);
+        ui.horizontal(|ui| {
+            ui.label("\u{f0e80} Min Saturation:");
+            ui.add(egui::Slider::new(&mut self.min_saturation, 0f32..=1f32));
+        });
+        ui.horizontal(|ui| {
+            ui.label("\u{f0766} Target:");
+            egui::ComboBox::from_id_salt("replace_hue_target")
+                .selected_text(
+                    RichText::new(crate::utils::resized_str(&colors[self.target].name, 16))
+                        .color(colors[self.target].get_full_value_color32()),
+                )
+                .show_ui(ui, |ui| {
+                    for (i, color) in colors.iter().enumerate() {
+                        ui.selectable_value(
+                            &mut self.target,
+                            i,
+                            RichText::new(crate::utils::resized_str(&color.name, 16))
+                                .color(color.get_full_value_color32()),
+                        );
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            if ui.button("\u{f0674} Generate").clicked() && self.hthread.is_none() {
+                let target = &colors[self.target];
+                let (l, a, b) = target.get_lab();
+                let (_, c, h) = lch::lab_to_lch(l, a, b);
+                let params = HueParams {
+                    source: self.source,
+                    tolerance: self.tolerance,
+                    min_saturation: self.min_saturation,
+                    target: (c, h),
+                };
+                let img = buffer.clone();
+                let (tx, rx) = mpsc::channel();
+                self.channel = Some(rx);
+                self.hthread = Some(thread::spawn(move || {
+                    let mut buffer = img.to_rgba8();
+                    for pixel in buffer.pixels_mut() {
+                        let [r, g, b, _] = pixel.0;
+                        let (r, g, b) = replace_hue(r, g, b, &params);
+                        pixel.0[0] = r;
+                        pixel.0[1] = g;
+                        pixel.0[2] = b;
+                    }
+                    tx.send(image::DynamicImage::ImageRgba8(buffer)).unwrap();
+                }));
+            }
+            if self.hthread.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(hth) = &self.hthread {
+            if hth.is_finished() {
+                let img = self.channel.take().and_then(|rx| rx.recv().ok());
+                self.hthread = None;
+                return img;
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_blue() -> HueParams {
+        let (l, a, b) = lch::rgb_to_lab(0, 0, 255);
+        let (_, c, h) = lch::lab_to_lch(l, a, b);
+        return HueParams {
+            source: 0f32,
+            tolerance: 30f32,
+            min_saturation: 0.2,
+            target: (c, h),
+        };
+    }
+
+    #[test]
+    fn test_replace_hue() {
+        let params = to_blue();
+        // red turns blue-ish and keeps its lightness
+        let (r, g, b) = replace_hue(200, 30, 30, &params);
+        assert!(b > r && b > g);
+        let before = lch::rgb_to_lab(200, 30, 30).0;
+        let after = lch::rgb_to_lab(r, g, b).0;
+        assert!((before - after).abs() < 3.0);
+        // other hues and grays stay as they are
+        assert_eq!(replace_hue(30, 200, 30, &params), (30, 200, 30));
+        assert_eq!(replace_hue(128, 120, 120, &params), (128, 120, 120));
+    }
+}
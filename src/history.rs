@@ -0,0 +1,101 @@
+// undo and redo of the color list, whole-list snapshots
+
+use crate::color_item::ColorItem;
+
+// the oldest snapshots are dropped beyond this
+const MAX_SNAPSHOTS: usize = 50;
+
+// what the change was called, and the colors on the other side of it
+type Snapshot = (String, Vec<ColorItem>);
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    /// remember the colors from before a change, anything undone so far can no longer be redone
+    pub fn push(&mut self, label: &str, before: Vec<ColorItem>) {
+        self.redo.clear();
+        self.undo.push((label.to_string(), before));
+        if self.undo.len() > MAX_SNAPSHOTS {
+            self.undo.remove(0);
+        }
+    }
+    /// name of the change undo would revert
+    pub fn undo_label(&self) -> Option<&str> {
+        return self.undo.last().map(|(label, _)| label.as_str());
+    }
+    /// name of the change redo would apply again
+    pub fn redo_label(&self) -> Option<&str> {
+        return self.redo.last().map(|(label, _)| label.as_str());
+    }
+    /// put back the colors from before the last change, returns its name
+    pub fn undo(&mut self, colors: &mut Vec<ColorItem>) -> Option<String> {
+        let (label, before) = self.undo.pop()?;
+        let after = std::mem::replace(colors, before);
+        self.redo.push((label.clone(), after));
+        return Some(label);
+    }
+    /// apply the last undone change again, returns its name
+    pub fn redo(&mut self, colors: &mut Vec<ColorItem>) -> Option<String> {
+        let (label, after) = self.redo.pop()?;
+        let before = std::mem::replace(colors, after);
+        self.undo.push((label.clone(), before));
+        return Some(label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette(n: u8) -> Vec<ColorItem> {
+        return (0..n).map(|i| ColorItem::new(&i.to_string(), i, i, i)).collect();
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = History::default();
+        let mut colors = palette(1);
+        assert_eq!(history.undo(&mut colors), None);
+
+        history.push("Add", colors.clone());
+        colors = palette(2);
+        history.push("Clear", colors.clone());
+        colors.clear();
+
+        assert_eq!(history.undo(&mut colors).as_deref(), Some("Clear"));
+        assert_eq!(colors, palette(2));
+        assert_eq!(history.redo_label(), Some("Clear"));
+        assert_eq!(history.undo(&mut colors).as_deref(), Some("Add"));
+        assert_eq!(colors, palette(1));
+        assert_eq!(history.undo_label(), None);
+
+        assert_eq!(history.redo(&mut colors).as_deref(), Some("Add"));
+        assert_eq!(colors, palette(2));
+        assert_eq!(history.undo_label(), Some("Add"));
+
+        // a new change after an undo drops the redo side
+        history.push("Reverse", colors.clone());
+        assert_eq!(history.redo_label(), None);
+        assert_eq!(history.redo(&mut colors), None);
+    }
+
+    #[test]
+    fn test_capped() {
+        let mut history = History::default();
+        let mut colors = Vec::new();
+        for i in 0..(MAX_SNAPSHOTS + 10) {
+            history.push(&i.to_string(), palette(i as u8));
+        }
+        let mut undone = 0;
+        while history.undo(&mut colors).is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_SNAPSHOTS);
+        // the oldest ones went first
+        assert_eq!(colors, palette(10));
+    }
+}
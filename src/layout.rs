@@ -0,0 +1,184 @@
+// dock layouts as stored in the settings, only the open tabs and how the splits divide the space
+
+use egui_dock::{DockState, Node, NodeIndex, SurfaceIndex, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::mainwindow::Tabs;
+
+/// a dock layout that can be saved and rebuilt, the rest of the dock state is laid out again on load
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    /// the docked tabs, none when every tab is closed or floating
+    pub main: Option<Pane>,
+    /// tabs of the floating windows, one list per window
+    pub windows: Vec<Vec<Tabs>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Pane {
+    Tabs(Vec<Tabs>),
+    /// side by side, `fraction` of the width goes to `first` on the left
+    Horizontal {
+        fraction: f32,
+        first: Box<Pane>,
+        second: Box<Pane>,
+    },
+    /// stacked, `fraction` of the height goes to `first` on top
+    Vertical {
+        fraction: f32,
+        first: Box<Pane>,
+        second: Box<Pane>,
+    },
+}
+
+impl Pane {
+    fn read(tree: &Tree<Tabs>, index: NodeIndex) -> Option<Self> {
+        let (vertical, fraction) = match tree.iter().nth(index.0)? {
+            Node::Leaf { tabs, .. } if !tabs.is_empty() => return Some(Pane::Tabs(tabs.clone())),
+            Node::Horizontal { fraction, .. } => (false, *fraction),
+            Node::Vertical { fraction, .. } => (true, *fraction),
+            _ => return None,
+        };
+        let first = Self::read(tree, index.left());
+        let second = Self::read(tree, index.right());
+        return Self::join(vertical, fraction, first, second);
+    }
+    // a split with one side gone is just the other side
+    fn join(vertical: bool, fraction: f32, first: Option<Self>, second: Option<Self>) -> Option<Self> {
+        let (first, second) = match (first, second) {
+            (Some(first), Some(second)) => (Box::new(first), Box::new(second)),
+            (first, second) => return first.or(second),
+        };
+        // files written by hand or by another version may hold anything
+        let fraction = if fraction.is_finite() { fraction.clamp(0f32, 1f32) } else { 0.5 };
+        return Some(if vertical {
+            Pane::Vertical { fraction, first, second }
+        } else {
+            Pane::Horizontal { fraction, first, second }
+        });
+    }
+    // the same pane without empty tab lists, none when nothing is left
+    fn pruned(self) -> Option<Self> {
+        return match self {
+            Pane::Tabs(tabs) => (!tabs.is_empty()).then_some(Pane::Tabs(tabs)),
+            Pane::Horizontal { fraction, first, second } => {
+                Self::join(false, fraction, first.pruned(), second.pruned())
+            }
+            Pane::Vertical { fraction, first, second } => {
+                Self::join(true, fraction, first.pruned(), second.pruned())
+            }
+        };
+    }
+    // tabs of the top left leaf, the ones a fresh node starts with
+    fn first_tabs(&self) -> Vec<Tabs> {
+        return match self {
+            Pane::Tabs(tabs) => tabs.clone(),
+            Pane::Horizontal { first, .. } | Pane::Vertical { first, .. } => first.first_tabs(),
+        };
+    }
+    // `index` holds the tabs of `first_tabs` so far, split it until it matches
+    fn build(&self, tree: &mut Tree<Tabs>, index: NodeIndex) {
+        let (vertical, fraction, first, second) = match self {
+            Pane::Tabs(_) => return,
+            Pane::Horizontal { fraction, first, second } => (false, *fraction, first, second),
+            Pane::Vertical { fraction, first, second } => (true, *fraction, first, second),
+        };
+        // the old tabs stay in the left or top child, the new ones go to the other
+        let [_, _] = if vertical {
+            tree.split_below(index, fraction, second.first_tabs())
+        } else {
+            tree.split_right(index, fraction, second.first_tabs())
+        };
+        first.build(tree, index.left());
+        second.build(tree, index.right());
+    }
+}
+
+impl Layout {
+    pub fn from_dock(dock: &DockState<Tabs>) -> Self {
+        let mut windows: Vec<(SurfaceIndex, Vec<Tabs>)> = Vec::new();
+        for ((surface, _), tab) in dock.iter_all_tabs() {
+            if surface == SurfaceIndex::main() {
+                continue;
+            }
+            match windows.iter_mut().find(|(index, _)| *index == surface) {
+                Some((_, tabs)) => tabs.push(*tab),
+                None => windows.push((surface, vec![*tab])),
+            }
+        }
+        return Self {
+            main: Pane::read(dock.main_surface(), NodeIndex::root()),
+            windows: windows.into_iter().map(|(_, tabs)| tabs).collect(),
+        };
+    }
+    /// the dock state this layout describes, none when it has no docked tabs
+    pub fn to_dock(&self) -> Option<DockState<Tabs>> {
+        let main = self.main.clone()?.pruned()?;
+        let mut dock = DockState::new(main.first_tabs());
+        main.build(dock.main_surface_mut(), NodeIndex::root());
+        for tabs in self.windows.iter().filter(|tabs| !tabs.is_empty()) {
+            dock.add_window(tabs.clone());
+        }
+        return Some(dock);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut dock = DockState::new(vec![Tabs::Preview]);
+        let tree = dock.main_surface_mut();
+        let [_, _] = tree.split_right(NodeIndex::root(), 0.3, vec![Tabs::Add, Tabs::Gen]);
+        let [_, _] = tree.split_below(NodeIndex::root().left(), 0.6, vec![Tabs::Colors]);
+        dock.add_window(vec![Tabs::Heatmap]);
+        let layout = Layout::from_dock(&dock);
+        assert_eq!(
+            layout.main,
+            Some(Pane::Horizontal {
+                fraction: 0.3,
+                first: Box::new(Pane::Vertical {
+                    fraction: 0.6,
+                    first: Box::new(Pane::Tabs(vec![Tabs::Preview])),
+                    second: Box::new(Pane::Tabs(vec![Tabs::Colors])),
+                }),
+                second: Box::new(Pane::Tabs(vec![Tabs::Add, Tabs::Gen])),
+            })
+        );
+        assert_eq!(layout.windows, vec![vec![Tabs::Heatmap]]);
+        let json = serde_json::to_string(&layout).unwrap();
+        let rebuilt = serde_json::from_str::<Layout>(&json).unwrap().to_dock().unwrap();
+        assert_eq!(Layout::from_dock(&rebuilt), layout);
+    }
+
+    #[test]
+    fn test_prune() {
+        // an empty side of a split and a fraction that is not a number
+        let layout = Layout {
+            main: Some(Pane::Vertical {
+                fraction: 0.5,
+                first: Box::new(Pane::Tabs(Vec::new())),
+                second: Box::new(Pane::Horizontal {
+                    fraction: f32::NAN,
+                    first: Box::new(Pane::Tabs(vec![Tabs::Colors])),
+                    second: Box::new(Pane::Tabs(vec![Tabs::Preview])),
+                }),
+            }),
+            windows: vec![Vec::new()],
+        };
+        let rebuilt = Layout::from_dock(&layout.to_dock().unwrap());
+        assert_eq!(
+            rebuilt.main,
+            Some(Pane::Horizontal {
+                fraction: 0.5,
+                first: Box::new(Pane::Tabs(vec![Tabs::Colors])),
+                second: Box::new(Pane::Tabs(vec![Tabs::Preview])),
+            })
+        );
+        assert!(rebuilt.windows.is_empty());
+        assert!(Layout::default().to_dock().is_none());
+    }
+}
@@ -1,15 +1,21 @@
 #![windows_subsystem = "windows"]
 mod add;
+mod analysis;
+mod batch;
 mod color_item;
 mod gen;
+mod history;
+mod layout;
 mod mainwindow;
+mod recovery;
+mod settings;
 mod utils;
 use eframe::egui;
 use eframe::egui::ViewportBuilder;
 use std::sync::Arc;
 
 include_flate::flate!(static NERDFONTS: [u8] from "assets/SymbolsNF.ttf");
-include_flate::flate!(static HACKFONT: [u8] from "assets/HackNerdFont-Regular.ttf");
+include_flate::flate!(pub static HACKFONT: [u8] from "assets/HackNerdFont-Regular.ttf");
 include_flate::flate!(static ICON: [u8] from "assets/colorlook.png");
 
 fn main() {
@@ -27,7 +33,10 @@ fn main() {
         viewport: ViewportBuilder {
             title: Some("ColorLook".to_string()),
             position: Some(egui::pos2(0f32, 0f32)),
-            inner_size: Some(egui::Vec2::new(1200f32, 700f32)),
+            inner_size: Some(settings::with(|s| s.window_size).map_or(
+                egui::Vec2::new(1200f32, 700f32),
+                |[w, h]| egui::Vec2::new(w, h),
+            )),
             icon: Some(Arc::new(icon_data)),
             ..Default::default()
         },
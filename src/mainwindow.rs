@@ -1,6 +1,9 @@
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 
 use crate::color_item;
+use crate::recovery;
+use crate::settings;
 
 const MARGIN: f32 = 40f32;
 const TEXTURE_NAME: &str = "bufferimg";
@@ -14,6 +17,8 @@ lazy_static::lazy_static! {
         (Tabs::Add, "\u{ea60} Add".into()),
         (Tabs::Gen, "\u{f0674} Generate".into()),
         (Tabs::Preview, "\u{f1205} Preview".into()),
+        (Tabs::Scatter, "\u{f0547} Hue Scatter".into()),
+        (Tabs::Heatmap, "\u{f0ce5} \u{0394}E Heatmap".into()),
      ];
 }
 
@@ -22,6 +27,33 @@ pub struct MainWindow {
     file_dialog: FileDialog,
     tab_viewer: MainWindowTabViewer,
     dock_tree: egui_dock::DockState<Tabs>,
+    array_name: String,
+    workspace_name: String,
+    show_about: bool,
+    // snapshots of the color list for undo and redo
+    history: crate::history::History,
+    // open Find Duplicates dialog, the chosen keeper of each (r, g, b) group
+    duplicates: Option<std::collections::HashMap<(u8, u8, u8), usize>>,
+    // describes how the current image was generated, written on save
+    image_metadata: Vec<(String, String)>,
+    // (image, metadata, path) before the last generator result
+    image_backup: Option<(image::DynamicImage, Vec<(String, String)>, Option<std::path::PathBuf>)>,
+    vibrance: f32,
+    // CIEDE2000 distance under which a color counts as a duplicate of an earlier one
+    dedup_threshold: f64,
+    // jpeg quality used on save, 0 to 100
+    image_quality: u8,
+    // file the working image came from, kept for crash recovery
+    image_path: Option<std::path::PathBuf>,
+    // left over from a crashed session, waiting for the user to restore or discard
+    pending_recovery: Option<recovery::Recovery>,
+    // what was written to the recovery file last, and when
+    autosaved: recovery::Recovery,
+    last_autosave: f64,
+    // folder of images being extracted in the background
+    batch: Option<crate::batch::Batch>,
+    // (r, g, b) of the colors the status bar metrics were computed from, mean ΔE, a*b* hull area
+    palette_metrics: Option<(Vec<(u8, u8, u8)>, f64, f64)>,
 }
 
 pub struct MainWindowTabViewer {
@@ -31,14 +63,26 @@ pub struct MainWindowTabViewer {
     pub add_component: Option<Box<dyn crate::add::AddColor>>,
     pub gen_component: Option<Box<dyn crate::gen::Generate>>,
     pub ui_msg: Option<TabMsg>,
+    // display order of a sort that is only previewed
+    pub sort_preview: Option<Vec<usize>>,
+    // colors picked in the Colors list, positions as shown
+    pub selection: color_item::Selection,
+    // color vision deficiency simulated beside every color in the Colors tab
+    pub cvd: Option<color_item::cvd::CvdKind>,
+    // blend of the colors in sequence above the Colors list, none when hidden
+    pub gradient: Option<crate::gen::Interpolation>,
+    // (r, g, b) of the colors the ΔE heatmap was computed from, and its matrix
+    heatmap: Option<(Vec<(u8, u8, u8)>, Vec<Vec<f64>>)>,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Tabs {
     Colors,
     Add,
     Gen,
     Preview,
+    Scatter,
+    Heatmap,
 }
 
 impl MainWindowTabViewer {
@@ -50,7 +94,34 @@ impl MainWindowTabViewer {
             add_component: None,
             gen_component: None,
             ui_msg: None,
+            sort_preview: None,
+            selection: color_item::Selection::default(),
+            cvd: None,
+            gradient: None,
+            heatmap: None,
+        };
+    }
+    /// sort by `compare` starting from the shown order, as a preview when the setting is on
+    pub fn sort_colors<F>(&mut self, compare: F)
+    where
+        F: Fn(&color_item::ColorItem, &color_item::ColorItem) -> std::cmp::Ordering,
+    {
+        let colors = &self.colors;
+        let mut order = match self.sort_preview.take() {
+            Some(order) if order.len() == colors.len() => order,
+            _ => (0..colors.len()).collect(),
         };
+        order.sort_by(|&a, &b| compare(&colors[a], &colors[b]));
+        self.set_order(order);
+    }
+    pub fn set_order(&mut self, order: Vec<usize>) {
+        // positions as shown change meaning
+        self.selection.clear();
+        if settings::with(|s| s.preview_sorts) {
+            self.sort_preview = Some(order);
+        } else {
+            color_item::apply_order(&mut self.colors, &order);
+        }
     }
     pub fn update_texture(&mut self, ctx: &egui::Context) {
         let manager = ctx.tex_manager();
@@ -62,10 +133,16 @@ impl MainWindowTabViewer {
         let pixels = rgba.as_flat_samples();
         let colorimg = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
 
+        // keep small images (LUTs, pixel art) crisp when they are scaled up
+        let options = if settings::with(|s| s.pixel_perfect) {
+            egui::TextureOptions::NEAREST
+        } else {
+            egui::TextureOptions::default()
+        };
         self.texture_id = Some(manager.write().alloc(
             TEXTURE_NAME.to_string(),
             colorimg.into(),
-            egui::TextureOptions::default(),
+            options,
         ));
     }
     pub fn ensure_texture(&mut self, ctx: &egui::Context) {
@@ -89,6 +166,8 @@ impl egui_dock::TabViewer for MainWindowTabViewer {
                 None => "\u{f0674} Generate".into(),
             },
             Tabs::Preview => "\u{eb28} Preview".into(),
+            Tabs::Scatter => "\u{f0547} Hue Scatter".into(),
+            Tabs::Heatmap => "\u{f0ce5} \u{0394}E Heatmap".into(),
         }
     }
 
@@ -100,15 +179,75 @@ impl egui_dock::TabViewer for MainWindowTabViewer {
         match tab {
             Tabs::Colors => {
                 ui.vertical(|ui| {
-                    color_item::draw_color_items(ui, &mut self.colors);
+                    if self.sort_preview.is_some() {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("\u{f1385} Sorted preview").strong());
+                            if ui
+                                .button("\u{f012c} Keep Sorted")
+                                .on_hover_text("store the colors in this order")
+                                .clicked()
+                            {
+                                if let Some(order) = self.sort_preview.take() {
+                                    let before = self.colors.clone();
+                                    color_item::apply_order(&mut self.colors, &order);
+                                    self.ui_msg = Some(TabMsg::Edit("Sort", before));
+                                }
+                            }
+                            if ui.button("\u{f0156} Cancel").clicked() {
+                                self.sort_preview = None;
+                                self.selection.clear();
+                            }
+                        });
+                        ui.separator();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("\u{f0208} Simulate:");
+                        ui.selectable_value(&mut self.cvd, None, "Off");
+                        for kind in color_item::cvd::CvdKind::ALL {
+                            ui.selectable_value(&mut self.cvd, Some(kind), kind.name());
+                        }
+                    })
+                    .response
+                    .on_hover_text("show how each color looks with a color vision deficiency");
+                    ui.horizontal(|ui| {
+                        ui.label("\u{f0b38} Gradient:");
+                        ui.selectable_value(&mut self.gradient, None, "Off");
+                        ui.selectable_value(&mut self.gradient, Some(crate::gen::Interpolation::RGB), "RGB");
+                        ui.selectable_value(&mut self.gradient, Some(crate::gen::Interpolation::Lab), "Lab")
+                            .on_hover_text("blend through CIE Lab, like the Line generator option");
+                    })
+                    .response
+                    .on_hover_text("preview the colors blended in sequence");
+                    if let Some(interpolation) = self.gradient {
+                        // in the order shown, a sort preview included
+                        let shown: Vec<color_item::ColorItem> = match &self.sort_preview {
+                            Some(order) if order.len() == self.colors.len() => {
+                                order.iter().map(|&i| self.colors[i].clone()).collect()
+                            }
+                            _ => self.colors.clone(),
+                        };
+                        crate::gen::gradient_strip(ui, &shown, interpolation);
+                    }
+                    if let Some((label, before)) = color_item::draw_color_items(
+                        ui,
+                        &mut self.colors,
+                        &mut self.sort_preview,
+                        &mut self.selection,
+                        self.cvd,
+                    ) {
+                        self.ui_msg = Some(TabMsg::Edit(label, before));
+                    }
                 });
             }
             Tabs::Add => {
                 ui.vertical(|ui| match self.add_component {
                     Some(ref mut component) => {
-                        if let Some(color) = component.paint_ui(ui, &self.image) {
+                        if let Some(color) = component.paint_ui(ui, &self.image, &self.colors) {
                             self.ui_msg = Some(TabMsg::Add(color));
                         }
+                        if let Some(error) = component.take_error() {
+                            self.ui_msg = Some(TabMsg::Error(error));
+                        }
                     }
                     None => {
                         ui.label("\u{f08a4} No Component Selected.");
@@ -118,8 +257,8 @@ impl egui_dock::TabViewer for MainWindowTabViewer {
             Tabs::Gen => {
                 ui.vertical(|ui| match self.gen_component {
                     Some(ref mut component) => {
-                        if let Some(img) = component.paint_ui(ui, &self.colors) {
-                            self.ui_msg = Some(TabMsg::Gen(img));
+                        if let Some(img) = component.paint_ui(ui, &self.colors, &self.image) {
+                            self.ui_msg = Some(TabMsg::Gen(img, component.metadata()));
                         }
                     }
                     None => {
@@ -138,16 +277,42 @@ impl egui_dock::TabViewer for MainWindowTabViewer {
                     );
                 }
             }
+            Tabs::Scatter => {
+                crate::analysis::scatter::draw_scatter(ui, &self.colors);
+            }
+            Tabs::Heatmap => {
+                // the matrix is quadratic in the palette size, only redo it when a color changed
+                let colors = &self.colors;
+                let stale = self.heatmap.as_ref().is_none_or(|(rgb, _)| {
+                    !rgb.iter().copied().eq(colors.iter().map(|c| (c.r, c.g, c.b)))
+                });
+                if stale {
+                    self.heatmap = Some((
+                        colors.iter().map(|c| (c.r, c.g, c.b)).collect(),
+                        crate::analysis::heatmap::delta_e_matrix(colors),
+                    ));
+                }
+                let (_, matrix) = self.heatmap.as_ref().unwrap();
+                crate::analysis::heatmap::draw_heatmap(ui, colors, matrix);
+            }
         }
     }
 
     fn allowed_in_windows(&self, _tab: &mut Self::Tab) -> bool {
         true
     }
+
+    fn scroll_bars(&self, tab: &Self::Tab) -> [bool; 2] {
+        // the Colors list scrolls itself to only build the visible rows
+        match tab {
+            Tabs::Colors => [false, false],
+            _ => [true, true],
+        }
+    }
 }
 
 impl MainWindow {
-    pub fn new() -> Self {
+    fn default_layout() -> egui_dock::DockState<Tabs> {
         let mut tree = egui_dock::DockState::new(vec![Tabs::Preview]);
         let [_, b] = tree.main_surface_mut().split_left(
             egui_dock::NodeIndex::root(),
@@ -157,23 +322,413 @@ impl MainWindow {
         let [_, _] = tree
             .main_surface_mut()
             .split_left(b, 0.5, vec![Tabs::Colors]);
+        return tree;
+    }
+    pub fn new() -> Self {
+        // the layout of the last run, unless no tab was left docked
+        let tree = settings::with(|s| s.layout.as_ref().and_then(|layout| layout.to_dock()))
+            .unwrap_or_else(Self::default_layout);
 
+        let pending_recovery = recovery::load()
+            .unwrap_or_else(|msg| {
+                println!("Error: {}", msg);
+                None
+            })
+            .filter(|r| !r.colors.is_empty() || r.image_path.is_some());
+        let mut tab_viewer = MainWindowTabViewer::new();
+        // a recovered palette always wins over the color of the day
+        if pending_recovery.is_none() && settings::with(|s| s.color_of_the_day) {
+            tab_viewer
+                .colors
+                .push(crate::utils::color_of_the_day(crate::utils::today()));
+        }
         return Self {
             toasts: egui_toast::Toasts::new()
                 .anchor(egui::Align2::LEFT_BOTTOM, (MARGIN, -MARGIN))
                 .direction(egui::Direction::BottomUp),
             file_dialog: FileDialog::None,
-            tab_viewer: MainWindowTabViewer::new(),
+            tab_viewer,
             dock_tree: tree,
+            array_name: crate::utils::export::DEFAULT_ARRAY_NAME.into(),
+            workspace_name: String::new(),
+            show_about: false,
+            history: crate::history::History::default(),
+            duplicates: None,
+            image_metadata: Vec::new(),
+            image_backup: None,
+            vibrance: 1.2,
+            dedup_threshold: crate::analysis::heatmap::NEAR_DUPLICATE_DELTA_E,
+            image_quality: 90,
+            image_path: None,
+            pending_recovery,
+            autosaved: recovery::Recovery::default(),
+            last_autosave: 0f64,
+            batch: None,
+            palette_metrics: None,
+        };
+    }
+    // the dock layout and window size the next run starts with, written along with the settings kept unsaved so far
+    fn remember_layout(&self, ctx: &egui::Context) {
+        let size = ctx.input(|i| i.viewport().inner_rect).map(|r| [r.width(), r.height()]);
+        let mut settings = settings::get();
+        settings.layout = Some(crate::layout::Layout::from_dock(&self.dock_tree));
+        if size.is_some() {
+            settings.window_size = size;
+        }
+        settings::set(settings);
+    }
+    // make the image at `path` the working image, false when it could not be read
+    fn load_image_file(&mut self, ctx: &egui::Context, path: &std::path::Path) -> bool {
+        match crate::utils::load_image(path) {
+            Ok((img, icc)) => {
+                self.tab_viewer.image = img;
+                self.image_metadata.clear();
+                self.image_path = Some(path.to_path_buf());
+                self.image_backup = None;
+                self.tab_viewer.update_texture(ctx);
+                self.toasts.add(egui_toast::Toast {
+                    kind: egui_toast::ToastKind::Success,
+                    text: format!("Loaded Image from {}", path.display()).into(),
+                    options: egui_toast::ToastOptions::default()
+                        .duration_in_seconds(2f64)
+                        .show_progress(true),
+                    style: egui_toast::ToastStyle::default(),
+                });
+                if let Some(icc) = icc {
+                    if !crate::utils::icc::is_srgb(&icc) {
+                        let desc = crate::utils::icc::profile_description(&icc)
+                            .unwrap_or("unknown".into());
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Warning,
+                            text: format!(
+                                "\u{f071} Image has a non-sRGB color profile ({}), colors are interpreted as sRGB",
+                                desc
+                            )
+                            .into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(5f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
+                    }
+                }
+                return true;
+            }
+            Err(e) => {
+                self.toasts.add(egui_toast::Toast {
+                    kind: egui_toast::ToastKind::Error,
+                    text: format!("\u{f071} {}", crate::utils::describe_load_error(path, &e))
+                        .into(),
+                    options: egui_toast::ToastOptions::default()
+                        .duration_in_seconds(5f64)
+                        .show_progress(true),
+                    style: egui_toast::ToastStyle::default(),
+                });
+                return false;
+            }
+        }
+    }
+    // files dropped on the window, the first image that loads becomes the working image
+    fn load_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        if dropped.is_empty() {
+            return;
+        }
+        let images: Vec<std::path::PathBuf> = dropped
+            .iter()
+            .filter_map(|file| file.path.clone())
+            .filter(|path| crate::utils::has_image_extension(path))
+            .collect();
+        let mut loaded = false;
+        for path in images.iter() {
+            if self.load_image_file(ctx, path) {
+                loaded = true;
+                break;
+            }
+        }
+        if !loaded {
+            if images.is_empty() {
+                self.toasts.add(egui_toast::Toast {
+                    kind: egui_toast::ToastKind::Warning,
+                    text: "\u{f071} Dropped files are not images (supported: png, jpeg, webp, bmp)"
+                        .into(),
+                    options: egui_toast::ToastOptions::default()
+                        .duration_in_seconds(5f64)
+                        .show_progress(true),
+                    style: egui_toast::ToastStyle::default(),
+                });
+            }
+            return;
+        }
+        if dropped.len() > 1 {
+            self.toasts.add(egui_toast::Toast {
+                kind: egui_toast::ToastKind::Warning,
+                text: format!(
+                    "\u{f071} Loaded one image, ignored {} other dropped file(s)",
+                    dropped.len() - 1
+                )
+                .into(),
+                options: egui_toast::ToastOptions::default()
+                    .duration_in_seconds(5f64)
+                    .show_progress(true),
+                style: egui_toast::ToastStyle::default(),
+            });
+        }
+    }
+    // write the working palette to the recovery file every few seconds when it changed
+    fn autosave(&mut self, ctx: &egui::Context) {
+        let interval = settings::with(|s| s.autosave_interval);
+        if interval == 0 {
+            return;
+        }
+        // wake up for the next write even when nothing is repainting
+        ctx.request_repaint_after(std::time::Duration::from_secs(interval as u64));
+        let now = ctx.input(|i| i.time);
+        if now - self.last_autosave < interval as f64 {
+            return;
+        }
+        self.last_autosave = now;
+        let state = recovery::Recovery {
+            colors: self.tab_viewer.colors.clone(),
+            image_path: self.image_path.clone(),
+        };
+        if state == self.autosaved {
+            return;
+        }
+        if let Err(msg) = recovery::save(&state) {
+            println!("Error: {}", msg);
+        }
+        self.autosaved = state;
+    }
+    // collect the results of the batch run, and sum it up once it is over
+    fn poll_batch(&mut self, ctx: &egui::Context) {
+        let Some(batch) = &mut self.batch else {
+            return;
+        };
+        batch.poll();
+        if !batch.is_finished() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        if batch.failures.is_empty() {
+            self.toasts.add(egui_toast::Toast {
+                kind: egui_toast::ToastKind::Success,
+                text: batch.summary().into(),
+                options: egui_toast::ToastOptions::default()
+                    .duration_in_seconds(2f64)
+                    .show_progress(true),
+                style: egui_toast::ToastStyle::default(),
+            });
+        } else {
+            self.toasts.add(egui_toast::Toast {
+                kind: egui_toast::ToastKind::Warning,
+                text: format!("\u{f071} {}", batch.summary()).into(),
+                options: egui_toast::ToastOptions::default()
+                    .duration_in_seconds(5f64)
+                    .show_progress(true),
+                style: egui_toast::ToastStyle::default(),
+            });
+            for (path, e) in batch.failures.iter().take(3) {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                self.toasts.add(egui_toast::Toast {
+                    kind: egui_toast::ToastKind::Error,
+                    text: format!("\u{e654} {}: {}", name, e).into(),
+                    options: egui_toast::ToastOptions::default()
+                        .duration_in_seconds(5f64)
+                        .show_progress(true),
+                    style: egui_toast::ToastStyle::default(),
+                });
+            }
+        }
+        self.batch = None;
+    }
+    // offer the palette of a session that did not exit cleanly
+    fn show_recovery(&mut self, ctx: &egui::Context) {
+        let Some(state) = &self.pending_recovery else {
+            return;
+        };
+        let mut restore = false;
+        let mut discard = false;
+        egui::Window::new("\u{f0450} Restore Session")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("ColorLook did not exit cleanly last time.");
+                ui.label(format!("\u{e22b} {} colors", state.colors.len()));
+                if let Some(path) = &state.image_path {
+                    ui.label(format!("\u{f02e9} {}", path.display()));
+                }
+                ui.horizontal(|ui| {
+                    restore = ui.button("\u{f0450} Restore").clicked();
+                    discard = ui.button("\u{f0413} Discard").clicked();
+                });
+            });
+        if restore {
+            let state = self.pending_recovery.take().unwrap();
+            self.tab_viewer.colors = state.colors;
+            if let Some(path) = state.image_path {
+                match crate::utils::load_image(&path) {
+                    Ok((img, _)) => {
+                        self.tab_viewer.image = img;
+                        self.image_metadata.clear();
+                        self.tab_viewer.update_texture(ctx);
+                        self.image_path = Some(path);
+                        self.image_backup = None;
+                    }
+                    Err(e) => {
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Warning,
+                            text: format!(
+                                "\u{f071} Cannot reload {}: {}",
+                                path.display(),
+                                crate::utils::describe_load_error(&path, &e)
+                            )
+                            .into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(5f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
+                    }
+                }
+            }
+            self.toasts.add(egui_toast::Toast {
+                kind: egui_toast::ToastKind::Success,
+                text: "Restored previous session".into(),
+                options: egui_toast::ToastOptions::default()
+                    .duration_in_seconds(2f64)
+                    .show_progress(true),
+                style: egui_toast::ToastStyle::default(),
+            });
+        } else if discard {
+            self.pending_recovery = None;
+            recovery::clear();
+        }
+    }
+    // review dialog of the Find Duplicates action
+    fn show_duplicates(&mut self, ctx: &egui::Context) {
+        let groups = duplicate_groups(&self.tab_viewer.colors);
+        let mut open = true;
+        let mut resolve = false;
+        let Some(keep) = &mut self.duplicates else {
+            return;
         };
+        egui::Window::new("\u{f0c5} Duplicates")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if groups.is_empty() {
+                    ui.label("No duplicate colors left.");
+                    return;
+                }
+                ui.label("Pick the name to keep in each group, the others are removed.");
+                egui::ScrollArea::vertical().max_height(400f32).show(ui, |ui| {
+                    for (rgb, indices) in groups.iter() {
+                        let color = &self.tab_viewer.colors[indices[0]];
+                        ui.horizontal(|ui| {
+                            let (rect, _) = ui.allocate_exact_size(
+                                egui::vec2(20f32, ui.text_style_height(&egui::TextStyle::Body)),
+                                egui::Sense::hover(),
+                            );
+                            color_item::paint_swatch(
+                                ui.painter(),
+                                rect,
+                                color_item::swatch_rounding(),
+                                color.to_color32(),
+                                egui::Stroke::new(0.5f32, egui::Color32::WHITE),
+                            );
+                            ui.label(format!("{} ({}\u{00d7})", color.get_hex(), indices.len()));
+                        });
+                        let selected = keep.entry(*rgb).or_insert(0);
+                        ui.indent(rgb, |ui| {
+                            for (n, index) in indices.iter().enumerate() {
+                                ui.radio_value(selected, n, &self.tab_viewer.colors[*index].name);
+                            }
+                        });
+                        ui.separator();
+                    }
+                });
+                if ui.button("\u{ea76} Keep Selected, Remove Others").clicked() {
+                    resolve = true;
+                }
+            });
+        if resolve {
+            let mut remove = Vec::new();
+            for (rgb, indices) in groups.iter() {
+                let selected = keep.get(rgb).cloned().unwrap_or(0);
+                for (n, index) in indices.iter().enumerate() {
+                    if n != selected {
+                        remove.push(*index);
+                    }
+                }
+            }
+            self.history.push("Remove Duplicates", self.tab_viewer.colors.clone());
+            remove.sort_unstable();
+            for index in remove.into_iter().rev() {
+                self.tab_viewer.colors.remove(index);
+            }
+            self.toasts.add(egui_toast::Toast {
+                kind: egui_toast::ToastKind::Success,
+                text: "Removed duplicate colors".into(),
+                options: egui_toast::ToastOptions::default()
+                    .duration_in_seconds(2f64)
+                    .show_progress(true),
+                style: egui_toast::ToastStyle::default(),
+            });
+            open = false;
+        }
+        if !open {
+            self.duplicates = None;
+        }
     }
 }
 
+// indices of colors sharing an exact (r, g, b), groups in order of first appearance
+fn duplicate_groups(colors: &[color_item::ColorItem]) -> Vec<((u8, u8, u8), Vec<usize>)> {
+    let mut groups: Vec<((u8, u8, u8), Vec<usize>)> = Vec::new();
+    let mut lookup: std::collections::HashMap<(u8, u8, u8), usize> = std::collections::HashMap::new();
+    for (i, color) in colors.iter().enumerate() {
+        let rgb = (color.r, color.g, color.b);
+        match lookup.get(&rgb) {
+            Some(&group) => groups[group].1.push(i),
+            None => {
+                lookup.insert(rgb, groups.len());
+                groups.push((rgb, vec![i]));
+            }
+        }
+    }
+    groups.retain(|(_, indices)| indices.len() > 1);
+    return groups;
+}
+
+// colors of a .json, .gpl or .ase palette and how many entries had to be skipped
+fn read_palette_file(path: &std::path::Path) -> Result<(Vec<color_item::ColorItem>, usize), String> {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    return match ext.as_str() {
+        "json" => serde_json::from_slice::<Vec<color_item::ColorItem>>(&data)
+            .map(|colors| (colors, 0))
+            .map_err(|e| e.to_string()),
+        "gpl" => crate::utils::gpl::parse(&String::from_utf8_lossy(&data)),
+        "ase" => crate::utils::ase::parse(&data),
+        _ => Err(format!("unsupported file type .{}", ext)),
+    };
+}
+
 #[derive(Clone, Copy)]
 pub enum MsgFile {
     Load,
+    Paste,
+    PasteAndExtract,
     Clear,
+    UndoImage,
     Save,
+    SaveSvg,
+    BatchExtract,
     Exit,
 }
 
@@ -188,8 +743,61 @@ pub enum MsgColor {
     SortByH,
     SortByS,
     SortByV,
-    Import,
+    SortByLabL,
+    SortByLabHue,
+    Import(ImportMode),
+    Undo,
+    Redo,
+    FindDuplicates,
     Export,
+    ImportHex(ImportMode),
+    ExportHex,
+    ImportAseprite(ImportMode),
+    ExportCard,
+    ExportAse,
+    ImportPalette(ImportMode),
+    Vibrance,
+    Dedup,
+    CopyRust,
+    CopyGlsl,
+    CopyCss,
+}
+
+impl MsgColor {
+    // name of the change in the undo history, none for actions that leave the list alone
+    fn history_label(&self) -> Option<&'static str> {
+        return match self {
+            MsgColor::Clear => Some("Clear"),
+            MsgColor::Reverse => Some("Reverse"),
+            MsgColor::SortByName
+            | MsgColor::SortByR
+            | MsgColor::SortByG
+            | MsgColor::SortByB
+            | MsgColor::SortByH
+            | MsgColor::SortByS
+            | MsgColor::SortByV
+            | MsgColor::SortByLabL
+            | MsgColor::SortByLabHue => Some("Sort"),
+            MsgColor::Vibrance => Some("Vibrance"),
+            MsgColor::Dedup => Some("Deduplicate"),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImportMode {
+    Append,
+    Replace,
+}
+
+#[derive(Clone)]
+pub enum MsgWorkspace {
+    Save,
+    Load(String),
+    Delete(String),
+    // back to the layout of a fresh install
+    Reset,
 }
 
 #[derive(Clone)]
@@ -197,14 +805,18 @@ pub enum Msg {
     File(MsgFile),
     Color(MsgColor),
     Add(Vec<color_item::ColorItem>),
-    Gen(image::DynamicImage),
+    Gen(image::DynamicImage, Vec<(String, String)>),
     AdjustTab(Tabs),
+    Workspace(MsgWorkspace),
 }
 
 #[derive(Clone)]
 pub enum TabMsg {
     Add(Vec<color_item::ColorItem>),
-    Gen(image::DynamicImage),
+    // the colors were changed in place, the name of the change and the colors from before it
+    Edit(&'static str, Vec<color_item::ColorItem>),
+    Error(String),
+    Gen(image::DynamicImage, Vec<(String, String)>),
 }
 
 pub enum FileDialog {
@@ -212,7 +824,16 @@ pub enum FileDialog {
     LoadImg(egui_file::FileDialog),
     SaveImg(egui_file::FileDialog),
     ExportJson(egui_file::FileDialog),
-    ImportJson(egui_file::FileDialog),
+    ImportJson(egui_file::FileDialog, ImportMode),
+    ImportHex(egui_file::FileDialog, ImportMode),
+    ExportHex(egui_file::FileDialog),
+    ImportAseprite(egui_file::FileDialog, ImportMode),
+    ExportCard(egui_file::FileDialog),
+    ExportAse(egui_file::FileDialog),
+    ImportPalette(egui_file::FileDialog, ImportMode),
+    // with the document, made when the export was asked for
+    SaveSvg(egui_file::FileDialog, String),
+    BatchFolder(egui_file::FileDialog),
 }
 impl eframe::App for MainWindow {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -227,10 +848,14 @@ impl eframe::App for MainWindow {
         let saveshortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::S);
         let clearshortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::C);
         let exitshortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Q);
+        let undoshortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
+        let redoshortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Y);
         let openshortcuttext = ctx.format_shortcut(&openshortcut);
         let saveshortcuttext = ctx.format_shortcut(&saveshortcut);
         let clearshortcuttext = ctx.format_shortcut(&clearshortcut);
         let exitshortcuttext = ctx.format_shortcut(&exitshortcut);
+        let undoshortcuttext = ctx.format_shortcut(&undoshortcut);
+        let redoshortcuttext = ctx.format_shortcut(&redoshortcut);
         if ctx.input(|is| is.clone().consume_shortcut(&openshortcut)) {
             ui_msg = Some(Msg::File(MsgFile::Load));
         }
@@ -243,6 +868,50 @@ impl eframe::App for MainWindow {
         if ctx.input(|is| is.clone().consume_shortcut(&exitshortcut)) {
             ui_msg = Some(Msg::File(MsgFile::Exit));
         }
+        // text fields keep their own undo
+        if !ctx.wants_keyboard_input() {
+            if ctx.input(|is| is.clone().consume_shortcut(&undoshortcut)) {
+                ui_msg = Some(Msg::Color(MsgColor::Undo));
+            }
+            if ctx.input(|is| is.clone().consume_shortcut(&redoshortcut)) {
+                ui_msg = Some(Msg::Color(MsgColor::Redo));
+            }
+        }
+        // both metrics are quadratic or worse in the palette size, only redo them when a color changed
+        let colors = &self.tab_viewer.colors;
+        let stale = self.palette_metrics.as_ref().is_none_or(|(rgb, _, _)| {
+            !rgb.iter().copied().eq(colors.iter().map(|c| (c.r, c.g, c.b)))
+        });
+        if stale {
+            self.palette_metrics = Some((
+                colors.iter().map(|c| (c.r, c.g, c.b)).collect(),
+                crate::analysis::metrics::mean_delta_e(colors),
+                crate::analysis::metrics::ab_hull_area(colors),
+            ));
+        }
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            let (_, spread, gamut) = self.palette_metrics.as_ref().unwrap();
+            ui.horizontal(|ui| {
+                ui.label(format!("\u{e22b} {} colors", self.tab_viewer.colors.len()));
+                ui.separator();
+                ui.label(format!("Spread: mean \u{0394}E {:.1}", spread))
+                    .on_hover_text("average CIEDE2000 distance between all pairs");
+                ui.separator();
+                ui.label(format!("Gamut: a*b* area {:.0}", gamut))
+                    .on_hover_text("area of the convex hull of the palette in the a*b* plane");
+                if let Some(batch) = &self.batch {
+                    ui.separator();
+                    ui.add(
+                        egui::ProgressBar::new(batch.done() as f32 / batch.total().max(1) as f32)
+                            .desired_width(160f32)
+                            .text(format!("Batch {}/{}", batch.done(), batch.total())),
+                    );
+                    if ui.button("\u{f0156} Cancel").clicked() {
+                        batch.cancel();
+                    }
+                }
+            });
+        });
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("\u{f0214} File", |ui| {
@@ -252,18 +921,82 @@ impl eframe::App for MainWindow {
                     {
                         ui_msg = Some(Msg::File(MsgFile::Load));
                     }
+                    if ui.button("\u{f0192} Paste Image").clicked() {
+                        ui_msg = Some(Msg::File(MsgFile::Paste));
+                    }
+                    if ui
+                        .button("\u{f0192} Paste Image & Extract")
+                        .on_hover_text("use the clipboard image and extract its palette")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::File(MsgFile::PasteAndExtract));
+                    }
                     if ui
                         .add(egui::Button::new("\u{f1604} Clear").shortcut_text(saveshortcuttext))
                         .clicked()
                     {
                         ui_msg = Some(Msg::File(MsgFile::Clear));
                     }
+                    if self.image_backup.is_some()
+                        && ui
+                            .button("\u{f054c} Undo Image Change")
+                            .on_hover_text("back to the image before the last generator result")
+                            .clicked()
+                    {
+                        ui_msg = Some(Msg::File(MsgFile::UndoImage));
+                    }
                     if ui
                         .add(egui::Button::new("\u{f0193} Save").shortcut_text(clearshortcuttext))
                         .clicked()
                     {
                         ui_msg = Some(Msg::File(MsgFile::Save));
                     }
+                    if ui
+                        .add_enabled(
+                            self.tab_viewer.gen_component.is_some(),
+                            egui::Button::new("\u{f0721} Export SVG"),
+                        )
+                        .on_hover_text("the Line, Blocks or Mono generator's image as vectors")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::File(MsgFile::SaveSvg));
+                    }
+                    if ui
+                        .add_enabled(
+                            self.batch.is_none(),
+                            egui::Button::new("\u{f0253} Batch Extract"),
+                        )
+                        .on_hover_text(
+                            "extract every image of a folder with the current Extract settings, \
+                             writing <image file name>.json next to each, never overwriting",
+                        )
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::File(MsgFile::BatchExtract));
+                    }
+                    ui.horizontal(|ui| {
+                        // the only lossy encoder, webp is written lossless
+                        ui.label("\u{f02e9} JPEG Quality:");
+                        ui.add(egui::DragValue::new(&mut self.image_quality).range(0..=100))
+                            .on_hover_text("quality of images saved as .jpg or .jpeg");
+                    });
+                    ui.horizontal(|ui| {
+                        let mut interval = settings::with(|s| s.autosave_interval);
+                        ui.label("\u{f0193} Auto-Save:");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut interval)
+                                    .range(0..=3600)
+                                    .suffix(" s"),
+                            )
+                            .on_hover_text("seconds between recovery writes, 0 turns it off")
+                            .changed()
+                        {
+                            let mut settings = settings::get();
+                            settings.autosave_interval = interval;
+                            settings::set(settings);
+                        }
+                    });
                     if ui
                         .add(egui::Button::new("\u{f05fc} Exit").shortcut_text(exitshortcuttext))
                         .clicked()
@@ -278,6 +1011,16 @@ impl eframe::App for MainWindow {
                     if ui.button("\u{eaf5} Reverse").clicked() {
                         ui_msg = Some(Msg::Color(MsgColor::Reverse));
                     }
+                    let mut preview_sorts = settings::with(|s| s.preview_sorts);
+                    if ui
+                        .checkbox(&mut preview_sorts, "Preview Sorts")
+                        .on_hover_text("sorting only reorders the view until Keep Sorted")
+                        .changed()
+                    {
+                        let mut settings = settings::get();
+                        settings.preview_sorts = preview_sorts;
+                        settings::set(settings);
+                    }
                     if ui.button("\u{f04bb} Sort By Name").clicked() {
                         ui_msg = Some(Msg::Color(MsgColor::SortByName));
                     }
@@ -299,12 +1042,195 @@ impl eframe::App for MainWindow {
                     if ui.button("\u{f1385} Sort By Value").clicked() {
                         ui_msg = Some(Msg::Color(MsgColor::SortByV));
                     }
-                    if ui.button("\u{f02fa} Import").clicked() {
-                        ui_msg = Some(Msg::Color(MsgColor::Import));
+                    if ui
+                        .button("\u{f1385} Sort By Lightness (Lab)")
+                        .on_hover_text("perceived lightness, dark to light")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::SortByLabL));
+                    }
+                    if ui
+                        .button("\u{f1385} Sort By Hue (Lab)")
+                        .on_hover_text("hue angle in Lab, follows perceived hue more closely than HSV")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::SortByLabHue));
+                    }
+                    if ui.button("\u{f02fa} Import (Append)").clicked() {
+                        ui_msg = Some(Msg::Color(MsgColor::Import(ImportMode::Append)));
+                    }
+                    if ui.button("\u{f02fa} Import (Replace)").clicked() {
+                        ui_msg = Some(Msg::Color(MsgColor::Import(ImportMode::Replace)));
+                    }
+                    if ui
+                        .button("\u{f02fa} Import Palette (Append)")
+                        .on_hover_text("append the colors of a .json, .gpl or .ase file")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::ImportPalette(ImportMode::Append)));
+                    }
+                    if ui
+                        .button("\u{f02fa} Import Palette (Replace)")
+                        .on_hover_text("replace the colors with those of a .json, .gpl or .ase file")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::ImportPalette(ImportMode::Replace)));
+                    }
+                    let undo_text = match self.history.undo_label() {
+                        Some(label) => format!("\u{f054c} Undo {}", label),
+                        None => "\u{f054c} Undo".into(),
+                    };
+                    if ui
+                        .add(egui::Button::new(undo_text).shortcut_text(undoshortcuttext))
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::Undo));
+                    }
+                    let redo_text = match self.history.redo_label() {
+                        Some(label) => format!("\u{f044e} Redo {}", label),
+                        None => "\u{f044e} Redo".into(),
+                    };
+                    if ui
+                        .add(egui::Button::new(redo_text).shortcut_text(redoshortcuttext))
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::Redo));
                     }
                     if ui.button("\u{f0207} Export").clicked() {
                         ui_msg = Some(Msg::Color(MsgColor::Export));
                     }
+                    if ui
+                        .button("\u{f0c5} Find Duplicates")
+                        .on_hover_text("colors with the exact same RGB")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::FindDuplicates));
+                    }
+                    ui.menu_button("\u{f0e80} Vibrance", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Chroma \u{00d7}");
+                            ui.add(
+                                egui::DragValue::new(&mut self.vibrance)
+                                    .range(0f32..=3f32)
+                                    .speed(0.01)
+                                    .fixed_decimals(2),
+                            );
+                        });
+                        if ui
+                            .button("\u{f0e80} Apply")
+                            .on_hover_text("scale LCh chroma, keeping lightness and hue")
+                            .clicked()
+                        {
+                            ui_msg = Some(Msg::Color(MsgColor::Vibrance));
+                        }
+                    });
+                    ui.menu_button("\u{f0a7a} Deduplicate", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("\u{0394}E <");
+                            ui.add(
+                                egui::DragValue::new(&mut self.dedup_threshold)
+                                    .range(0f64..=100f64)
+                                    .speed(0.05)
+                                    .fixed_decimals(1),
+                            );
+                        });
+                        if ui
+                            .button("\u{f0a7a} Apply")
+                            .on_hover_text("remove colors this close to an earlier one, the first is kept")
+                            .clicked()
+                        {
+                            ui_msg = Some(Msg::Color(MsgColor::Dedup));
+                        }
+                    });
+                    if ui
+                        .button("\u{f02e9} Export Share Card")
+                        .on_hover_text("PNG banner with every color, its name and hex")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::ExportCard));
+                    }
+                    ui.menu_button("\u{f0768} Hex Palette", |ui| {
+                        if ui.button("\u{f02fa} Import (Append)").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::ImportHex(ImportMode::Append)));
+                        }
+                        if ui.button("\u{f02fa} Import (Replace)").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::ImportHex(ImportMode::Replace)));
+                        }
+                        if ui.button("\u{f0207} Export").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::ExportHex));
+                        }
+                    });
+                    ui.menu_button("\u{f0768} Aseprite Palette", |ui| {
+                        if ui.button("\u{f02fa} Import (Append)").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::ImportAseprite(ImportMode::Append)));
+                        }
+                        if ui.button("\u{f02fa} Import (Replace)").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::ImportAseprite(ImportMode::Replace)));
+                        }
+                    });
+                    ui.menu_button("\u{f0768} Adobe Swatch Exchange", |ui| {
+                        if ui.button("\u{f0207} Export").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::ExportAse));
+                        }
+                    });
+                    ui.menu_button("\u{f0169} Copy As Code", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Array Name:");
+                            ui.text_edit_singleline(&mut self.array_name);
+                        });
+                        if ui.button("\u{e7a8} Rust").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::CopyRust));
+                        }
+                        if ui.button("\u{f0169} GLSL").clicked() {
+                            ui_msg = Some(Msg::Color(MsgColor::CopyGlsl));
+                        }
+                    });
+                    if ui
+                        .button("\u{e749} Copy CSS Gradient")
+                        .on_hover_text("linear-gradient() of the colors evenly spaced, at the Line generator's angle")
+                        .clicked()
+                    {
+                        ui_msg = Some(Msg::Color(MsgColor::CopyCss));
+                    }
+                });
+                ui.menu_button("\u{f06e} View", |ui| {
+                    let mut settings = settings::get();
+                    let mut changed = ui
+                        .checkbox(&mut settings.float_values, "Float Values")
+                        .on_hover_text("show channels as 0.0-1.0")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut settings.compact_colors, "Compact Colors")
+                        .on_hover_text("right click a color for its actions")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut settings.lab_readouts, "Lab Readouts")
+                        .on_hover_text("show L*a*b* and LCh under every color, the values perceptual sorts use")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut settings.rounded_swatches, "Rounded Swatches")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut settings.color_of_the_day, "Color Of The Day")
+                        .on_hover_text("start with a color picked from today's date")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        let mut seeded = settings.name_seed.is_some();
+                        if ui
+                            .checkbox(&mut seeded, "Seeded Names")
+                            .on_hover_text("name new colors from a seed, the same names every run")
+                            .changed()
+                        {
+                            settings.name_seed = seeded.then_some(0);
+                            changed = true;
+                        }
+                        if let Some(seed) = &mut settings.name_seed {
+                            changed |= ui.add(egui::DragValue::new(seed)).changed();
+                        }
+                    });
+                    if changed {
+                        settings::set(settings);
+                    }
                 });
                 ui.menu_button("\u{eae4} Window", |ui| {
                     let getlabel = |tab, text| {
@@ -319,6 +1245,48 @@ impl eframe::App for MainWindow {
                             ui_msg = Some(Msg::AdjustTab(tab.clone()));
                         }
                     }
+                    ui.separator();
+                    let mut settings = settings::get();
+                    if ui
+                        .checkbox(&mut settings.pixel_perfect, "Pixel Perfect Preview")
+                        .on_hover_text("scale the preview without smoothing")
+                        .changed()
+                    {
+                        settings::set(settings);
+                        self.tab_viewer.update_texture(ctx);
+                    }
+                    ui.separator();
+                    ui.menu_button("\u{ebeb} Workspaces", |ui| {
+                        if ui.button("\u{f0450} Reset Layout").clicked() {
+                            ui_msg = Some(Msg::Workspace(MsgWorkspace::Reset));
+                        }
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.workspace_name)
+                                    .hint_text("name")
+                                    .desired_width(120f32),
+                            );
+                            if ui.button("\u{eb4b} Save").clicked() {
+                                ui_msg = Some(Msg::Workspace(MsgWorkspace::Save));
+                            }
+                        });
+                        let names: Vec<String> =
+                            settings::with(|s| s.workspaces.keys().cloned().collect());
+                        if names.is_empty() {
+                            ui.label(egui::RichText::new("No saved workspace.").weak());
+                        }
+                        for name in names {
+                            ui.horizontal(|ui| {
+                                if ui.button(&name).clicked() {
+                                    ui_msg =
+                                        Some(Msg::Workspace(MsgWorkspace::Load(name.clone())));
+                                }
+                                if ui.button("\u{ea76}").on_hover_text("delete").clicked() {
+                                    ui_msg = Some(Msg::Workspace(MsgWorkspace::Delete(name)));
+                                }
+                            });
+                        }
+                    });
                 });
                 ui.menu_button("\u{ea60} Add", |ui| {
                     for (name, component) in crate::add::NAMELIST.iter() {
@@ -336,6 +1304,12 @@ impl eframe::App for MainWindow {
                         }
                     }
                 });
+                ui.menu_button("\u{f02d7} Help", |ui| {
+                    if ui.button("\u{ea74} About").clicked() {
+                        self.show_about = true;
+                        ui.close_menu();
+                    }
+                });
             });
             egui_dock::DockArea::new(&mut self.dock_tree)
                 .style(egui_dock::Style::from_egui(ctx.style().as_ref()))
@@ -347,26 +1321,95 @@ impl eframe::App for MainWindow {
                     TabMsg::Add(color) => {
                         ui_msg = Some(Msg::Add(color.clone()));
                     }
-                    TabMsg::Gen(img) => {
-                        ui_msg = Some(Msg::Gen(img.clone()));
+                    TabMsg::Gen(img, metadata) => {
+                        ui_msg = Some(Msg::Gen(img.clone(), metadata.clone()));
+                    }
+                    TabMsg::Edit(label, before) => {
+                        if *before != self.tab_viewer.colors {
+                            self.history.push(label, std::mem::take(before));
+                        }
+                    }
+                    TabMsg::Error(error) => {
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Error,
+                            text: format!("\u{f071} {}", error).into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(5f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
                     }
                 }
                 self.tab_viewer.ui_msg = None;
             }
             None => {}
         }
+        if self.duplicates.is_some() {
+            self.show_duplicates(ctx);
+        }
+        self.load_dropped_files(ctx);
+        // hold off writing until the user decided about the previous session
+        if self.pending_recovery.is_some() {
+            self.show_recovery(ctx);
+        } else {
+            self.autosave(ctx);
+        }
+        self.poll_batch(ctx);
+        if ctx.input(|i| i.viewport().close_requested()) {
+            recovery::clear();
+            self.remember_layout(ctx);
+        }
+        egui::Window::new("\u{ea74} About")
+            .open(&mut self.show_about)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading("ColorLook");
+                egui::Grid::new("about_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Version");
+                    ui.label(env!("CARGO_PKG_VERSION"));
+                    ui.end_row();
+                    ui.label("Commit");
+                    ui.label(env!("COLORLOOK_GIT_HASH"));
+                    ui.end_row();
+                    ui.label("egui");
+                    ui.label(env!("COLORLOOK_EGUI_VERSION"));
+                    ui.end_row();
+                });
+                if ui.button("\u{ebcc} Copy").clicked() {
+                    ui.output_mut(|o| {
+                        o.copied_text = format!(
+                            "ColorLook {} ({}), egui {}",
+                            env!("CARGO_PKG_VERSION"),
+                            env!("COLORLOOK_GIT_HASH"),
+                            env!("COLORLOOK_EGUI_VERSION")
+                        );
+                    });
+                }
+            });
         self.toasts.show(ctx);
         match &mut self.file_dialog {
             FileDialog::LoadImg(dlg) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path().map(|p| p.to_path_buf()) {
+                        self.load_image_file(ctx, &path);
+                    }
+                }
+            }
+            FileDialog::SaveImg(dlg) => {
                 if dlg.show(ctx).selected() {
                     if let Some(path) = dlg.path() {
-                        match image::open(path) {
-                            Ok(img) => {
-                                self.tab_viewer.image = img;
-                                self.tab_viewer.update_texture(ctx);
+                        match crate::utils::save_image(
+                            &self.tab_viewer.image,
+                            path,
+                            &self.image_metadata,
+                            self.image_quality,
+                        ) {
+                            Ok(format) => {
                                 self.toasts.add(egui_toast::Toast {
                                     kind: egui_toast::ToastKind::Success,
-                                    text: format!("Loaded Image from {}", path.display()).into(),
+                                    text: format!("Saved {} to {}", format.name(), path.display())
+                                        .into(),
                                     options: egui_toast::ToastOptions::default()
                                         .duration_in_seconds(2f64)
                                         .show_progress(true),
@@ -383,32 +1426,86 @@ impl eframe::App for MainWindow {
                                     style: egui_toast::ToastStyle::default(),
                                 });
                             }
-                        }
+                        };
                     }
                 }
             }
-            FileDialog::SaveImg(dlg) => {
+            FileDialog::SaveSvg(dlg, svg) => {
                 if dlg.show(ctx).selected() {
                     if let Some(path) = dlg.path() {
-                        if let Err(e) = self.tab_viewer.image.save(path) {
-                            self.toasts.add(egui_toast::Toast {
-                                kind: egui_toast::ToastKind::Error,
-                                text: format!("Error: {}", e).into(),
-                                options: egui_toast::ToastOptions::default()
-                                    .duration_in_seconds(5f64)
-                                    .show_progress(true),
-                                style: egui_toast::ToastStyle::default(),
-                            });
-                        } else {
-                            self.toasts.add(egui_toast::Toast {
-                                kind: egui_toast::ToastKind::Success,
-                                text: format!("Saved PNG to {}", path.display()).into(),
-                                options: egui_toast::ToastOptions::default()
-                                    .duration_in_seconds(2f64)
-                                    .show_progress(true),
-                                style: egui_toast::ToastStyle::default(),
-                            });
-                        };
+                        match std::fs::write(path, svg.as_bytes()) {
+                            Ok(_) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Success,
+                                    text: format!("Exported SVG to {}", path.display()).into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(2f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("\u{e654} Error Write SVG: {e}").into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            FileDialog::BatchFolder(dlg) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(dir) = dlg.path() {
+                        // the open Extract component decides the settings, a fresh one otherwise
+                        let settings = self
+                            .tab_viewer
+                            .add_component
+                            .as_ref()
+                            .and_then(|component| component.batch_settings())
+                            .unwrap_or_else(crate::add::BatchSettings::new);
+                        match crate::batch::Batch::start(dir, settings) {
+                            Ok(batch) if batch.total() == 0 => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Info,
+                                    text: format!("No images in {}", dir.display()).into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(3f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Ok(batch) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Info,
+                                    text: format!(
+                                        "Extracting {} image(s) from {}",
+                                        batch.total(),
+                                        dir.display()
+                                    )
+                                    .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(3f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                                self.batch = Some(batch);
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("\u{e654} Error Read Folder: {e}").into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
                     }
                 }
             }
@@ -449,14 +1546,22 @@ impl eframe::App for MainWindow {
                     }
                 }
             }
-            FileDialog::ImportJson(dlg) => {
+            FileDialog::ImportJson(dlg, mode) => {
                 if dlg.show(ctx).selected() {
                     if let Some(path) = dlg.path() {
                         let mut err = None;
                         match std::fs::read_to_string(path) {
                             Ok(str) => {
                                 match serde_json::from_str::<Vec<color_item::ColorItem>>(&str) {
+                                    // nothing to add, and a replace would only clear the list
+                                    Ok(color) if color.is_empty() => {
+                                        err = Some("no colors in the file".into());
+                                    }
                                     Ok(mut color) => {
+                                        self.history.push("Import", self.tab_viewer.colors.clone());
+                                        if *mode == ImportMode::Replace {
+                                            self.tab_viewer.colors.clear();
+                                        }
                                         self.tab_viewer.colors.append(&mut color);
                                     }
                                     Err(e) => {
@@ -490,9 +1595,307 @@ impl eframe::App for MainWindow {
                     }
                 }
             }
+            FileDialog::ImportHex(dlg, mode) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path() {
+                        let result = std::fs::read_to_string(path)
+                            .map(|text| crate::utils::hexfile::parse(&text));
+                        match result {
+                            // nothing to add, and a replace would only clear the list
+                            Ok((colors, skipped)) if colors.is_empty() => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!(
+                                        "\u{e654} No colors in {}, {} malformed line(s)",
+                                        path.display(),
+                                        skipped
+                                    )
+                                    .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Ok((mut colors, skipped)) => {
+                                self.history.push("Import", self.tab_viewer.colors.clone());
+                                if *mode == ImportMode::Replace {
+                                    self.tab_viewer.colors.clear();
+                                }
+                                self.tab_viewer.colors.append(&mut colors);
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Success,
+                                    text: format!("Imported Hex Palette from {}", path.display())
+                                        .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(2f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                                if skipped > 0 {
+                                    self.toasts.add(egui_toast::Toast {
+                                        kind: egui_toast::ToastKind::Warning,
+                                        text: format!(
+                                            "\u{f071} Skipped {} malformed line(s)",
+                                            skipped
+                                        )
+                                        .into(),
+                                        options: egui_toast::ToastOptions::default()
+                                            .duration_in_seconds(5f64)
+                                            .show_progress(true),
+                                        style: egui_toast::ToastStyle::default(),
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("\u{e654} Error Read Hex: {e}").into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            FileDialog::ImportAseprite(dlg, mode) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path() {
+                        let result = std::fs::read(path)
+                            .map_err(|e| e.to_string())
+                            .and_then(|data| crate::utils::aseprite::parse(&data));
+                        match result {
+                            // nothing to add, and a replace would only clear the list
+                            Ok(colors) if colors.is_empty() => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("\u{e654} No colors in {}", path.display())
+                                        .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Ok(mut colors) => {
+                                self.history.push("Import", self.tab_viewer.colors.clone());
+                                if *mode == ImportMode::Replace {
+                                    self.tab_viewer.colors.clear();
+                                }
+                                self.tab_viewer.colors.append(&mut colors);
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Success,
+                                    text: format!(
+                                        "Imported Aseprite Palette from {}",
+                                        path.display()
+                                    )
+                                    .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(2f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("\u{e654} Error Read Aseprite: {e}").into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            FileDialog::ExportHex(dlg) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path() {
+                        let text = crate::utils::hexfile::to_string(&self.tab_viewer.colors);
+                        match std::fs::write(path, text) {
+                            Ok(_) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Success,
+                                    text: format!("Exported Hex Palette to {}", path.display())
+                                        .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(2f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("Error Write Hex: {}", e).into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            FileDialog::ImportPalette(dlg, mode) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path() {
+                        match read_palette_file(path) {
+                            // nothing to add, and a replace would only clear the list
+                            Ok((colors, skipped)) if colors.is_empty() => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!(
+                                        "\u{e654} No colors in {}, {} malformed entries",
+                                        path.display(),
+                                        skipped
+                                    )
+                                    .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Ok((mut colors, skipped)) => {
+                                let imported = colors.len();
+                                self.history.push("Import", self.tab_viewer.colors.clone());
+                                if *mode == ImportMode::Replace {
+                                    self.tab_viewer.colors.clear();
+                                }
+                                self.tab_viewer.colors.append(&mut colors);
+                                if skipped > 0 {
+                                    self.toasts.add(egui_toast::Toast {
+                                        kind: egui_toast::ToastKind::Warning,
+                                        text: format!(
+                                            "\u{f071} Imported {} color(s) from {}, skipped {} malformed entries",
+                                            imported,
+                                            path.display(),
+                                            skipped
+                                        )
+                                        .into(),
+                                        options: egui_toast::ToastOptions::default()
+                                            .duration_in_seconds(5f64)
+                                            .show_progress(true),
+                                        style: egui_toast::ToastStyle::default(),
+                                    });
+                                } else {
+                                    self.toasts.add(egui_toast::Toast {
+                                        kind: egui_toast::ToastKind::Success,
+                                        text: format!(
+                                            "Imported {} color(s) from {}",
+                                            imported,
+                                            path.display()
+                                        )
+                                        .into(),
+                                        options: egui_toast::ToastOptions::default()
+                                            .duration_in_seconds(2f64)
+                                            .show_progress(true),
+                                        style: egui_toast::ToastStyle::default(),
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("\u{e654} Error Read Palette: {e}").into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            FileDialog::ExportAse(dlg) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path() {
+                        let data = crate::utils::ase::to_bytes(&self.tab_viewer.colors);
+                        match std::fs::write(path, data) {
+                            Ok(_) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Success,
+                                    text: format!("Exported ASE Palette to {}", path.display())
+                                        .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(2f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Error,
+                                    text: format!("Error Write ASE: {}", e).into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            FileDialog::ExportCard(dlg) => {
+                if dlg.show(ctx).selected() {
+                    if let Some(path) = dlg.path() {
+                        match crate::utils::share_card::render(&self.tab_viewer.colors) {
+                            Some(card) => match card.save(path) {
+                                Ok(_) => {
+                                    self.toasts.add(egui_toast::Toast {
+                                        kind: egui_toast::ToastKind::Success,
+                                        text: format!("Exported Share Card to {}", path.display())
+                                            .into(),
+                                        options: egui_toast::ToastOptions::default()
+                                            .duration_in_seconds(2f64)
+                                            .show_progress(true),
+                                        style: egui_toast::ToastStyle::default(),
+                                    });
+                                }
+                                Err(e) => {
+                                    self.toasts.add(egui_toast::Toast {
+                                        kind: egui_toast::ToastKind::Error,
+                                        text: format!("Error: {}", e).into(),
+                                        options: egui_toast::ToastOptions::default()
+                                            .duration_in_seconds(5f64)
+                                            .show_progress(true),
+                                        style: egui_toast::ToastStyle::default(),
+                                    });
+                                }
+                            },
+                            None => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "No colors to export".into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
             FileDialog::None => {}
         }
         if let Some(msg) = ui_msg {
+            // changes to the color list are snapshotted when they did change something
+            let history_label = match &msg {
+                Msg::Color(msg) => msg.history_label(),
+                Msg::Add(_) => Some("Add"),
+                _ => None,
+            };
+            let before = history_label.map(|_| self.tab_viewer.colors.clone());
             match msg {
                 Msg::File(msg) => match msg {
                     MsgFile::Load => {
@@ -503,21 +1906,116 @@ impl eframe::App for MainWindow {
                         dialog.open();
                         self.file_dialog = FileDialog::LoadImg(dialog);
                     }
+                    MsgFile::Paste | MsgFile::PasteAndExtract => {
+                        match crate::utils::clipboard_image() {
+                            Ok(img) => {
+                                self.tab_viewer.image = img;
+                                self.image_metadata.clear();
+                                self.image_path = None;
+                                self.image_backup = None;
+                                self.tab_viewer.update_texture(ctx);
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Success,
+                                    text: "Pasted image from clipboard".into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(2f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                                if let MsgFile::PasteAndExtract = msg {
+                                    self.tab_viewer.add_component =
+                                        Some(crate::add::get_extract_autostart());
+                                    if self.dock_tree.find_tab(&Tabs::Add).is_none() {
+                                        self.dock_tree.add_window(vec![Tabs::Add]);
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: format!("\u{f071} {}", e).into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(5f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
                     MsgFile::Clear => {
                         self.tab_viewer.image = PLACEHOLDER.clone();
+                        self.image_metadata.clear();
+                        self.image_path = None;
+                        self.image_backup = None;
                         self.tab_viewer.update_texture(ctx);
                     }
+                    MsgFile::UndoImage => {
+                        if let Some((img, metadata, path)) = self.image_backup.take() {
+                            self.tab_viewer.image = img;
+                            self.image_metadata = metadata;
+                            self.image_path = path;
+                            self.tab_viewer.update_texture(ctx);
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Success,
+                                text: "Image change undone".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(2f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                    }
                     MsgFile::Save => {
                         let mut dialog = egui_file::FileDialog::save_file(None)
-                            .title("Save PNG")
+                            .title("Save Image")
                             .default_filename("untitled.png")
-                            .filename_filter(Box::new(|name| name.ends_with(".png")))
+                            .filename_filter(Box::new(|name| crate::utils::is_save_name(name)))
                             .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
                             .current_pos(egui::pos2(width / 4f32, MARGIN));
                         dialog.open();
                         self.file_dialog = FileDialog::SaveImg(dialog);
                     }
+                    MsgFile::SaveSvg => {
+                        let svg = self
+                            .tab_viewer
+                            .gen_component
+                            .as_ref()
+                            .and_then(|component| component.svg(&self.tab_viewer.colors));
+                        match svg {
+                            Some(svg) => {
+                                let mut dialog = egui_file::FileDialog::save_file(None)
+                                    .title("Export SVG")
+                                    .default_filename("untitled.svg")
+                                    .filename_filter(Box::new(|name| name.ends_with(".svg")))
+                                    .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                                    .current_pos(egui::pos2(width / 4f32, MARGIN));
+                                dialog.open();
+                                self.file_dialog = FileDialog::SaveSvg(dialog, svg);
+                            }
+                            None => {
+                                self.toasts.add(egui_toast::Toast {
+                                    kind: egui_toast::ToastKind::Info,
+                                    text: "Nothing to export, SVG comes from the Line, Blocks and Mono generators"
+                                        .into(),
+                                    options: egui_toast::ToastOptions::default()
+                                        .duration_in_seconds(3f64)
+                                        .show_progress(true),
+                                    style: egui_toast::ToastStyle::default(),
+                                });
+                            }
+                        }
+                    }
+                    MsgFile::BatchExtract => {
+                        let mut dialog = egui_file::FileDialog::select_folder(None)
+                            .title("Batch Extract Folder")
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::BatchFolder(dialog);
+                    }
                     MsgFile::Exit => {
+                        recovery::clear();
+                        self.remember_layout(ctx);
                         std::process::exit(0);
                     }
                 },
@@ -526,43 +2024,213 @@ impl eframe::App for MainWindow {
                         self.tab_viewer.colors.clear();
                     }
                     MsgColor::Reverse => {
-                        self.tab_viewer.colors.reverse();
+                        let mut order = match self.tab_viewer.sort_preview.take() {
+                            Some(order) if order.len() == self.tab_viewer.colors.len() => order,
+                            _ => (0..self.tab_viewer.colors.len()).collect(),
+                        };
+                        order.reverse();
+                        self.tab_viewer.set_order(order);
                     }
                     MsgColor::SortByName => {
-                        self.tab_viewer.colors.sort_by(|a, b| a.name.cmp(&b.name));
+                        self.tab_viewer.sort_colors(|a, b| a.name.cmp(&b.name));
                     }
                     MsgColor::SortByR => {
-                        self.tab_viewer.colors.sort_by(|a, b| a.r.cmp(&b.r));
+                        self.tab_viewer.sort_colors(|a, b| a.r.cmp(&b.r));
                     }
                     MsgColor::SortByG => {
-                        self.tab_viewer.colors.sort_by(|a, b| a.g.cmp(&b.g));
+                        self.tab_viewer.sort_colors(|a, b| a.g.cmp(&b.g));
                     }
                     MsgColor::SortByB => {
-                        self.tab_viewer.colors.sort_by(|a, b| a.b.cmp(&b.b));
+                        self.tab_viewer.sort_colors(|a, b| a.b.cmp(&b.b));
                     }
                     MsgColor::SortByH => {
                         self.tab_viewer
-                            .colors
-                            .sort_by(|a, b| a.get_h().total_cmp(&b.get_h()));
+                            .sort_colors(|a, b| a.get_h().total_cmp(&b.get_h()));
                     }
                     MsgColor::SortByS => {
                         self.tab_viewer
-                            .colors
-                            .sort_by(|a, b| a.get_s().total_cmp(&b.get_s()));
+                            .sort_colors(|a, b| a.get_s().total_cmp(&b.get_s()));
                     }
                     MsgColor::SortByV => {
                         self.tab_viewer
-                            .colors
-                            .sort_by(|a, b| a.get_v().total_cmp(&b.get_v()));
+                            .sort_colors(|a, b| a.get_v().total_cmp(&b.get_v()));
+                    }
+                    MsgColor::SortByLabL => {
+                        self.tab_viewer
+                            .sort_colors(|a, b| a.get_lab_l().total_cmp(&b.get_lab_l()));
+                    }
+                    MsgColor::SortByLabHue => {
+                        self.tab_viewer
+                            .sort_colors(|a, b| a.get_lab_hue().total_cmp(&b.get_lab_hue()));
                     }
-                    MsgColor::Import => {
+                    MsgColor::Import(mode) => {
                         let mut dialog = egui_file::FileDialog::open_file(None)
                             .title("Import JSON")
                             .filename_filter(Box::new(|name| name.ends_with(".json")))
                             .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
                             .current_pos(egui::pos2(width / 4f32, MARGIN));
                         dialog.open();
-                        self.file_dialog = FileDialog::ImportJson(dialog);
+                        self.file_dialog = FileDialog::ImportJson(dialog, mode);
+                    }
+                    MsgColor::ExportCard => {
+                        let mut dialog = egui_file::FileDialog::save_file(None)
+                            .title("Export Share Card")
+                            .default_filename("palette.png")
+                            .filename_filter(Box::new(|name| name.ends_with(".png")))
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::ExportCard(dialog);
+                    }
+                    MsgColor::Vibrance => {
+                        for color in self.tab_viewer.colors.iter_mut() {
+                            *color = crate::utils::lch::scale_chroma(color, self.vibrance as f64);
+                        }
+                    }
+                    MsgColor::Dedup => {
+                        let removed = crate::analysis::metrics::near_duplicates(
+                            &self.tab_viewer.colors,
+                            self.dedup_threshold,
+                        );
+                        if removed.is_empty() {
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Info,
+                                text: "No near-duplicate colors".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(3f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        } else {
+                            // back to front keeps the remaining indices valid
+                            for &i in removed.iter().rev() {
+                                self.tab_viewer.colors.remove(i);
+                            }
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Success,
+                                text: format!("Removed {} near-duplicate color(s)", removed.len())
+                                    .into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(2f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                    }
+                    MsgColor::ImportHex(mode) => {
+                        let mut dialog = egui_file::FileDialog::open_file(None)
+                            .title("Import Hex Palette")
+                            .filename_filter(Box::new(|name| name.ends_with(".hex")))
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::ImportHex(dialog, mode);
+                    }
+                    MsgColor::ImportAseprite(mode) => {
+                        let mut dialog = egui_file::FileDialog::open_file(None)
+                            .title("Import Aseprite Palette")
+                            .filename_filter(Box::new(|name| {
+                                name.ends_with(".aseprite") || name.ends_with(".ase")
+                            }))
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::ImportAseprite(dialog, mode);
+                    }
+                    MsgColor::ExportHex => {
+                        let mut dialog = egui_file::FileDialog::save_file(None)
+                            .title("Export Hex Palette")
+                            .default_filename("untitled.hex")
+                            .filename_filter(Box::new(|name| name.ends_with(".hex")))
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::ExportHex(dialog);
+                    }
+                    MsgColor::ImportPalette(mode) => {
+                        let mut dialog = egui_file::FileDialog::open_file(None)
+                            .title("Import Palette")
+                            .filename_filter(Box::new(|name| {
+                                name.ends_with(".json")
+                                    || name.ends_with(".gpl")
+                                    || name.ends_with(".ase")
+                            }))
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::ImportPalette(dialog, mode);
+                    }
+                    MsgColor::ExportAse => {
+                        let mut dialog = egui_file::FileDialog::save_file(None)
+                            .title("Export ASE Palette")
+                            .default_filename("untitled.ase")
+                            .filename_filter(Box::new(|name| name.ends_with(".ase")))
+                            .default_size(egui::vec2(width / 2f32, height - 2f32 * MARGIN))
+                            .current_pos(egui::pos2(width / 4f32, MARGIN));
+                        dialog.open();
+                        self.file_dialog = FileDialog::ExportAse(dialog);
+                    }
+                    MsgColor::Undo => match self.history.undo(&mut self.tab_viewer.colors) {
+                        Some(label) => {
+                            self.tab_viewer.sort_preview = None;
+                            self.tab_viewer.selection.clear();
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Success,
+                                text: format!("{} undone", label).into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(2f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                        None => {
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Info,
+                                text: "Nothing to undo".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(3f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                    },
+                    MsgColor::Redo => match self.history.redo(&mut self.tab_viewer.colors) {
+                        Some(label) => {
+                            self.tab_viewer.sort_preview = None;
+                            self.tab_viewer.selection.clear();
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Success,
+                                text: format!("{} redone", label).into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(2f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                        None => {
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Info,
+                                text: "Nothing to redo".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(3f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                    },
+                    MsgColor::FindDuplicates => {
+                        if duplicate_groups(&self.tab_viewer.colors).is_empty() {
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Info,
+                                text: "No duplicate colors".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(3f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        } else {
+                            self.duplicates = Some(std::collections::HashMap::new());
+                        }
                     }
                     MsgColor::Export => {
                         let mut dialog = egui_file::FileDialog::save_file(None)
@@ -574,14 +2242,114 @@ impl eframe::App for MainWindow {
                         dialog.open();
                         self.file_dialog = FileDialog::ExportJson(dialog);
                     }
+                    MsgColor::CopyRust => {
+                        let code = crate::utils::export::to_rust_array(
+                            &self.tab_viewer.colors,
+                            &self.array_name,
+                        );
+                        ctx.output_mut(|o| o.copied_text = code);
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Success,
+                            text: "Copied Rust array".into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(2f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
+                    }
+                    MsgColor::CopyGlsl => {
+                        let code = crate::utils::export::to_glsl_array(
+                            &self.tab_viewer.colors,
+                            &self.array_name,
+                        );
+                        ctx.output_mut(|o| o.copied_text = code);
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Success,
+                            text: "Copied GLSL array".into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(2f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
+                    }
+                    MsgColor::CopyCss => {
+                        if self.tab_viewer.colors.is_empty() {
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Info,
+                                text: "No colors to copy".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(3f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        } else {
+                            let angle = self
+                                .tab_viewer
+                                .gen_component
+                                .as_ref()
+                                .and_then(|component| component.css_angle())
+                                .unwrap_or(crate::utils::export::DEFAULT_CSS_ANGLE);
+                            let css = crate::utils::export::to_css_gradient(&self.tab_viewer.colors, angle);
+                            ctx.output_mut(|o| o.copied_text = css);
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Success,
+                                text: "Copied CSS gradient".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(2f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                    }
                 },
                 Msg::Add(color) => {
+                    if color.is_empty() {
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Warning,
+                            text: "\u{f071} No colors were added, check the component settings"
+                                .into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(5f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
+                    }
+                    crate::add::frequent::record_usage(&color);
                     for i in color {
                         self.tab_viewer.colors.push(i);
                     }
                 }
-                Msg::Gen(img) => {
-                    self.tab_viewer.image = img;
+                Msg::Gen(img, metadata) => {
+                    let expect_uniform = self
+                        .tab_viewer
+                        .gen_component
+                        .as_ref()
+                        .is_some_and(|component| component.uniform_by_design());
+                    let uniform = if expect_uniform {
+                        None
+                    } else {
+                        crate::utils::uniform_color(&img)
+                    };
+                    if let Some([r, g, b, _]) = uniform {
+                        self.toasts.add(egui_toast::Toast {
+                            kind: egui_toast::ToastKind::Warning,
+                            text: format!(
+                                "\u{f071} Generated image is a single color (#{:02X}{:02X}{:02X}), check the color positions and that the palette has distinct colors",
+                                r,
+                                g,
+                                b
+                            )
+                            .into(),
+                            options: egui_toast::ToastOptions::default()
+                                .duration_in_seconds(5f64)
+                                .show_progress(true),
+                            style: egui_toast::ToastStyle::default(),
+                        });
+                    }
+                    let previous = std::mem::replace(&mut self.tab_viewer.image, img);
+                    let previous_metadata = std::mem::replace(&mut self.image_metadata, metadata);
+                    self.image_backup =
+                        Some((previous, previous_metadata, self.image_path.take()));
                     self.tab_viewer.update_texture(ctx);
                 }
                 Msg::AdjustTab(tab) => match self.dock_tree.find_tab(&tab) {
@@ -592,6 +2360,55 @@ impl eframe::App for MainWindow {
                         self.dock_tree.add_window(vec![tab]);
                     }
                 },
+                Msg::Workspace(msg) => match msg {
+                    MsgWorkspace::Save => {
+                        let name = self.workspace_name.trim().to_string();
+                        if name.is_empty() {
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Warning,
+                                text: "Give the workspace a name first".into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(5f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        } else {
+                            let mut settings = settings::get();
+                            settings
+                                .workspaces
+                                .insert(name.clone(), crate::layout::Layout::from_dock(&self.dock_tree));
+                            settings::set(settings);
+                            self.toasts.add(egui_toast::Toast {
+                                kind: egui_toast::ToastKind::Success,
+                                text: format!("Saved workspace {}", name).into(),
+                                options: egui_toast::ToastOptions::default()
+                                    .duration_in_seconds(2f64)
+                                    .show_progress(true),
+                                style: egui_toast::ToastStyle::default(),
+                            });
+                        }
+                    }
+                    MsgWorkspace::Load(name) => {
+                        let tree = settings::with(|s| s.workspaces.get(&name).and_then(|layout| layout.to_dock()));
+                        if let Some(tree) = tree {
+                            self.dock_tree = tree;
+                            self.workspace_name = name;
+                        }
+                    }
+                    MsgWorkspace::Delete(name) => {
+                        let mut settings = settings::get();
+                        settings.workspaces.remove(&name);
+                        settings::set(settings);
+                    }
+                    MsgWorkspace::Reset => {
+                        self.dock_tree = Self::default_layout();
+                    }
+                },
+            }
+            if let (Some(label), Some(before)) = (history_label, before) {
+                if before != self.tab_viewer.colors {
+                    self.history.push(label, before);
+                }
             }
         }
     }
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::color_item;
+
+const RECOVERY_FILE: &str = "recovery.json";
+
+/// working state written periodically, so a crash does not lose the palette
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Recovery {
+    pub colors: Vec<color_item::ColorItem>,
+    /// file the working image was loaded from, none for pasted or generated images
+    pub image_path: Option<std::path::PathBuf>,
+}
+
+fn recovery_path() -> Result<std::path::PathBuf, String> {
+    let exe_path = std::env::current_exe()
+        .or(Err("Error Get EXE Path".to_string()))?
+        .parent()
+        .ok_or("No Parent Dir".to_string())?
+        .to_path_buf();
+    return Ok(exe_path.join(RECOVERY_FILE));
+}
+
+/// recovery left behind by a session that did not exit cleanly
+pub fn load() -> Result<Option<Recovery>, String> {
+    let path = recovery_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(path).or(Err("Error Read Recovery".to_string()))?;
+    return serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| e.to_string());
+}
+
+pub fn save(recovery: &Recovery) -> Result<(), String> {
+    let json = serde_json::to_string(recovery).map_err(|e| e.to_string())?;
+    return std::fs::write(recovery_path()?, json).map_err(|e| e.to_string());
+}
+
+/// remove the recovery file, called on a clean exit
+pub fn clear() {
+    if let Ok(path) = recovery_path() {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(path) {
+                println!("Error: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use crate::layout::Layout;
+
+const SETTINGS_FILE: &str = "settings.json";
+// folder inside the OS config folder
+const APP_DIR: &str = "colorlook";
+
+// false once an unreadable settings file could not be backed up, so it is never overwritten
+static WRITABLE: AtomicBool = AtomicBool::new(true);
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<Settings> = RwLock::new(load().unwrap_or_else(|msg| {
+        println!("Error: {}", msg);
+        Settings::default()
+    }));
+}
+
+/// global preferences, stored as json in the OS config folder
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// show and edit channels as 0.0-1.0 instead of 0-255
+    pub float_values: bool,
+    /// one-line color cells with actions in a context menu
+    pub compact_colors: bool,
+    /// Lab and LCh values under every color in the Colors tab
+    pub lab_readouts: bool,
+    /// nearest-neighbor magnification for the Preview texture
+    pub pixel_perfect: bool,
+    /// draw swatches with rounded corners
+    pub rounded_swatches: bool,
+    /// start with a color picked from today's date
+    pub color_of_the_day: bool,
+    /// name new colors from this seed, the same names run after run, random when unset
+    pub name_seed: Option<u64>,
+    /// sorting only reorders the view until kept
+    pub preview_sorts: bool,
+    /// named dock layouts, recalled from the Window menu
+    #[serde(deserialize_with = "lenient_workspaces")]
+    pub workspaces: BTreeMap<String, Layout>,
+    /// seconds between writes of the recovery file, 0 turns auto-save off
+    pub autosave_interval: u32,
+    /// how often each color was added, keyed by hex
+    pub color_usage: BTreeMap<String, u32>,
+    /// dock layout at the last exit, restored on start
+    #[serde(deserialize_with = "lenient")]
+    pub layout: Option<Layout>,
+    /// inner window size at the last exit
+    pub window_size: Option<[f32; 2]>,
+}
+
+// a value written by another version is dropped instead of failing the whole file
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    return Ok(serde_json::from_value(value).ok());
+}
+
+// named layouts that no longer load are dropped one by one
+fn lenient_workspaces<'de, D>(deserializer: D) -> Result<BTreeMap<String, Layout>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let workspaces = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    return Ok(workspaces
+        .into_iter()
+        .filter_map(|(name, value)| serde_json::from_value(value).ok().map(|layout| (name, layout)))
+        .collect());
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        return Self {
+            float_values: false,
+            compact_colors: false,
+            lab_readouts: false,
+            pixel_perfect: false,
+            rounded_swatches: false,
+            color_of_the_day: false,
+            name_seed: None,
+            preview_sorts: false,
+            workspaces: BTreeMap::new(),
+            autosave_interval: 30,
+            color_usage: BTreeMap::new(),
+            layout: None,
+            window_size: None,
+        };
+    }
+}
+
+// the per-user config folder of the OS
+fn config_dir() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).map(PathBuf::from).filter(|p| p.is_absolute());
+    if cfg!(windows) {
+        return var("APPDATA");
+    }
+    if cfg!(target_os = "macos") {
+        return var("HOME").map(|home| home.join("Library").join("Application Support"));
+    }
+    return var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")));
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    let dir = config_dir().ok_or("No Config Dir".to_string())?.join(APP_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    return Ok(dir.join(SETTINGS_FILE));
+}
+
+// where settings were kept before they moved to the config folder
+fn legacy_settings_path() -> Result<PathBuf, String> {
+    let exe_path = std::env::current_exe()
+        .or(Err("Error Get EXE Path".to_string()))?
+        .parent()
+        .ok_or("No Parent Dir".to_string())?
+        .to_path_buf();
+    return Ok(exe_path.join(SETTINGS_FILE));
+}
+
+fn load() -> Result<Settings, String> {
+    let mut path = settings_path()?;
+    if !path.exists() {
+        path = legacy_settings_path()?;
+    }
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+    let json = std::fs::read_to_string(&path).map_err(|_| {
+        WRITABLE.store(false, Ordering::Relaxed);
+        return "Error Read Settings".to_string();
+    })?;
+    return serde_json::from_str(&json).map_err(|e| {
+        // keep what the user had instead of overwriting it with defaults on the next save
+        let backup = path.with_extension("json.bak");
+        if std::fs::copy(&path, &backup).is_ok() {
+            return format!("{}, kept a copy at {}", e, backup.display());
+        }
+        WRITABLE.store(false, Ordering::Relaxed);
+        return format!("{}, not saving settings this session", e);
+    });
+}
+
+fn save(settings: &Settings) -> Result<(), String> {
+    if !WRITABLE.load(Ordering::Relaxed) {
+        return Err("Settings file could not be read or backed up, not overwriting it".into());
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    return std::fs::write(settings_path()?, json).map_err(|e| e.to_string());
+}
+
+pub fn get() -> Settings {
+    return SETTINGS.read().unwrap().clone();
+}
+
+pub fn set(settings: Settings) {
+    if let Err(msg) = save(&settings) {
+        println!("Error: {}", msg);
+    }
+    *SETTINGS.write().unwrap() = settings;
+}
+
+/// change values without writing the file, they are saved by the next `set`, at the latest on exit
+pub fn update_unsaved(f: impl FnOnce(&mut Settings)) {
+    f(&mut SETTINGS.write().unwrap());
+}
+
+/// read a value without cloning the whole settings
+pub fn with<R>(f: impl FnOnce(&Settings) -> R) -> R {
+    return f(&SETTINGS.read().unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreadable_layout() {
+        let json = r#"{"float_values": true, "layout": {"main": "garbage"}, "window_size": [800.0, 600.0]}"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        assert!(settings.float_values);
+        assert!(settings.layout.is_none());
+        assert_eq!(settings.window_size, Some([800f32, 600f32]));
+    }
+
+    #[test]
+    fn test_layout_round_trip() {
+        use crate::mainwindow::Tabs;
+        let mut settings = Settings::default();
+        let dock = egui_dock::DockState::new(vec![Tabs::Colors, Tabs::Preview]);
+        settings.layout = Some(Layout::from_dock(&dock));
+        let json = serde_json::to_string(&settings).unwrap();
+        let loaded: Settings = serde_json::from_str(&json).unwrap();
+        let tree = loaded.layout.unwrap().to_dock().unwrap();
+        let tabs: Vec<Tabs> = tree.iter_all_tabs().map(|(_, tab)| *tab).collect();
+        assert_eq!(tabs, vec![Tabs::Colors, Tabs::Preview]);
+    }
+
+    #[test]
+    fn test_workspace_round_trip() {
+        let mut settings = Settings::default();
+        let dock = egui_dock::DockState::new(vec![crate::mainwindow::Tabs::Gen]);
+        settings.workspaces.insert("gen".into(), Layout::from_dock(&dock));
+        let json = serde_json::to_string(&settings).unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["workspaces"]["broken"] = serde_json::json!({"main": "garbage"});
+        let loaded: Settings = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.workspaces.keys().collect::<Vec<_>>(), ["gen"]);
+    }
+}
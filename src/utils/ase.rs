@@ -0,0 +1,160 @@
+// Adobe Swatch Exchange (.ase): big-endian, one color entry block per swatch
+
+use crate::color_item::ColorItem;
+
+const SIGNATURE: &[u8; 4] = b"ASEF";
+const VERSION: (u16, u16) = (1, 0);
+const COLOR_ENTRY: u16 = 0x0001;
+const RGB_MODEL: &[u8; 4] = b"RGB ";
+const CMYK_MODEL: &[u8; 4] = b"CMYK";
+const GRAY_MODEL: &[u8; 4] = b"Gray";
+// global = 0, spot = 1, normal = 2
+const NORMAL_COLOR: u16 = 2;
+
+/// name as UTF-16BE code units with a null terminator, prefixed by the unit count
+fn write_name(data: &mut Vec<u8>, name: &str) {
+    let units: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    data.extend((units.len() as u16).to_be_bytes());
+    for unit in units {
+        data.extend(unit.to_be_bytes());
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    return Some(u16::from_be_bytes([bytes[0], bytes[1]]));
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    return Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+fn read_f32(data: &[u8], offset: usize) -> Option<f32> {
+    return read_u32(data, offset).map(f32::from_bits);
+}
+
+// body of a color entry: name, model and its channels
+fn read_color(block: &[u8]) -> Option<ColorItem> {
+    let units = read_u16(block, 0)? as usize;
+    let name: Vec<u16> = (0..units)
+        .map(|i| read_u16(block, 2 + i * 2))
+        .collect::<Option<_>>()?;
+    let name = String::from_utf16_lossy(&name);
+    let name = name.trim_end_matches('\0');
+    let offset = 2 + units * 2;
+    let model = block.get(offset..offset + 4)?;
+    let channel = |i: usize| read_f32(block, offset + 4 + i * 4);
+    let to_u8 = |v: f32| (v.clamp(0f32, 1f32) * 255f32).round() as u8;
+    if model == RGB_MODEL {
+        return Some(ColorItem::new(
+            name,
+            to_u8(channel(0)?),
+            to_u8(channel(1)?),
+            to_u8(channel(2)?),
+        ));
+    } else if model == CMYK_MODEL {
+        return Some(ColorItem::from_cmyk(
+            channel(0)?,
+            channel(1)?,
+            channel(2)?,
+            channel(3)?,
+            name,
+        ));
+    } else if model == GRAY_MODEL {
+        let v = to_u8(channel(0)?);
+        return Some(ColorItem::new(name, v, v, v));
+    }
+    // LAB swatches are not supported
+    return None;
+}
+
+/// read the color entries of an .ase file, groups are flattened.
+/// returns the colors and how many entries could not be read.
+pub fn parse(data: &[u8]) -> Result<(Vec<ColorItem>, usize), String> {
+    if data.get(0..4) != Some(SIGNATURE.as_slice()) {
+        return Err("not an ASE file".into());
+    }
+    let count = read_u32(data, 8).ok_or("truncated header")?;
+    let mut colors = Vec::new();
+    let mut skipped = 0;
+    let mut offset = 12;
+    for _ in 0..count {
+        let kind = read_u16(data, offset).ok_or("truncated block")?;
+        let length = read_u32(data, offset + 2).ok_or("truncated block")? as usize;
+        let block = data
+            .get(offset + 6..offset + 6 + length)
+            .ok_or("truncated block")?;
+        if kind == COLOR_ENTRY {
+            match read_color(block) {
+                Some(color) => colors.push(color),
+                None => skipped += 1,
+            }
+        }
+        offset += 6 + length;
+    }
+    return Ok((colors, skipped));
+}
+
+/// the whole palette as an .ase file, colors as RGB floats 0-1, alpha is not stored
+pub fn to_bytes(colors: &[ColorItem]) -> Vec<u8> {
+    let mut data = SIGNATURE.to_vec();
+    data.extend(VERSION.0.to_be_bytes());
+    data.extend(VERSION.1.to_be_bytes());
+    data.extend((colors.len() as u32).to_be_bytes());
+    for color in colors {
+        let mut block = Vec::new();
+        write_name(&mut block, &color.name);
+        block.extend(RGB_MODEL);
+        for channel in [color.r, color.g, color.b] {
+            block.extend((channel as f32 / 255f32).to_be_bytes());
+        }
+        block.extend(NORMAL_COLOR.to_be_bytes());
+        data.extend(COLOR_ENTRY.to_be_bytes());
+        data.extend((block.len() as u32).to_be_bytes());
+        data.extend(block);
+    }
+    return data;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_red() {
+        let data = to_bytes(&[ColorItem::new("red", 255, 0, 0)]);
+        #[rustfmt::skip]
+        let expected: Vec<u8> = vec![
+            b'A', b'S', b'E', b'F', 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            // color entry, 28 bytes
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x1c,
+            // "red" plus the terminator
+            0x00, 0x04, 0x00, b'r', 0x00, b'e', 0x00, b'd', 0x00, 0x00,
+            b'R', b'G', b'B', b' ',
+            0x3f, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x02,
+        ];
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let colors = vec![
+            ColorItem::new("red", 255, 0, 0),
+            ColorItem::new("\u{7a7a} sky", 0, 128, 255),
+        ];
+        let (read, skipped) = parse(&to_bytes(&colors)).unwrap();
+        assert_eq!(read, colors);
+        assert_eq!(skipped, 0);
+        assert!(parse(b"GIMP Palette").is_err());
+        let mut truncated = to_bytes(&colors);
+        truncated.truncate(truncated.len() - 4);
+        assert!(parse(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(to_bytes(&[]), b"ASEF\x00\x01\x00\x00\x00\x00\x00\x00".to_vec());
+    }
+}
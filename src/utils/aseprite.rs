@@ -0,0 +1,243 @@
+// palette chunks of Aseprite files, layers and cels are skipped.
+// see docs/ase-file-specs.md in the aseprite repository for the layout.
+
+use crate::color_item::ColorItem;
+
+const HEADER_SIZE: usize = 128;
+const FRAME_HEADER_SIZE: usize = 16;
+const CHUNK_HEADER_SIZE: usize = 6;
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+const PALETTE_CHUNK: u16 = 0x2019;
+const OLD_PALETTE_CHUNK: u16 = 0x0004;
+// palette entry flag: a name string follows the color
+const HAS_NAME: u16 = 1;
+// palettes are indexed by u16, anything above is a broken file
+const MAX_INDEX: usize = 0xFFFF;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    return Some(u16::from_le_bytes([bytes[0], bytes[1]]));
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    return Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+// (rgba, name) by palette index, entries can be set by several chunks
+type Entries = Vec<Option<([u8; 4], Option<String>)>>;
+
+fn set_entry(entries: &mut Entries, index: usize, entry: ([u8; 4], Option<String>)) -> Option<()> {
+    if index > MAX_INDEX {
+        return None;
+    }
+    if entries.len() <= index {
+        entries.resize(index + 1, None);
+    }
+    entries[index] = Some(entry);
+    return Some(());
+}
+
+// 0x2019: new size, first and last index, then flags, rgba and an optional name per entry
+fn read_palette_chunk(data: &[u8], entries: &mut Entries) -> Option<()> {
+    let first = read_u32(data, 4)? as usize;
+    let last = read_u32(data, 8)? as usize;
+    if last < first || last > MAX_INDEX {
+        return None;
+    }
+    let mut offset = 20;
+    for index in first..=last {
+        let flags = read_u16(data, offset)?;
+        let rgba = data.get(offset + 2..offset + 6)?;
+        offset += 6;
+        let mut name = None;
+        if flags & HAS_NAME != 0 {
+            let length = read_u16(data, offset)? as usize;
+            let text = data.get(offset + 2..offset + 2 + length)?;
+            name = Some(String::from_utf8_lossy(text).to_string());
+            offset += 2 + length;
+        }
+        set_entry(entries, index, ([rgba[0], rgba[1], rgba[2], rgba[3]], name))?;
+    }
+    return Some(());
+}
+
+// 0x0004: packets of (entries to skip, count with 0 for 256, rgb per color)
+fn read_old_palette_chunk(data: &[u8], entries: &mut Entries) -> Option<()> {
+    let packets = read_u16(data, 0)?;
+    let mut offset = 2;
+    let mut index = 0;
+    for _ in 0..packets {
+        index += *data.get(offset)? as usize;
+        let count = match *data.get(offset + 1)? {
+            0 => 256,
+            n => n as usize,
+        };
+        offset += 2;
+        for _ in 0..count {
+            let rgb = data.get(offset..offset + 3)?;
+            set_entry(entries, index, ([rgb[0], rgb[1], rgb[2], 255], None))?;
+            offset += 3;
+            index += 1;
+        }
+    }
+    return Some(());
+}
+
+/// read the palette of an .aseprite/.ase file. the newer palette chunk wins,
+/// the old one is only used when a file has none. unnamed colors are named by index.
+pub fn parse(data: &[u8]) -> Result<Vec<ColorItem>, String> {
+    if read_u16(data, 4) != Some(FILE_MAGIC) {
+        return Err("not an Aseprite file".into());
+    }
+    let frames = read_u16(data, 6).unwrap_or(0);
+    let mut entries = Entries::new();
+    let mut old_entries = Entries::new();
+    let mut offset = HEADER_SIZE;
+    for _ in 0..frames {
+        let truncated = || "truncated frame".to_string();
+        let frame_size = read_u32(data, offset).ok_or_else(truncated)? as usize;
+        if read_u16(data, offset + 4) != Some(FRAME_MAGIC) || frame_size < FRAME_HEADER_SIZE {
+            return Err("broken frame header".into());
+        }
+        let old_count = read_u16(data, offset + 6).ok_or_else(truncated)? as usize;
+        let count = match read_u32(data, offset + 12).ok_or_else(truncated)? as usize {
+            0 => old_count,
+            n => n,
+        };
+        let mut chunk = offset + FRAME_HEADER_SIZE;
+        for _ in 0..count {
+            let size = read_u32(data, chunk).ok_or_else(truncated)? as usize;
+            let kind = read_u16(data, chunk + 4).ok_or_else(truncated)?;
+            if size < CHUNK_HEADER_SIZE {
+                return Err("broken chunk header".into());
+            }
+            let body = data
+                .get(chunk + CHUNK_HEADER_SIZE..chunk + size)
+                .ok_or_else(truncated)?;
+            let read = match kind {
+                PALETTE_CHUNK => read_palette_chunk(body, &mut entries),
+                OLD_PALETTE_CHUNK => read_old_palette_chunk(body, &mut old_entries),
+                _ => Some(()),
+            };
+            if read.is_none() {
+                return Err(format!("broken palette chunk 0x{:04x}", kind));
+            }
+            chunk += size;
+        }
+        offset += frame_size;
+    }
+    if entries.is_empty() {
+        entries = old_entries;
+    }
+    if entries.is_empty() {
+        return Err("no palette in file".into());
+    }
+    return Ok(entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let ([r, g, b, a], name) = entry?;
+            let name = name.filter(|n| !n.is_empty()).unwrap_or_else(|| index.to_string());
+            return Some(ColorItem::new(&name, r, g, b).with_alpha(a));
+        })
+        .collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(kind: u16, body: &[u8]) -> Vec<u8> {
+        let mut data = ((body.len() + CHUNK_HEADER_SIZE) as u32).to_le_bytes().to_vec();
+        data.extend(kind.to_le_bytes());
+        data.extend(body);
+        return data;
+    }
+
+    // one frame holding the given chunks
+    fn file(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        let body: Vec<u8> = chunks.concat();
+        frame.extend(((FRAME_HEADER_SIZE + body.len()) as u32).to_le_bytes());
+        frame.extend(FRAME_MAGIC.to_le_bytes());
+        frame.extend((chunks.len() as u16).to_le_bytes());
+        frame.extend([0u8; 4]);
+        frame.extend((chunks.len() as u32).to_le_bytes());
+        frame.extend(body);
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[0..4].copy_from_slice(&((HEADER_SIZE + frame.len()) as u32).to_le_bytes());
+        data[4..6].copy_from_slice(&FILE_MAGIC.to_le_bytes());
+        data[6..8].copy_from_slice(&1u16.to_le_bytes());
+        data.extend(frame);
+        return data;
+    }
+
+    fn palette_chunk() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend(2u32.to_le_bytes());
+        body.extend(0u32.to_le_bytes());
+        body.extend(1u32.to_le_bytes());
+        body.extend([0u8; 8]);
+        body.extend(0u16.to_le_bytes());
+        body.extend([255, 0, 0, 255]);
+        body.extend(HAS_NAME.to_le_bytes());
+        body.extend([0, 128, 255, 64]);
+        body.extend(3u16.to_le_bytes());
+        body.extend(b"sky");
+        return chunk(PALETTE_CHUNK, &body);
+    }
+
+    fn old_palette_chunk() -> Vec<u8> {
+        // skip 1 entry, then 2 colors
+        let body = [1, 0, 1, 2, 10, 20, 30, 40, 50, 60];
+        return chunk(OLD_PALETTE_CHUNK, &body);
+    }
+
+    #[test]
+    fn test_parse_palette_chunk() {
+        // a layer chunk is skipped, the new palette wins over the old one
+        let layer = chunk(0x2004, &[0u8; 12]);
+        let colors = parse(&file(&[layer, old_palette_chunk(), palette_chunk()])).unwrap();
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0], ColorItem::new("0", 255, 0, 0));
+        assert_eq!(colors[1], ColorItem::new("sky", 0, 128, 255).with_alpha(64));
+    }
+
+    #[test]
+    fn test_parse_old_palette_chunk() {
+        let colors = parse(&file(&[old_palette_chunk()])).unwrap();
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0], ColorItem::new("1", 10, 20, 30));
+        assert_eq!(colors[1], ColorItem::new("2", 40, 50, 60));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(b"GIMP Palette").is_err());
+        assert!(parse(&file(&[])).is_err());
+        let mut truncated = file(&[palette_chunk()]);
+        truncated.truncate(truncated.len() - 4);
+        assert!(parse(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_parse_bad_indices() {
+        let range = |first: u32, last: u32| {
+            let mut body = Vec::new();
+            body.extend(1u32.to_le_bytes());
+            body.extend(first.to_le_bytes());
+            body.extend(last.to_le_bytes());
+            body.extend([0u8; 8]);
+            body.extend(0u16.to_le_bytes());
+            body.extend([255, 0, 0, 255]);
+            return file(&[chunk(PALETTE_CHUNK, &body)]);
+        };
+        assert!(parse(&range(0, 0)).is_ok());
+        // last before first, and an index that would allocate billions of entries
+        assert!(parse(&range(1, 0)).is_err());
+        assert!(parse(&range(u32::MAX - 1, u32::MAX)).is_err());
+        assert!(parse(&range(0x10000, 0x10000)).is_err());
+    }
+}
@@ -2,6 +2,8 @@ use super::math::clustering::algorithm::ClusteringAlgorithm;
 use super::math::clustering::cluster::Cluster;
 use super::math::clustering::dbscan::algorithm::DBSCAN;
 use super::math::clustering::gmeans::algorithm::Gmeans;
+use super::math::clustering::kmeans::algorithm::KMeans;
+use super::math::clustering::median_cut::MedianCut;
 use super::math::distance::DistanceMetric;
 use super::math::number::Float;
 use super::math::point::Point;
@@ -15,15 +17,43 @@ use super::math::point::Point;
 /// let image = image::open("./path/to/image.png").unwrap();
 /// let palette = Palette::extract_with_algorithm(&image, &Algorithm::GMeans);
 /// let palette = Palette::extract_with_algorithm(&image, &Algorithm::DBSCAN);
+/// let palette = Palette::extract_with_algorithm(&image, &Algorithm::KMeans);
+/// let palette = Palette::extract_with_algorithm(&image, &Algorithm::MedianCut);
 /// ```
-#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[derive(Debug,Clone,Copy,PartialEq)]
 pub enum Algorithm {
     /// G-means clustering algorithm.
     GMeans,
+    /// G-means clustering algorithm assigning points by the given distance metric,
+    /// `GMeans` uses `DistanceMetric::Euclidean`.
+    GMeansWith { metric: DistanceMetric },
     /// DBSCAN clustering algorithm.
     DBSCAN,
+    /// DBSCAN clustering algorithm with the given neighborhood radius in normalized
+    /// Lab and position units, measured by the given distance metric.
+    /// `DBSCAN` uses `DBSCAN_EPSILON` and `DistanceMetric::Euclidean`.
+    DBSCANWith {
+        epsilon: f64,
+        metric: DistanceMetric,
+    },
+    /// K-means clustering algorithm with k-means++ seeding and `KMEANS_CLUSTERS` clusters.
+    KMeans,
+    /// Median cut quantization into `MEDIAN_CUT_BUCKETS` buckets, fast on large images.
+    MedianCut,
 }
 
+/// The neighborhood radius used by `Algorithm::DBSCAN`.
+pub const DBSCAN_EPSILON: f64 = 0.04;
+
+/// The number of neighbors that make a point a core point of a DBSCAN cluster.
+pub const DBSCAN_MIN_POINTS: usize = 16; // 4x4 grid
+
+/// The number of clusters found by `Algorithm::KMeans`, similar ones are merged afterwards.
+pub const KMEANS_CLUSTERS: usize = 32; // 2^5
+
+/// The number of buckets cut by `Algorithm::MedianCut`, similar ones are merged afterwards.
+pub const MEDIAN_CUT_BUCKETS: usize = 32; // 2^5
+
 impl Algorithm {
     /// Applies the clustering algorithm to the given points.
     ///
@@ -37,44 +67,128 @@ impl Algorithm {
     /// * `F` - The float type used for calculations.
     /// * `P` - The point type used for calculations.
     pub(crate) fn apply<F, P>(&self, points: &[P]) -> Vec<Cluster<F, P>>
+    where
+        F: Float,
+        P: Point<F>,
+    {
+        self.apply_with_progress(points, &|_| true)
+    }
+
+    /// Applies the clustering algorithm to the given points, reporting its progress.
+    ///
+    /// # Arguments
+    /// * `points` - The points to cluster.
+    /// * `progress` - Called with the progress in `0.0..=1.0`. G-means and DBSCAN report
+    ///   along the way and stop early when it returns `false`, the other algorithms only
+    ///   report once they are done.
+    ///
+    /// # Returns
+    /// The clusters found by the algorithm.
+    ///
+    /// # Type Parameters
+    /// * `F` - The float type used for calculations.
+    /// * `P` - The point type used for calculations.
+    pub(crate) fn apply_with_progress<F, P>(
+        &self,
+        points: &[P],
+        progress: &dyn Fn(f64) -> bool,
+    ) -> Vec<Cluster<F, P>>
     where
         F: Float,
         P: Point<F>,
     {
         match self {
-            Algorithm::GMeans => cluster_with_gmeans(points),
-            Algorithm::DBSCAN => cluster_with_dbscan(points),
+            Algorithm::GMeans => cluster_with_gmeans(points, &DistanceMetric::Euclidean, progress),
+            Algorithm::GMeansWith { metric } => cluster_with_gmeans(points, metric, progress),
+            Algorithm::DBSCAN => {
+                cluster_with_dbscan(points, DBSCAN_EPSILON, &DistanceMetric::Euclidean, progress)
+            }
+            Algorithm::DBSCANWith { epsilon, metric } => {
+                cluster_with_dbscan(points, *epsilon, metric, progress)
+            }
+            Algorithm::KMeans => {
+                let clusters = cluster_with_kmeans(points);
+                progress(1.0);
+                clusters
+            }
+            Algorithm::MedianCut => {
+                let clusters = cluster_with_median_cut(points);
+                progress(1.0);
+                clusters
+            }
         }
     }
 }
 
 #[allow(unused)]
-fn cluster_with_gmeans<F, P>(points: &[P]) -> Vec<Cluster<F, P>>
+fn cluster_with_gmeans<F, P>(
+    points: &[P],
+    metric: &DistanceMetric,
+    progress: &dyn Fn(f64) -> bool,
+) -> Vec<Cluster<F, P>>
 where
     F: Float,
     P: Point<F>,
 {
+    // squaring keeps the nearest centroid and skips the square root
+    let metric = match metric {
+        DistanceMetric::Euclidean => &DistanceMetric::SquaredEuclidean,
+        metric => metric,
+    };
     let gmeans = Gmeans::new(
         32, // 2^5
         8,
         16, // 4x4 grid
         F::from_f64(1e-3),
-        &DistanceMetric::SquaredEuclidean,
+        metric,
     );
-    gmeans.fit(points)
+    gmeans.fit_with_progress(points, progress)
 }
 
 #[allow(unused)]
-fn cluster_with_dbscan<F, P>(points: &[P]) -> Vec<Cluster<F, P>>
+fn cluster_with_dbscan<F, P>(
+    points: &[P],
+    epsilon: f64,
+    metric: &DistanceMetric,
+    progress: &dyn Fn(f64) -> bool,
+) -> Vec<Cluster<F, P>>
+where
+    F: Float,
+    P: Point<F>,
+{
+    let (epsilon, metric) = match metric {
+        // squared for the metric
+        DistanceMetric::Euclidean | DistanceMetric::SquaredEuclidean => {
+            (epsilon * epsilon, &DistanceMetric::SquaredEuclidean)
+        }
+        metric => (epsilon, metric),
+    };
+    let dbscan = DBSCAN::new(DBSCAN_MIN_POINTS, F::from_f64(epsilon), metric);
+    let (clusters, _) = dbscan.fit_with_progress(points, progress);
+    clusters
+}
+#[allow(unused)]
+fn cluster_with_kmeans<F, P>(points: &[P]) -> Vec<Cluster<F, P>>
 where
     F: Float,
     P: Point<F>,
 {
-    let dbscan = DBSCAN::new(
-        16,                  // 4x4 grid
-        F::from_f64(0.0016), // 0.04^2
+    let kmeans = KMeans::new(
+        KMEANS_CLUSTERS,
+        16,
+        F::from_f64(1e-4),
+        0, // fixed seed, the same image gives the same palette
         &DistanceMetric::SquaredEuclidean,
     );
-    let (clusters, _) = dbscan.fit(points);
-    clusters
-}
\ No newline at end of file
+    kmeans.fit(points)
+}
+
+#[allow(unused)]
+fn cluster_with_median_cut<F, P>(points: &[P]) -> Vec<Cluster<F, P>>
+where
+    F: Float,
+    P: Point<F>,
+{
+    let median_cut = MedianCut::new(MEDIAN_CUT_BUCKETS);
+    median_cut.fit(points)
+}
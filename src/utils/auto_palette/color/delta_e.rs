@@ -30,6 +30,10 @@ pub enum DeltaE {
     /// The CIEDE2000 formula.
     #[allow(unused)]
     CIE2000,
+    /// The CIEDE2000 formula with parametric factors for lightness, chroma and hue,
+    /// e.g. `k_l = 2.0` as used for textiles.
+    #[allow(unused)]
+    CIE2000Weighted { k_l: f64, k_c: f64, k_h: f64 },
 }
 
 impl DeltaE {
@@ -60,7 +64,14 @@ impl DeltaE {
                 F::from_f64(0.045),
                 F::from_f64(0.015),
             ),
-            DeltaE::CIE2000 => ciede2000(lab1, lab2),
+            DeltaE::CIE2000 => ciede2000(lab1, lab2, F::one(), F::one(), F::one()),
+            DeltaE::CIE2000Weighted { k_l, k_c, k_h } => ciede2000(
+                lab1,
+                lab2,
+                F::from_f64(k_l),
+                F::from_f64(k_c),
+                F::from_f64(k_h),
+            ),
         }
     }
 }
@@ -99,7 +110,7 @@ where
 }
 
 #[allow(unused)]
-fn ciede2000<F, WP>(lab1: &Lab<F, WP>, lab2: &Lab<F, WP>) -> F
+fn ciede2000<F, WP>(lab1: &Lab<F, WP>, lab2: &Lab<F, WP>, k_l: F, k_c: F, k_h: F) -> F
 where
     F: Float,
     WP: WhitePoint<F>,
@@ -182,8 +193,50 @@ where
         .to_radians()
         .sin();
 
-    let l = delta_l_prime / (F::from_f64(1.0) * s_l);
-    let c = delta_c_prime / (F::from_f64(1.0) * s_c);
-    let h = delta_H_prime / (F::from_f64(1.0) * s_h);
+    let l = delta_l_prime / (k_l * s_l);
+    let c = delta_c_prime / (k_c * s_c);
+    let h = delta_H_prime / (k_h * s_h);
     (l * l + c * c + h * h + r_t * c * h).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::white_point::D65;
+    use super::*;
+
+    #[test]
+    fn test_ciede2000_weighted() {
+        let lab1 = Lab::<f64, D65>::new(50.0, 2.6772, -79.7751);
+        let lab2 = Lab::<f64, D65>::new(60.0, 0.0, -82.7485);
+        let unweighted = DeltaE::CIE2000.measure(&lab1, &lab2);
+        let unit = DeltaE::CIE2000Weighted {
+            k_l: 1.0,
+            k_c: 1.0,
+            k_h: 1.0,
+        }
+        .measure(&lab1, &lab2);
+        assert!((unweighted - unit).abs() < 1e-9);
+
+        // a larger k_L discounts the lightness difference
+        let textile = DeltaE::CIE2000Weighted {
+            k_l: 2.0,
+            k_c: 1.0,
+            k_h: 1.0,
+        }
+        .measure(&lab1, &lab2);
+        assert!(textile < unweighted);
+        assert!(textile > 0.0);
+
+        // with only a lightness difference the result scales by 1 / k_L
+        let gray1 = Lab::<f64, D65>::new(40.0, 0.0, 0.0);
+        let gray2 = Lab::<f64, D65>::new(45.0, 0.0, 0.0);
+        let full = DeltaE::CIE2000.measure(&gray1, &gray2);
+        let half = DeltaE::CIE2000Weighted {
+            k_l: 2.0,
+            k_c: 1.0,
+            k_h: 1.0,
+        }
+        .measure(&gray1, &gray2);
+        assert!((full / 2.0 - half).abs() < 1e-9);
+    }
 }
\ No newline at end of file
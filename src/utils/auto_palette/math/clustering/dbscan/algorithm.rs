@@ -7,7 +7,7 @@ use super::super::super::super::math::neighbors::neighbor::Neighbor;
 use super::super::super::super::math::neighbors::search::NeighborSearch;
 use super::super::super::super::math::number::Float;
 use super::super::super::super::math::point::Point;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 
 /// Struct representing DBSCAN clustering algorithm.
 ///
@@ -53,6 +53,7 @@ where
         ns: &N,
         neighbors: &[Neighbor<F>],
         labels: &mut [Label],
+        labeled: &mut usize,
     ) where
         P: Point<F>,
         N: NeighborSearch<F, P>,
@@ -82,6 +83,7 @@ where
                 match labels[secondary_index] {
                     Label::Undefined => {
                         labels[secondary_index] = Label::Marked;
+                        *labeled += 1;
                         queue.push_back(secondary_index);
                     }
                     Label::Outlier => {
@@ -103,6 +105,32 @@ where
 
     #[must_use]
     fn fit(&self, points: &[P]) -> Self::Output {
+        self.fit_with_progress(points, &|_| true)
+    }
+}
+
+impl<'a, F> DBSCAN<'a, F>
+where
+    F: Float,
+{
+    /// Fits the algorithm like `fit`, reporting the fraction of labeled points as it goes.
+    ///
+    /// # Arguments
+    /// * `points` - A slice of data points to cluster.
+    /// * `progress` - Called with the progress in `0.0..=1.0`, at most once per percent.
+    ///   Returning `false` stops labeling, the clusters found so far are returned.
+    ///
+    /// # Returns
+    /// The clusters and the indices of the outliers.
+    #[must_use]
+    pub fn fit_with_progress<P>(
+        &self,
+        points: &[P],
+        progress: &dyn Fn(f64) -> bool,
+    ) -> (Vec<Cluster<F, P>>, HashSet<usize>)
+    where
+        P: Point<F>,
+    {
         if points.is_empty() {
             return (Vec::new(), HashSet::new());
         }
@@ -110,7 +138,16 @@ where
         let neighbor_search = KDTreeSearch::new(points, self.metric);
         let mut labels = vec![Label::Undefined; points.len()];
         let mut cluster_id: usize = 0;
+        let mut labeled: usize = 0;
+        let mut reported = 0;
         for (index, point) in points.iter().enumerate() {
+            let percent = labeled * 100 / points.len();
+            if percent > reported {
+                reported = percent;
+                if !progress(labeled as f64 / points.len() as f64) {
+                    break;
+                }
+            }
             if !labels[index].is_undefined() {
                 continue;
             }
@@ -118,10 +155,14 @@ where
             let neighbors = neighbor_search.search_radius(point, self.epsilon);
             if neighbors.len() < self.min_samples {
                 labels[index] = Label::Outlier;
+                labeled += 1;
                 continue;
             }
 
             neighbors.iter().for_each(|neighbor| {
+                if labels[neighbor.index].is_undefined() {
+                    labeled += 1;
+                }
                 labels[neighbor.index] = Label::Marked;
             });
             self.expand_cluster(
@@ -130,11 +171,14 @@ where
                 &neighbor_search,
                 &neighbors,
                 &mut labels,
+                &mut labeled,
             );
             cluster_id += 1;
         }
+        progress(1.0);
 
-        let mut cluster_map: HashMap<usize, Cluster<F, P>> = HashMap::new();
+        // keyed by cluster id so the clusters come out in the order they were found
+        let mut cluster_map: BTreeMap<usize, Cluster<F, P>> = BTreeMap::new();
         let mut outlier_set: HashSet<usize> = HashSet::new();
         for (index, label) in labels.into_iter().enumerate() {
             match label {
@@ -161,4 +205,68 @@ where
             .collect();
         (clusters, outlier_set)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::super::super::math::point::Point2;
+
+    fn dataset() -> Vec<Point2<f64>> {
+        let mut points = Vec::new();
+        // a handful of well separated blobs, one outlier
+        for (cx, cy) in [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0), (5.0, 5.0)] {
+            for i in 0..5 {
+                points.push(Point2(cx + i as f64 * 0.1, cy));
+            }
+        }
+        points.push(Point2(50.0, 50.0));
+        points
+    }
+
+    #[test]
+    fn test_fit_with_progress() {
+        let points = dataset();
+        let dbscan = DBSCAN::new(3, 0.5, &DistanceMetric::Euclidean);
+        let reports = std::cell::RefCell::new(Vec::new());
+        let (clusters, outliers) = dbscan.fit_with_progress(&points, &|p| {
+            reports.borrow_mut().push(p);
+            true
+        });
+        assert_eq!((clusters, outliers), dbscan.fit(&points));
+
+        let reports = reports.into_inner();
+        assert_eq!(reports.last(), Some(&1.0));
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+        assert!(reports.iter().all(|p| (0.0..=1.0).contains(p)));
+    }
+
+    #[test]
+    fn test_fit_stopped() {
+        let points = dataset();
+        let dbscan = DBSCAN::new(3, 0.5, &DistanceMetric::Euclidean);
+        // stopping at the first report keeps only the first blob
+        let (clusters, _) = dbscan.fit_with_progress(&points, &|_| false);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].membership(), &[0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_fit_deterministic() {
+        let points = dataset();
+        let dbscan = DBSCAN::new(3, 0.5, &DistanceMetric::Euclidean);
+        let (clusters1, outliers1) = dbscan.fit(&points);
+        let (clusters2, outliers2) = dbscan.fit(&points);
+        assert_eq!(clusters1.len(), 5);
+        assert_eq!(outliers1, outliers2);
+        assert_eq!(outliers1.len(), 1);
+
+        let membership1: Vec<Vec<usize>> = clusters1.iter().map(|c| c.membership().to_vec()).collect();
+        let membership2: Vec<Vec<usize>> = clusters2.iter().map(|c| c.membership().to_vec()).collect();
+        assert_eq!(membership1, membership2);
+
+        // clusters come in the order of the points that started them
+        for (i, membership) in membership1.iter().enumerate() {
+            assert!(membership.contains(&(i * 5)));
+        }
+    }
+}
@@ -134,6 +134,29 @@ where
 
     #[must_use]
     fn fit(&self, points: &[P]) -> Self::Output {
+        self.fit_with_progress(points, &|_| true)
+    }
+}
+
+impl<'a, F> Gmeans<'a, F>
+where
+    F: Float,
+{
+    /// Fits the algorithm like `fit`, reporting the fraction of points in clusters
+    /// that will not be split again.
+    ///
+    /// # Arguments
+    /// * `points` - A slice of data points to cluster.
+    /// * `progress` - Called with the progress in `0.0..=1.0` whenever clusters settle.
+    ///   Returning `false` stops splitting, the clusters found so far are returned.
+    ///
+    /// # Returns
+    /// The clusters found by the algorithm.
+    #[must_use]
+    pub fn fit_with_progress<P>(&self, points: &[P], progress: &dyn Fn(f64) -> bool) -> Vec<Cluster<F, P>>
+    where
+        P: Point<F>,
+    {
         if points.is_empty() {
             return Vec::new();
         }
@@ -146,6 +169,7 @@ where
         let mut clusters = vec![cluster];
         let membership: Vec<usize> = (0..points.len()).collect();
         if self.assign(&mut clusters, &membership, points) {
+            progress(1.0);
             return clusters;
         }
 
@@ -154,6 +178,7 @@ where
             Priority::new(cluster, priority)
         }));
         let mut clusters = Vec::with_capacity(self.max_k);
+        let mut settled = 0;
         while clusters.len() < self.max_k {
             let Some(largest) = heap.pop() else {
                 break;
@@ -166,6 +191,15 @@ where
 
             let largest_cluster = largest.0;
             let (cluster1, cluster2) = self.split(&largest_cluster, points);
+            // All points of the cluster are equal, so it cannot be split any further.
+            if cluster1.is_empty() || cluster2.is_empty() {
+                settled += largest_size;
+                clusters.push(largest_cluster);
+                if !progress(settled as f64 / points.len() as f64) {
+                    break;
+                }
+                continue;
+            }
             let centroid1 = cluster1.centroid();
             let centroid2 = cluster2.centroid();
 
@@ -182,8 +216,12 @@ where
                 break;
             };
             if score < F::from_f64(1.8692) {
+                settled += cluster1.size() + cluster2.size();
                 clusters.push(cluster1);
                 clusters.push(cluster2);
+                if !progress(settled as f64 / points.len() as f64) {
+                    break;
+                }
             } else {
                 let priority1 = cluster1.size();
                 heap.push(Priority::new(cluster1, priority1));
@@ -192,6 +230,25 @@ where
                 heap.push(Priority::new(cluster2, priority2));
             }
         }
+        progress(1.0);
         clusters
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::super::super::math::point::Point2;
+
+    #[test]
+    fn test_fit_identical_points() {
+        // two groups of equal points, neither can be split once they are apart
+        let mut points = vec![Point2(0.0, 0.0); 40];
+        points.extend(vec![Point2(1.0, 1.0); 40]);
+        let gmeans = Gmeans::new(25, 10, 4, 1e-4, &DistanceMetric::Euclidean);
+        let clusters = gmeans.fit(&points);
+        let mut sizes: Vec<usize> = clusters.iter().map(|c| c.size()).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [40, 40]);
+    }
+}
@@ -73,7 +73,7 @@ where
         linkage: &mut impl Linkage<F>,
     ) -> Dendrogram<F> {
         let n_dataset = dataset.len();
-        let mut dendrogram = Dendrogram::new(n_dataset * 2 - 1);
+        let mut dendrogram = Dendrogram::new((n_dataset * 2).saturating_sub(1));
         dataset.iter().enumerate().for_each(|(i, _)| {
             let node = Node::new(i, None, None, F::zero());
             dendrogram.push(node);
@@ -114,4 +114,31 @@ where
         }
         dendrogram
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::super::linkage::CompleteLinkage;
+    use super::*;
+
+    fn fit(dataset: &[f64]) -> Dendrogram<f64> {
+        let distance_fn = |a: &f64, b: &f64| (a - b).abs();
+        let mut linkage = CompleteLinkage::new(dataset, &distance_fn);
+        HierarchicalClustering::new().fit_with_linkage(dataset, &mut linkage)
+    }
+
+    #[test]
+    fn test_fit_empty() {
+        let dendrogram = fit(&[]);
+        assert_eq!(dendrogram.len(), 0);
+        assert!(dendrogram.partition(1).is_empty());
+    }
+
+    #[test]
+    fn test_fit_single() {
+        let dendrogram = fit(&[1.0]);
+        assert_eq!(dendrogram.len(), 1);
+        assert_eq!(dendrogram.partition(1).len(), 1);
+        // asking for more clusters than there are elements returns the single one once
+        assert_eq!(dendrogram.partition(3).len(), 1);
+    }
+}
@@ -87,6 +87,12 @@ where
                 break;
             };
 
+            // a leaf is a cluster of its own, and must be counted only once
+            if node.node1.is_none() && node.node2.is_none() {
+                membership.push(node.clone());
+                continue;
+            }
+
             if let Some(node1) = node.node1 {
                 let node = &self.nodes[node1];
                 heap.push(Priority::new(node, node.distance));
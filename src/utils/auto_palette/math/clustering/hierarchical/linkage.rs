@@ -67,6 +67,14 @@ where
         DF: Fn(&T, &T) -> F,
     {
         let n_elements = dataset.len();
+        // nothing to measure, `n_elements * 2 - 1` would underflow
+        if n_elements == 0 {
+            return Self {
+                distances: Vec::new(),
+                size: 0,
+            };
+        }
+        // a single element still gets its one diagonal slot
         let size = n_elements * 2 - 1;
         let capacity = size * (size + 1) / 2;
         let mut distances = vec![F::max_value(); capacity];
@@ -286,4 +294,87 @@ where
         self.next_index += 1;
         label
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(n: usize) -> DistanceMatrix<f64> {
+        let dataset: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        DistanceMatrix::new(&dataset, &|a: &f64, b: &f64| (a - b).abs())
+    }
+
+    #[test]
+    fn test_distance_matrix_new() {
+        let matrix = matrix(4);
+        assert_eq!(matrix.size, 7);
+        assert_eq!(matrix.distances.len(), 7 * 8 / 2);
+        assert_eq!(matrix.get(0, 1), 1.0);
+        assert_eq!(matrix.get(0, 3), 3.0);
+        assert_eq!(matrix.get(1, 3), 2.0);
+        assert_eq!(matrix.get(2, 3), 1.0);
+        // merged nodes have no distance until they are set
+        assert_eq!(matrix.get(0, 4), f64::MAX);
+        assert_eq!(matrix.get(5, 6), f64::MAX);
+    }
+
+    #[test]
+    fn test_distance_matrix_symmetry() {
+        let mut matrix = matrix(3);
+        matrix.set(1, 4, 2.5);
+        assert_eq!(matrix.get(4, 1), 2.5);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(matrix.get(i, j), matrix.get(j, i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance_matrix_index() {
+        // every pair up to the merged node capacity has its own slot
+        let matrix = matrix(5);
+        let mut seen = HashSet::new();
+        for i in 0..matrix.size {
+            for j in i..matrix.size {
+                let index = matrix.index(i, j);
+                assert!(index < matrix.distances.len());
+                assert!(seen.insert(index), "({}, {}) collides at {}", i, j, index);
+                assert_eq!(index, matrix.index(j, i));
+            }
+        }
+        assert_eq!(seen.len(), matrix.distances.len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_distance_matrix_out_of_range() {
+        let matrix = matrix(2);
+        let _ = matrix.index(0, 3);
+    }
+
+    #[test]
+    fn test_distance_matrix_empty() {
+        let matrix = matrix(0);
+        assert_eq!(matrix.size, 0);
+        assert!(matrix.distances.is_empty());
+    }
+
+    #[test]
+    fn test_distance_matrix_single() {
+        let matrix = matrix(1);
+        assert_eq!(matrix.size, 1);
+        assert_eq!(matrix.distances.len(), 1);
+        assert_eq!(matrix.get(0, 0), f64::MAX);
+    }
+
+    #[test]
+    fn test_complete_linkage_merge() {
+        let dataset = vec![0.0, 1.0, 5.0];
+        let mut linkage = CompleteLinkage::new(&dataset, &|a: &f64, b: &f64| (a - b).abs());
+        let label = linkage.merge(0, 1);
+        assert_eq!(label, 3);
+        assert_eq!(linkage.distance(2, 3), 5.0);
+        assert_eq!(linkage.distance(0, 2), f64::MAX);
+    }
+}
@@ -0,0 +1,207 @@
+use super::super::super::super::math::clustering::algorithm::ClusteringAlgorithm;
+use super::super::super::super::math::clustering::cluster::Cluster;
+use super::super::super::super::math::distance::DistanceMetric;
+use super::super::super::super::math::neighbors::kdtree::search::KDTreeSearch;
+use super::super::super::super::math::neighbors::search::NeighborSearch;
+use super::super::super::super::math::number::Float;
+use super::super::super::super::math::point::Point;
+use rand::{Rng, SeedableRng};
+
+/// Struct representing K-means clustering algorithm with k-means++ seeding.
+///
+/// # Type Parameters
+/// * `F` - The float type used for calculations.
+///
+/// # References
+/// * [k-means++: The Advantages of Careful Seeding](https://theory.stanford.edu/~sergei/papers/kMeansPP-soda.pdf)
+#[derive(Debug, PartialEq)]
+pub struct KMeans<'a, F>
+where
+    F: Float,
+{
+    k: usize,
+    max_iter: usize,
+    tolerance: F,
+    seed: u64,
+    metric: &'a DistanceMetric,
+}
+
+impl<'a, F> KMeans<'a, F>
+where
+    F: Float,
+{
+    /// Creates a new `KMeans` instance.
+    ///
+    /// # Arguments
+    /// * `k` - The number of clusters.
+    /// * `max_iter` - The maximum number of iterations.
+    /// * `tolerance` - The minimum squared movement of any centroid required to continue iterating.
+    /// * `seed` - The seed of the k-means++ seeding, the same seed gives the same clusters.
+    /// * `metric` - The distance metric used for seeding and assignment.
+    ///
+    /// # Returns
+    /// A new `KMeans` instance.
+    #[must_use]
+    pub fn new(
+        k: usize,
+        max_iter: usize,
+        tolerance: F,
+        seed: u64,
+        metric: &'a DistanceMetric,
+    ) -> Self {
+        assert!(k >= 1, "The number of clusters must be at least 1.");
+        Self {
+            k,
+            max_iter,
+            tolerance,
+            seed,
+            metric,
+        }
+    }
+
+    /// Picks the initial centroids, each one with a probability proportional to
+    /// its distance from the centroids picked so far.
+    #[must_use]
+    fn seed_centroids<P: Point<F>>(&self, points: &[P]) -> Vec<P> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(self.seed);
+        let mut centroids = Vec::with_capacity(self.k);
+        centroids.push(points[rng.gen_range(0..points.len())]);
+
+        let mut distances: Vec<F> = points
+            .iter()
+            .map(|point| self.metric.measure(point, &centroids[0]))
+            .collect();
+        while centroids.len() < self.k.min(points.len()) {
+            let total = distances.iter().fold(F::zero(), |sum, &d| sum + d);
+            if total <= F::zero() {
+                // every point already sits on a centroid
+                break;
+            }
+
+            let mut target = F::from_f64(rng.gen::<f64>()) * total;
+            let mut chosen = points.len() - 1;
+            for (index, &distance) in distances.iter().enumerate() {
+                if target < distance {
+                    chosen = index;
+                    break;
+                }
+                target -= distance;
+            }
+
+            let centroid = points[chosen];
+            for (distance, point) in distances.iter_mut().zip(points) {
+                *distance = distance.min(self.metric.measure(point, &centroid));
+            }
+            centroids.push(centroid);
+        }
+        centroids
+    }
+
+    #[must_use]
+    fn assign<P: Point<F>>(&self, clusters: &mut [Cluster<F, P>], points: &[P]) -> bool {
+        let mut centroids = Vec::with_capacity(clusters.len());
+        for cluster in clusters.iter_mut() {
+            centroids.push(*cluster.centroid());
+            cluster.clear();
+        }
+
+        let neighbor_search = KDTreeSearch::new(&centroids, self.metric);
+        for (index, point) in points.iter().enumerate() {
+            let Some(nearest) = neighbor_search.search_nearest(point) else {
+                continue;
+            };
+            clusters[nearest.index].insert(index, point);
+        }
+
+        let mut converged = true;
+        for (cluster, old_centroid) in clusters.iter_mut().zip(centroids) {
+            if cluster.is_empty() {
+                // keep the old centroid so the cluster can pick up points again
+                *cluster = Cluster::new(old_centroid);
+                continue;
+            }
+
+            let movement = DistanceMetric::SquaredEuclidean.measure(&old_centroid, cluster.centroid());
+            if movement >= self.tolerance {
+                converged = false;
+            }
+        }
+        converged
+    }
+}
+
+impl<'a, F, P> ClusteringAlgorithm<F, P> for KMeans<'a, F>
+where
+    F: Float,
+    P: Point<F>,
+{
+    type Output = Vec<Cluster<F, P>>;
+
+    #[must_use]
+    fn fit(&self, points: &[P]) -> Self::Output {
+        if points.is_empty() {
+            return Vec::new();
+        }
+
+        let mut clusters: Vec<Cluster<F, P>> = self
+            .seed_centroids(points)
+            .into_iter()
+            .map(Cluster::new)
+            .collect();
+        for _ in 0..self.max_iter {
+            if self.assign(&mut clusters, points) {
+                break;
+            }
+        }
+        clusters.retain(|cluster| !cluster.is_empty());
+        clusters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::super::super::math::point::Point2;
+
+    fn dataset() -> Vec<Point2<f64>> {
+        let mut points = Vec::new();
+        for (cx, cy) in [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)] {
+            for i in 0..10 {
+                points.push(Point2(cx + (i % 3) as f64 * 0.1, cy + (i / 3) as f64 * 0.1));
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_fit() {
+        let points = dataset();
+        let kmeans = KMeans::new(3, 50, 1e-6, 0, &DistanceMetric::SquaredEuclidean);
+        let clusters = kmeans.fit(&points);
+        assert_eq!(clusters.len(), 3);
+        // every blob ends up as one cluster
+        let mut memberships: Vec<Vec<usize>> = clusters
+            .iter()
+            .map(|cluster| {
+                let mut membership = cluster.membership().to_vec();
+                membership.sort();
+                membership
+            })
+            .collect();
+        memberships.sort();
+        let expected: Vec<Vec<usize>> = (0..3).map(|blob| (blob * 10..blob * 10 + 10).collect()).collect();
+        assert_eq!(memberships, expected);
+
+        // the same seed gives the same result
+        assert_eq!(kmeans.fit(&points), clusters);
+    }
+
+    #[test]
+    fn test_fit_fewer_points_than_k() {
+        let points = vec![Point2(0.0, 0.0), Point2(0.0, 0.0), Point2(1.0, 1.0)];
+        let kmeans = KMeans::new(8, 10, 1e-6, 0, &DistanceMetric::SquaredEuclidean);
+        let clusters = kmeans.fit(&points);
+        assert_eq!(clusters.len(), 2);
+        assert!(kmeans.fit(&Vec::<Point2<f64>>::new()).is_empty());
+    }
+}
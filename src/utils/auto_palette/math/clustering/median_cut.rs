@@ -0,0 +1,133 @@
+use super::super::super::math::clustering::algorithm::ClusteringAlgorithm;
+use super::super::super::math::clustering::cluster::Cluster;
+use super::super::super::math::number::Float;
+use super::super::super::math::point::Point;
+use std::cmp::Ordering;
+
+/// The number of leading components that bound a box, the Lab color of a pixel point.
+/// The position components that follow are averaged but never split on.
+const COLOR_COMPONENTS: usize = 3;
+
+/// Struct representing median cut quantization.
+///
+/// # Type Parameters
+/// * `F` - The float type used for calculations.
+///
+/// # References
+/// * [Color Image Quantization for Frame Buffer Display](https://dl.acm.org/doi/10.1145/965145.801294)
+#[derive(Debug, PartialEq)]
+pub struct MedianCut<F>
+where
+    F: Float,
+{
+    k: usize,
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F> MedianCut<F>
+where
+    F: Float,
+{
+    /// Creates a new `MedianCut` instance.
+    ///
+    /// # Arguments
+    /// * `k` - The number of buckets to split the points into.
+    ///
+    /// # Returns
+    /// A new `MedianCut` instance.
+    #[must_use]
+    pub fn new(k: usize) -> Self {
+        assert!(k >= 1, "The number of buckets must be at least 1.");
+        Self {
+            k,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the longest color axis of the bucket and its extent.
+    #[must_use]
+    fn longest_axis<P: Point<F>>(bucket: &[usize], points: &[P]) -> (usize, F) {
+        let mut longest = (0, F::zero());
+        for axis in 0..COLOR_COMPONENTS.min(points[bucket[0]].dimension()) {
+            let mut min = points[bucket[0]][axis];
+            let mut max = min;
+            for &index in bucket.iter() {
+                min = min.min(points[index][axis]);
+                max = max.max(points[index][axis]);
+            }
+            if max - min > longest.1 {
+                longest = (axis, max - min);
+            }
+        }
+        longest
+    }
+}
+
+impl<F, P> ClusteringAlgorithm<F, P> for MedianCut<F>
+where
+    F: Float,
+    P: Point<F>,
+{
+    type Output = Vec<Cluster<F, P>>;
+
+    #[must_use]
+    fn fit(&self, points: &[P]) -> Self::Output {
+        if points.is_empty() {
+            return Vec::new();
+        }
+
+        let mut buckets = vec![(0..points.len()).collect::<Vec<usize>>()];
+        while buckets.len() < self.k {
+            // the bucket spanning the widest range is cut next
+            let Some((index, axis)) = buckets
+                .iter()
+                .enumerate()
+                .filter(|(_, bucket)| bucket.len() > 1)
+                .map(|(index, bucket)| (index, Self::longest_axis(bucket, points)))
+                .filter(|(_, (_, extent))| *extent > F::zero())
+                .max_by(|(_, (_, a)), (_, (_, b))| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                .map(|(index, (axis, _))| (index, axis))
+            else {
+                // every bucket is a single color
+                break;
+            };
+
+            let mut bucket = buckets.swap_remove(index);
+            bucket.sort_by(|&a, &b| {
+                points[a][axis]
+                    .partial_cmp(&points[b][axis])
+                    .unwrap_or(Ordering::Equal)
+            });
+            let upper = bucket.split_off(bucket.len() / 2);
+            buckets.push(bucket);
+            buckets.push(upper);
+        }
+
+        buckets
+            .into_iter()
+            .map(|bucket| {
+                let mut cluster = Cluster::default();
+                for index in bucket {
+                    cluster.insert(index, &points[index]);
+                }
+                cluster
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::super::math::point::Point5;
+
+    #[test]
+    fn test_fit_stops_at_single_colors() {
+        let points = vec![Point5(0.5, 0.5, 0.5, 0.0, 0.0), Point5(0.5, 0.5, 0.5, 1.0, 1.0)];
+        let clusters = MedianCut::new(4).fit(&points);
+        // the positions differ but are never split on
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].size(), 2);
+        assert!(MedianCut::<f64>::new(4).fit(&Vec::<Point5<f64>>::new()).is_empty());
+    }
+}
@@ -4,3 +4,5 @@ mod cmp;
 pub mod dbscan;
 pub mod gmeans;
 pub mod hierarchical;
+pub mod kmeans;
+pub mod median_cut;
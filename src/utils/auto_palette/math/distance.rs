@@ -2,10 +2,14 @@ use super::super::math::number::Float;
 use super::super::math::point::Point;
 
 /// Enum representing distance metric.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DistanceMetric {
     Euclidean,
     SquaredEuclidean,
+    /// Sum of the absolute differences.
+    Manhattan,
+    /// Largest absolute difference.
+    Chebyshev,
 }
 
 impl DistanceMetric {
@@ -25,6 +29,8 @@ impl DistanceMetric {
         match *self {
             DistanceMetric::Euclidean => squared_euclidean(point1, point2).sqrt(),
             DistanceMetric::SquaredEuclidean => squared_euclidean(point1, point2),
+            DistanceMetric::Manhattan => manhattan(point1, point2),
+            DistanceMetric::Chebyshev => chebyshev(point1, point2),
         }
     }
 }
@@ -41,3 +47,82 @@ fn squared_euclidean<F: Float, P: Point<F>>(point1: &P, point2: &P) -> F {
             total
         })
 }
+
+#[inline]
+#[must_use]
+fn manhattan<F: Float, P: Point<F>>(point1: &P, point2: &P) -> F {
+    point1
+        .iter()
+        .zip(point2.iter())
+        .fold(F::zero(), |total, (value1, value2)| {
+            total + (value1 - value2).abs()
+        })
+}
+
+#[inline]
+#[must_use]
+fn chebyshev<F: Float, P: Point<F>>(point1: &P, point2: &P) -> F {
+    point1
+        .iter()
+        .zip(point2.iter())
+        .fold(F::zero(), |max, (value1, value2)| {
+            max.max((value1 - value2).abs())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::math::point::Point3;
+    use super::*;
+
+    #[test]
+    fn test_euclidean() {
+        let point1 = Point3(1.0, 2.0, 3.0);
+        let point2 = Point3(4.0, -2.0, 3.0);
+        assert_eq!(DistanceMetric::Euclidean.measure(&point1, &point2), 5.0);
+        assert_eq!(
+            DistanceMetric::SquaredEuclidean.measure(&point1, &point2),
+            25.0
+        );
+    }
+
+    #[test]
+    fn test_manhattan() {
+        let point1 = Point3(1.0, 2.0, 3.0);
+        let point2 = Point3(4.0, -2.0, 3.5);
+        // 3 + 4 + 0.5
+        assert_eq!(DistanceMetric::Manhattan.measure(&point1, &point2), 7.5);
+        assert_eq!(DistanceMetric::Manhattan.measure(&point2, &point1), 7.5);
+        assert_eq!(DistanceMetric::Manhattan.measure(&point1, &point1), 0.0);
+    }
+
+    #[test]
+    fn test_manhattan_triangle_inequality() {
+        let points = [
+            Point3(0.0, 0.0, 0.0),
+            Point3(1.0, 2.0, 3.0),
+            Point3(-4.0, 0.5, 2.0),
+            Point3(0.25, -3.0, -1.0),
+        ];
+        for a in points.iter() {
+            for b in points.iter() {
+                for c in points.iter() {
+                    let direct: f64 = DistanceMetric::Manhattan.measure(a, c);
+                    let detour = DistanceMetric::Manhattan.measure(a, b)
+                        + DistanceMetric::Manhattan.measure(b, c);
+                    assert!(direct <= detour + 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chebyshev() {
+        let point1 = Point3(1.0, 2.0, 3.0);
+        let point2 = Point3(4.0, -2.0, 3.5);
+        // the largest of 3, 4 and 0.5
+        assert_eq!(DistanceMetric::Chebyshev.measure(&point1, &point2), 4.0);
+        assert_eq!(DistanceMetric::Chebyshev.measure(&point2, &point1), 4.0);
+        assert_eq!(DistanceMetric::Chebyshev.measure(&point1, &point1), 0.0);
+    }
+}
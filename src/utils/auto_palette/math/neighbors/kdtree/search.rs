@@ -51,61 +51,6 @@ where
         }
     }
 
-    #[inline]
-    #[must_use]
-    fn partition_by_key<V, T>(slice: &mut [T], value_fn: &V) -> usize
-    where
-        T: Ord,
-        V: Fn(&T) -> F,
-    {
-        let pivot = slice.len() / 2;
-        let pivot_value = value_fn(&slice[pivot]);
-
-        let mut left = 0;
-        let mut right = slice.len() - 1;
-        while left <= right {
-            while value_fn(&slice[left]) < pivot_value {
-                left += 1;
-            }
-            while value_fn(&slice[right]) > pivot_value {
-                right -= 1;
-            }
-
-            if left <= right {
-                slice.swap(left, right);
-                left += 1;
-                right -= 1;
-            }
-        }
-        left - 1
-    }
-
-    #[inline]
-    #[must_use]
-    fn find_nth_index<T, V>(slice: &mut [T], n: usize, value_fn: V) -> usize
-    where
-        T: Ord,
-        V: Fn(&T) -> F,
-    {
-        if slice.len() <= 1 {
-            return 0;
-        }
-
-        let pivot_index = Self::partition_by_key(slice, &value_fn);
-        match n.cmp(&pivot_index) {
-            Ordering::Less => Self::find_nth_index(&mut slice[..pivot_index], n, value_fn),
-            Ordering::Greater => {
-                let index = Self::find_nth_index(
-                    &mut slice[pivot_index + 1..],
-                    n - pivot_index - 1,
-                    value_fn,
-                );
-                index + pivot_index + 1
-            }
-            _ => pivot_index,
-        }
-    }
-
     #[inline]
     #[must_use]
     fn build_node(points: &[P], indices: &mut [usize], depth: usize) -> Option<KDNode> {
@@ -115,13 +60,16 @@ where
 
         let axis = depth % points[0].dimension();
         let median = indices.len() / 2;
-        let median_index = Self::find_nth_index(indices, median, |&index: &usize| {
-            let point = &points[index];
-            point[axis]
+        // the median lands at `median`, with no larger value before it and no smaller after it,
+        // so both halves around it are complete
+        indices.select_nth_unstable_by(median, |&index1, &index2| {
+            points[index1][axis]
+                .partial_cmp(&points[index2][axis])
+                .unwrap_or(Ordering::Equal)
         });
 
         let node = KDNode::new(
-            indices[median_index],
+            indices[median],
             axis,
             Self::build_node(points, &mut indices[..median], depth + 1),
             Self::build_node(points, &mut indices[median + 1..], depth + 1),
@@ -129,6 +77,17 @@ where
         Some(node)
     }
 
+    /// Returns the smallest distance, in the units of the metric, between the query and any point
+    /// on the other side of a split that is `delta` away along the split axis.
+    #[inline]
+    #[must_use]
+    fn axis_distance(&self, delta: F) -> F {
+        match *self.metric {
+            DistanceMetric::SquaredEuclidean => delta * delta,
+            _ => delta.abs(),
+        }
+    }
+
     #[inline]
     fn search_recursively(
         &self,
@@ -168,7 +127,7 @@ where
         }
 
         let delta = query[node.axis] - point[node.axis];
-        if neighbors.len() < k || delta.abs() <= neighbors[k - 1].distance {
+        if neighbors.len() < k || self.axis_distance(delta) <= neighbors[k - 1].distance {
             self.search_recursively(node.left(), query, k, neighbors);
             self.search_recursively(node.right(), query, k, neighbors);
         } else if delta < F::zero() {
@@ -192,14 +151,14 @@ where
 
         let point = &self.points[node.index];
         let distance = self.metric.measure(point, query);
-        let neighbor = Neighbor::new(node.index, distance);
 
+        // a node farther than the best so far can still have closer points below it
         let best_distance = best_neighbor.map(|n| n.distance).unwrap_or(F::max_value());
-        if distance >= best_distance {
-            return best_neighbor;
-        }
-
-        let nearest = Some(neighbor);
+        let nearest = if distance < best_distance {
+            Some(Neighbor::new(node.index, distance))
+        } else {
+            best_neighbor
+        };
         if node.is_leaf() {
             return nearest;
         }
@@ -213,7 +172,7 @@ where
 
         let nearest = self.search_nearest_recursively(primary, query, nearest);
         let best_distance = nearest.map(|n| n.distance).unwrap_or(F::max_value());
-        if delta.abs() < best_distance {
+        if self.axis_distance(delta) < best_distance {
             self.search_nearest_recursively(secondary, query, nearest)
         } else {
             nearest
@@ -239,7 +198,7 @@ where
         }
 
         let delta = query[node.axis] - point[node.axis];
-        if delta.abs() <= radius {
+        if self.axis_distance(delta) <= radius {
             self.search_radius_recursively(node.left(), query, radius, neighbors);
             self.search_radius_recursively(node.right(), query, radius, neighbors);
         } else if delta < F::zero() {
@@ -287,4 +246,62 @@ where
         self.search_radius_recursively(&self.root, query, radius, &mut neighbors);
         neighbors
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::super::super::math::point::Point2;
+
+    #[test]
+    fn test_search_radius() {
+        // runs of equal coordinates used to lose points when the tree was built
+        let mut points = Vec::new();
+        for (cx, cy) in [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0), (5.0, 5.0)] {
+            for i in 0..5 {
+                points.push(Point2(cx + i as f64 * 0.1, cy));
+            }
+        }
+        let search = KDTreeSearch::new(&points, &DistanceMetric::Euclidean);
+        for (index, point) in points.iter().enumerate() {
+            let mut found: Vec<usize> = search.search_radius(point, 0.5).iter().map(|n| n.index).collect();
+            found.sort_unstable();
+            let blob = index / 5 * 5;
+            assert_eq!(found, (blob..blob + 5).collect::<Vec<_>>());
+        }
+        assert_eq!(search.search_nearest(&Point2(10.32, 10.0)).map(|n| n.index), Some(18));
+        let nearest: Vec<usize> = search.search(&Point2(0.0, 0.0), 3).iter().map(|n| n.index).collect();
+        assert_eq!(nearest, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_search_nearest() {
+        // (1, 10) is farther from the query than the root, the closest point is below it
+        let points = [
+            Point2(-5.0, 0.0),
+            Point2(-6.0, 0.0),
+            Point2(0.0, 0.0),
+            Point2(1.0, 10.0),
+            Point2(1.0, 0.5),
+        ];
+        for metric in [DistanceMetric::Euclidean, DistanceMetric::SquaredEuclidean] {
+            let search = KDTreeSearch::new(&points, &metric);
+            assert_eq!(search.search_nearest(&Point2(1.0, 0.0)).map(|n| n.index), Some(4));
+        }
+
+        // across the root split by 0.3, which is more than the squared distance 0.25 to the root
+        let points = [
+            Point2(-5.0, 0.0),
+            Point2(-6.0, 0.0),
+            Point2(0.0, 0.0),
+            Point2(0.1, 0.35),
+            Point2(3.0, 3.0),
+        ];
+        let search = KDTreeSearch::new(&points, &DistanceMetric::SquaredEuclidean);
+        let query = Point2(-0.3, 0.4);
+        assert_eq!(search.search_nearest(&query).map(|n| n.index), Some(3));
+        assert_eq!(search.search(&query, 1)[0].index, 3);
+        let found: Vec<usize> = search.search_radius(&query, 0.2).iter().map(|n| n.index).collect();
+        assert_eq!(found, [3]);
+    }
+}
@@ -151,6 +151,35 @@ where
     }
 }
 
+/// Struct representing a point in 4-dimensional space.
+///
+/// # Type Parameters
+/// * `F` - The type of the point's components.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Point4<F: Float>(pub F, pub F, pub F, pub F);
+
+impl<F> Index<usize> for Point4<F>
+where
+    F: Float,
+{
+    type Output = F;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.0,
+            1 => &self.1,
+            2 => &self.2,
+            3 => &self.3,
+            _ => panic!(
+                "Index {} out of bounds for dimension {}",
+                index,
+                self.dimension()
+            ),
+        }
+    }
+}
+
 /// Struct representing a point in 5-dimensional space.
 ///
 /// # Type Parameters
@@ -304,6 +333,15 @@ macro_rules! impl_point {
 
 impl_point!(Point2 { x: 0, y: 1 }, 2);
 impl_point!(Point3 { x: 0, y: 1, z: 2 }, 3);
+impl_point!(
+    Point4 {
+        w: 0,
+        x: 1,
+        y: 2,
+        z: 3
+    },
+    4
+);
 impl_point!(
     Point5 {
         v: 0,
@@ -313,4 +351,68 @@ impl_point!(
         z: 4
     },
     5
-);
\ No newline at end of file
+);
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::math::clustering::algorithm::ClusteringAlgorithm;
+    use super::super::super::math::clustering::dbscan::algorithm::DBSCAN;
+    use super::super::super::math::distance::DistanceMetric;
+    use super::super::super::math::neighbors::kdtree::search::KDTreeSearch;
+    use super::super::super::math::neighbors::search::NeighborSearch;
+    use super::*;
+
+    #[test]
+    fn test_point4_arithmetic() {
+        let a = Point4(1.0, 2.0, 3.0, 4.0);
+        let b = Point4(0.5, 0.5, 0.5, 0.5);
+        assert_eq!(a.dimension(), 4);
+        assert_eq!(a + b, Point4(1.5, 2.5, 3.5, 4.5));
+        assert_eq!(a + &b, Point4(1.5, 2.5, 3.5, 4.5));
+        assert_eq!(a - &b, Point4(0.5, 1.5, 2.5, 3.5));
+        assert_eq!(a * 2.0, Point4(2.0, 4.0, 6.0, 8.0));
+        assert_eq!(a / 2.0, Point4(0.5, 1.0, 1.5, 2.0));
+        assert_eq!(a.dot(&b), 5.0);
+        assert_eq!(a.iter().collect::<Vec<f64>>(), vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(a[3], 4.0);
+        assert!(Point4::<f64>::zero().is_zero());
+        assert_eq!(format!("{}", a), "Point4(1.0, 2.0, 3.0, 4.0)");
+
+        let mut c = a;
+        c += &b;
+        c -= &a;
+        assert_eq!(c, b);
+        c *= 4.0;
+        c /= 2.0;
+        assert_eq!(c, Point4(1.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_point4_index_out_of_bounds() {
+        let _ = Point4(0.0, 0.0, 0.0, 0.0)[4];
+    }
+
+    #[test]
+    fn test_point4_search_and_clustering() {
+        // two groups that only differ in the 4th coordinate, like opaque and clear pixels
+        let mut points = Vec::new();
+        for i in 0..5 {
+            let offset = i as f64 * 0.01;
+            points.push(Point4(0.5 + offset, 0.2, 0.2, 1.0));
+            points.push(Point4(0.5 + offset, 0.2, 0.2, 0.0));
+        }
+        let search = KDTreeSearch::new(&points, &DistanceMetric::Euclidean);
+        let nearest = search.search(&Point4(0.5, 0.2, 0.2, 0.9), 1);
+        assert_eq!(nearest[0].index, 0);
+
+        let dbscan = DBSCAN::new(3, 0.1, &DistanceMetric::Euclidean);
+        let (clusters, outliers) = dbscan.fit(&points);
+        assert_eq!(clusters.len(), 2);
+        assert!(outliers.is_empty());
+        for cluster in clusters.iter() {
+            let alpha = points[cluster.membership()[0]].3;
+            assert!(cluster.membership().iter().all(|&i| points[i].3 == alpha));
+        }
+    }
+}
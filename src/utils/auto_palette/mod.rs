@@ -11,4 +11,8 @@ pub use algorithm::*;
 pub use color::*;
 pub use palette::*;
 pub use swatch::*;
-pub use theme::*;
\ No newline at end of file
+pub use theme::*;
+pub use math::distance::DistanceMetric;
+pub use math::neighbors::kdtree::search::KDTreeSearch;
+pub use math::neighbors::search::NeighborSearch;
+pub use math::point::Point3;
@@ -11,11 +11,14 @@ use super::math::clustering::hierarchical::dendrogram::Dendrogram;
 use super::math::clustering::hierarchical::linkage::CompleteLinkage;
 use super::math::clustering::hierarchical::node::Node;
 use super::math::distance::DistanceMetric;
+use super::math::neighbors::kdtree::search::KDTreeSearch;
+use super::math::neighbors::search::NeighborSearch;
 use super::math::number::Float;
-use super::math::point::{Point3, Point5};
+use super::math::point::{Point, Point3, Point4, Point5};
 use super::swatch::Swatch;
-use super::{Algorithm, Theme};
-use image::{ColorType, DynamicImage};
+use super::{Algorithm, Theme, DBSCAN_MIN_POINTS};
+use image::imageops::FilterType;
+use image::DynamicImage;
 use num_traits::Zero;
 use std::cmp::{Ordering, Reverse};
 
@@ -68,7 +71,7 @@ where
     /// A new extracted `Palette` instance.
     #[allow(unused)]
     pub fn extract(image: &DynamicImage) -> Palette<F> {
-        Self::extract_with_algorithm(image, &Algorithm::DBSCAN)
+        PaletteBuilder::new().build(image)
     }
 
     /// Extract a color palette from the given image using the specified algorithm.
@@ -81,36 +84,199 @@ where
     /// A new extracted `Palette` instance.
     #[allow(unused)]
     pub fn extract_with_algorithm(image: &DynamicImage, algorithm: &Algorithm) -> Palette<F> {
-        let image_data = match image.color() {
-            ColorType::Rgb8 => ImageData::from(&image.to_rgb8()),
-            ColorType::Rgba8 => ImageData::from(&image.to_rgba8()),
-            _ => unimplemented!("Unsupported image type"),
-        };
-        let pixels = convert_to_pixels(&image_data);
+        PaletteBuilder::new().algorithm(*algorithm).build(image)
+    }
 
-        // Merge pixels that are close in color and position, and exclude outliers.
-        let pixel_clusters = algorithm.apply(&pixels);
-        let (candidates, colors): (Vec<_>, Vec<_>) = pixel_clusters
-            .iter()
-            .filter_map(|cluster| {
-                pixel_cluster_to_swatch(cluster, image_data.width(), image_data.height())
-            })
-            .map(|swatch| {
-                let Lab { l, a, b, .. } = swatch.color().to_lab();
-                let point = Point3(l, a, b);
-                (swatch, point)
-            })
-            .unzip();
+    /// Extract a color palette where every pixel adds its weight instead of 1
+    /// to the population of its swatch.
+    ///
+    /// # Arguments
+    /// * `image` - The image to use for color palette extraction.
+    /// * `algorithm` - The algorithm to use for color palette extraction.
+    /// * `weight` - The weight of the pixel at (x, y).
+    /// * `with_alpha` - Whether the opacity of an RGBA image is clustered along with the color,
+    ///   so semi-transparent areas become swatches of their own. Fully transparent pixels are
+    ///   always skipped.
+    ///
+    /// # Returns
+    /// A new extracted `Palette` instance.
+    #[allow(unused)]
+    pub fn extract_weighted<W>(
+        image: &DynamicImage,
+        algorithm: &Algorithm,
+        weight: &W,
+        with_alpha: bool,
+    ) -> Palette<F>
+    where
+        W: Fn(u32, u32) -> F,
+    {
+        Self::extract_with_progress(image, algorithm, weight, with_alpha, &|_| true)
+    }
 
-        // Merge colors with small color differences and extract the dominant swatches.
-        // According to the Digital Color Imaging Handbook, a ∆E ≤ 2.3 is perceived as identical by human perception.
-        let dbscan = DBSCAN::new(1, F::from_f64(2.3), &DistanceMetric::Euclidean);
-        let (swatch_clusters, _) = dbscan.fit(&colors);
-        let swatches = swatch_clusters
-            .iter()
-            .filter_map(|cluster| color_cluster_to_swatch(cluster, &candidates))
-            .collect();
-        Self { swatches }
+    /// Extract a color palette like `extract_weighted`, reporting the progress of the clustering.
+    ///
+    /// # Arguments
+    /// * `image` - The image to use for color palette extraction.
+    /// * `algorithm` - The algorithm to use for color palette extraction.
+    /// * `weight` - The weight of the pixel at (x, y), see `extract_weighted`.
+    /// * `with_alpha` - Whether the opacity is clustered along with the color, see `extract_weighted`.
+    /// * `progress` - Called on the extracting thread with the progress in `0.0..=1.0`,
+    ///   returning `false` stops the clustering early with the clusters found so far.
+    ///
+    /// # Returns
+    /// A new extracted `Palette` instance.
+    pub fn extract_with_progress<W>(
+        image: &DynamicImage,
+        algorithm: &Algorithm,
+        weight: &W,
+        with_alpha: bool,
+        progress: &dyn Fn(f64) -> bool,
+    ) -> Palette<F>
+    where
+        W: Fn(u32, u32) -> F,
+    {
+        let candidates = extract_candidates(image, algorithm, weight, with_alpha, progress);
+        Self {
+            swatches: merge_candidates(candidates, with_alpha),
+        }
+    }
+
+    /// Extract a color palette like `extract_with_progress` from pixels converted beforehand,
+    /// which skips the conversion when the same image is extracted repeatedly.
+    ///
+    /// # Arguments
+    /// * `pixels` - The converted pixels of the image, see `Pixels::new`.
+    /// * `algorithm` - The algorithm to use for color palette extraction.
+    /// * `weight` - The weight of the pixel at (x, y), see `extract_weighted`.
+    /// * `with_alpha` - Whether the opacity is clustered along with the color, see `extract_weighted`.
+    /// * `progress` - Called with the progress in `0.0..=1.0`, see `extract_with_progress`.
+    ///
+    /// # Returns
+    /// A new extracted `Palette` instance.
+    pub fn extract_from_pixels<W>(
+        pixels: &Pixels<F>,
+        algorithm: &Algorithm,
+        weight: &W,
+        with_alpha: bool,
+        progress: &dyn Fn(f64) -> bool,
+    ) -> Palette<F>
+    where
+        W: Fn(u32, u32) -> F,
+    {
+        let candidates = pixel_candidates(pixels, algorithm, weight, with_alpha, progress);
+        Self {
+            swatches: merge_candidates(candidates, with_alpha),
+        }
+    }
+
+    /// Extract a color palette tile by tile, which bounds the memory of the clustering
+    /// and runs the tiles in parallel. The partial swatches of all tiles are merged
+    /// the same way as the clusters of a whole image extraction.
+    ///
+    /// # Arguments
+    /// * `image` - The image to use for color palette extraction.
+    /// * `algorithm` - The algorithm to use for each tile.
+    /// * `tile_size` - The width and height of a tile in pixels.
+    /// * `weight` - The weight of the pixel at (x, y) of the whole image, see `extract_weighted`.
+    /// * `with_alpha` - Whether the opacity is clustered along with the color, see `extract_weighted`.
+    ///
+    /// # Returns
+    /// A new extracted `Palette` instance.
+    #[allow(unused)]
+    pub fn extract_tiled<W>(
+        image: &DynamicImage,
+        algorithm: &Algorithm,
+        tile_size: u32,
+        weight: &W,
+        with_alpha: bool,
+    ) -> Palette<F>
+    where
+        F: Send,
+        W: Fn(u32, u32) -> F + Sync,
+    {
+        Self::extract_tiled_with_progress(image, algorithm, tile_size, weight, with_alpha, &|_| {
+            true
+        })
+    }
+
+    /// Extract a color palette like `extract_tiled`, reporting the fraction of finished tiles.
+    ///
+    /// # Arguments
+    /// * `image` - The image to use for color palette extraction.
+    /// * `algorithm` - The algorithm to use for each tile.
+    /// * `tile_size` - The width and height of a tile in pixels.
+    /// * `weight` - The weight of the pixel at (x, y) of the whole image, see `extract_weighted`.
+    /// * `with_alpha` - Whether the opacity is clustered along with the color, see `extract_weighted`.
+    /// * `progress` - Called on the calling thread with the progress in `0.0..=1.0`
+    ///   whenever a batch of tiles is done, returning `false` skips the remaining tiles.
+    ///
+    /// # Returns
+    /// A new extracted `Palette` instance.
+    pub fn extract_tiled_with_progress<W>(
+        image: &DynamicImage,
+        algorithm: &Algorithm,
+        tile_size: u32,
+        weight: &W,
+        with_alpha: bool,
+        progress: &dyn Fn(f64) -> bool,
+    ) -> Palette<F>
+    where
+        F: Send,
+        W: Fn(u32, u32) -> F + Sync,
+    {
+        let tile_size = tile_size.max(1);
+        let mut tiles = Vec::new();
+        for y in (0..image.height()).step_by(tile_size as usize) {
+            for x in (0..image.width()).step_by(tile_size as usize) {
+                tiles.push((x, y));
+            }
+        }
+        // one tile per thread, at most as many threads as cores at a time
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+        let mut candidates = Vec::new();
+        let mut done = 0;
+        for batch in tiles.chunks(threads) {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|&(x, y)| {
+                        scope.spawn(move || {
+                            let tile = image.crop_imm(x, y, tile_size, tile_size);
+                            // weights come from the position in the whole image
+                            let tile_weight = |px: u32, py: u32| weight(px + x, py + y);
+                            extract_candidates::<F, _>(
+                                &tile,
+                                algorithm,
+                                &tile_weight,
+                                with_alpha,
+                                &|_| true,
+                            )
+                            .into_iter()
+                            .map(|swatch| {
+                                let (px, py) = swatch.position();
+                                Swatch::new(
+                                    swatch.color().clone(),
+                                    (px + x, py + y),
+                                    swatch.population(),
+                                )
+                                .with_alpha(swatch.alpha())
+                            })
+                            .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    candidates.extend(handle.join().expect("Tile extraction panicked"));
+                }
+            });
+            done += batch.len();
+            if !progress(done as f64 / tiles.len() as f64) {
+                break;
+            }
+        }
+        Self {
+            swatches: merge_candidates(candidates, with_alpha),
+        }
     }
 
     /// Returns the number of swatches in this palette.
@@ -216,7 +382,8 @@ where
                     swatch2.position()
                 };
                 let population = swatch1.population() + swatch2.population();
-                Swatch::new(color, position, population)
+                let alpha = swatch1.alpha() + (swatch2.alpha() - swatch1.alpha()) * fraction;
+                Swatch::new(color, position, population).with_alpha(alpha)
             }
             (Some(node1), None) => self.find_swatch(nodes, node1, score_fn),
             (None, Some(node2)) => self.find_swatch(nodes, node2, score_fn),
@@ -235,34 +402,617 @@ where
     }
 }
 
+/// The color difference under which candidate swatches are merged into one.
+/// According to the Digital Color Imaging Handbook, a ∆E ≤ 2.3 is perceived as identical by human perception.
+pub const MERGE_THRESHOLD: f64 = 2.3;
+
+/// Struct representing the settings of a palette extraction, for tuning it beyond
+/// the defaults of `Palette::extract`.
+///
+/// # Type Parameters
+/// * `F` - The float type used for calculations.
+///
+/// # Example
+/// ```ignore
+/// use auto_palette::{Algorithm, DistanceMetric, PaletteBuilder};
+///
+/// let image = image::open("/path/to/image.png").unwrap();
+/// let palette: Palette<f64> = PaletteBuilder::new()
+///     .algorithm(Algorithm::KMeans)
+///     .merge_threshold(5.0)
+///     .merge_metric(DistanceMetric::Manhattan)
+///     .max_pixels(512 * 512)
+///     .build(&image);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteBuilder<F: Float> {
+    algorithm: Algorithm,
+    merge_threshold: F,
+    merge_metric: DistanceMetric,
+    max_dimension: u32,
+    max_pixels: Option<usize>,
+}
+
+impl<F> PaletteBuilder<F>
+where
+    F: Float,
+{
+    /// Creates a new `PaletteBuilder` instance with the defaults of `Palette::extract`:
+    /// DBSCAN, merging at `MERGE_THRESHOLD` by Euclidean distance and downsampling to `MAX_DIMENSION`.
+    ///
+    /// # Returns
+    /// A new `PaletteBuilder` instance.
+    pub fn new() -> Self {
+        Self {
+            algorithm: Algorithm::DBSCAN,
+            merge_threshold: F::from_f64(MERGE_THRESHOLD),
+            merge_metric: DistanceMetric::Euclidean,
+            max_dimension: MAX_DIMENSION,
+            max_pixels: None,
+        }
+    }
+
+    /// Sets the clustering algorithm.
+    ///
+    /// # Arguments
+    /// * `algorithm` - The algorithm clustering the pixels into candidate swatches.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the color difference under which candidate swatches are merged.
+    ///
+    /// # Arguments
+    /// * `threshold` - The distance in Lab, in the units of the merge metric.
+    ///   Larger values give fewer swatches.
+    ///
+    /// # Returns
+    /// This builder.
+    #[allow(unused)]
+    pub fn merge_threshold(mut self, threshold: F) -> Self {
+        self.merge_threshold = threshold;
+        self
+    }
+
+    /// Sets how the color difference of candidate swatches is measured.
+    ///
+    /// # Arguments
+    /// * `metric` - The distance metric in Lab.
+    ///
+    /// # Returns
+    /// This builder.
+    #[allow(unused)]
+    pub fn merge_metric(mut self, metric: DistanceMetric) -> Self {
+        self.merge_metric = metric;
+        self
+    }
+
+    /// Sets the largest width and height of the clustered image, see `Pixels::with_max_dimension`.
+    ///
+    /// # Arguments
+    /// * `max_dimension` - The largest width and height in pixels.
+    ///
+    /// # Returns
+    /// This builder.
+    #[allow(unused)]
+    pub fn max_dimension(mut self, max_dimension: u32) -> Self {
+        self.max_dimension = max_dimension;
+        self
+    }
+
+    /// Sets the largest number of pixels that are clustered. Larger images are scaled down
+    /// before the clustering, and the swatch positions and populations scaled back up.
+    ///
+    /// # Arguments
+    /// * `max_pixels` - The largest number of pixels.
+    ///
+    /// # Returns
+    /// This builder.
+    #[allow(unused)]
+    pub fn max_pixels(mut self, max_pixels: usize) -> Self {
+        self.max_pixels = Some(max_pixels);
+        self
+    }
+
+    /// Extract a color palette from the given image with these settings.
+    ///
+    /// # Arguments
+    /// * `image` - The image to use for color palette extraction.
+    ///
+    /// # Returns
+    /// A new extracted `Palette` instance.
+    pub fn build(&self, image: &DynamicImage) -> Palette<F> {
+        let (width, height) = (image.width(), image.height());
+        let mut scale = self.max_dimension.max(1) as f64 / width.max(height).max(1) as f64;
+        if let Some(max_pixels) = self.max_pixels {
+            scale = scale.min((max_pixels.max(1) as f64 / (width as f64 * height as f64)).sqrt());
+        }
+        let pixels = Pixels::scaled(image, scale);
+        let candidates =
+            pixel_candidates(&pixels, &self.algorithm, &|_, _| F::one(), false, &|_| true);
+        Palette {
+            swatches: merge_candidates_with(
+                candidates,
+                false,
+                self.merge_threshold,
+                &self.merge_metric,
+            ),
+        }
+    }
+}
+
+impl<F> Default for PaletteBuilder<F>
+where
+    F: Float,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The largest width and height of an image that is clustered as is by `Palette::extract*`,
+/// larger images are scaled down first.
+pub const MAX_DIMENSION: u32 = 512;
+
+/// Struct representing the pixels of an image converted for clustering.
+/// The conversion does not depend on the algorithm or the weights, so the same
+/// pixels can be extracted again with different settings.
+///
+/// # Type Parameters
+/// * `F` - The float type used for calculations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pixels<F: Float> {
+    width: u32,
+    height: u32,
+    has_alpha: bool,
+    points: Vec<Point5<F>>,
+    alphas: Vec<F>,
+    coords: Vec<(u32, u32)>,
+    // the number of image pixels every converted pixel stands for
+    area: F,
+}
+
+impl<F> Pixels<F>
+where
+    F: Float,
+{
+    /// Converts the given image to pixels, scaled down to `MAX_DIMENSION` if it is larger.
+    ///
+    /// # Arguments
+    /// * `image` - The image to convert, any color type.
+    ///
+    /// # Returns
+    /// A new `Pixels` instance without the fully transparent pixels.
+    pub fn new(image: &DynamicImage) -> Self {
+        Self::with_max_dimension(image, MAX_DIMENSION)
+    }
+
+    /// Converts the given image to pixels, scaled down with a Lanczos filter first when its
+    /// width or height exceeds the given limit. The positions stay in the coordinates of the
+    /// given image and every pixel counts for the area it was scaled from, so the swatches
+    /// come out like those of the full image.
+    ///
+    /// # Arguments
+    /// * `image` - The image to convert, any color type.
+    /// * `max_dimension` - The largest width and height to convert.
+    ///
+    /// # Returns
+    /// A new `Pixels` instance without the fully transparent pixels.
+    pub fn with_max_dimension(image: &DynamicImage, max_dimension: u32) -> Self {
+        let largest = image.width().max(image.height()).max(1);
+        Self::scaled(image, max_dimension.max(1) as f64 / largest as f64)
+    }
+
+    /// Converts the given image to pixels after scaling it by the given factor.
+    ///
+    /// # Arguments
+    /// * `image` - The image to convert, any color type.
+    /// * `scale` - The factor of the width and height, 1 or more keeps the image as is.
+    ///
+    /// # Returns
+    /// A new `Pixels` instance in the coordinates of the given image.
+    fn scaled(image: &DynamicImage, scale: f64) -> Self {
+        if scale >= 1.0 {
+            return Self::convert(image);
+        }
+        let (width, height) = (image.width(), image.height());
+        let new_width = ((width as f64 * scale).round() as u32).max(1);
+        let new_height = ((height as f64 * scale).round() as u32).max(1);
+        let small = if image.color().has_alpha() {
+            DynamicImage::from(image::imageops::resize(
+                &image.to_rgba8(),
+                new_width,
+                new_height,
+                FilterType::Lanczos3,
+            ))
+        } else {
+            DynamicImage::from(image::imageops::resize(
+                &image.to_rgb8(),
+                new_width,
+                new_height,
+                FilterType::Lanczos3,
+            ))
+        };
+        let mut pixels = Self::convert(&small);
+        // The points hold relative positions, so only the coordinates and the size go back.
+        let scale_x = width as f64 / new_width as f64;
+        let scale_y = height as f64 / new_height as f64;
+        for (x, y) in pixels.coords.iter_mut() {
+            *x = (((*x as f64 + 0.5) * scale_x) as u32).min(width - 1);
+            *y = (((*y as f64 + 0.5) * scale_y) as u32).min(height - 1);
+        }
+        pixels.width = width;
+        pixels.height = height;
+        pixels.area = F::from_f64(scale_x * scale_y);
+        pixels
+    }
+
+    /// Converts the given image to pixels as is.
+    ///
+    /// # Arguments
+    /// * `image` - The image to convert, any color type.
+    ///
+    /// # Returns
+    /// A new `Pixels` instance without the fully transparent pixels.
+    fn convert(image: &DynamicImage) -> Self {
+        // Other color types, like grayscale or 16 bit, are converted to 8 bit RGB(A) first.
+        let image_data = if image.color().has_alpha() {
+            ImageData::from(&image.to_rgba8())
+        } else {
+            ImageData::from(&image.to_rgb8())
+        };
+        return convert_to_pixels(&image_data);
+    }
+
+    /// Return the number of converted pixels.
+    ///
+    /// # Returns
+    /// The number of converted pixels.
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Return whether there are no converted pixels.
+    ///
+    /// # Returns
+    /// `true` if every pixel of the image was transparent.
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Evaluates the given weight at the position of every pixel.
+    ///
+    /// # Arguments
+    /// * `weight` - The weight of the pixel at (x, y).
+    ///
+    /// # Returns
+    /// The weight of every pixel times the area it stands for, indexed like the points.
+    fn weights<W>(&self, weight: &W) -> Vec<F>
+    where
+        W: Fn(u32, u32) -> F,
+    {
+        self.coords
+            .iter()
+            .map(|&(x, y)| weight(x, y) * self.area)
+            .collect()
+    }
+}
+
+/// Clusters the pixels of the given image into candidate swatches.
+///
+/// # Arguments
+/// * `image` - The image to cluster.
+/// * `algorithm` - The clustering algorithm to use.
+/// * `weight` - The weight of the pixel at (x, y), summed into the swatch populations.
+/// * `with_alpha` - Whether to cluster on Lab and opacity instead of Lab and position,
+///   only used when the image has an alpha channel.
+/// * `progress` - Called with the progress of the clustering in `0.0..=1.0`, see `Algorithm::apply_with_progress`.
+///
+/// # Returns
+/// The candidate swatches, one per pixel cluster.
+fn extract_candidates<F, W>(
+    image: &DynamicImage,
+    algorithm: &Algorithm,
+    weight: &W,
+    with_alpha: bool,
+    progress: &dyn Fn(f64) -> bool,
+) -> Vec<Swatch<F>>
+where
+    F: Float,
+    W: Fn(u32, u32) -> F,
+{
+    let pixels = Pixels::new(image);
+    pixel_candidates(&pixels, algorithm, weight, with_alpha, progress)
+}
+
+/// Clusters the given converted pixels into candidate swatches.
+///
+/// # Arguments
+/// * `pixels` - The converted pixels of the image.
+/// * `algorithm` - The clustering algorithm to use.
+/// * `weight` - The weight of the pixel at (x, y), summed into the swatch populations.
+/// * `with_alpha` - Whether to cluster on Lab and opacity instead of Lab and position,
+///   only used when the image has an alpha channel.
+/// * `progress` - Called with the progress of the clustering in `0.0..=1.0`, see `Algorithm::apply_with_progress`.
+///
+/// # Returns
+/// The candidate swatches, one per pixel cluster.
+fn pixel_candidates<F, W>(
+    pixels: &Pixels<F>,
+    algorithm: &Algorithm,
+    weight: &W,
+    with_alpha: bool,
+    progress: &dyn Fn(f64) -> bool,
+) -> Vec<Swatch<F>>
+where
+    F: Float,
+    W: Fn(u32, u32) -> F,
+{
+    let weights = pixels.weights(weight);
+
+    if with_alpha && pixels.has_alpha {
+        // Merge pixels that are close in color and opacity, wherever they are.
+        let points: Vec<_> = pixels
+            .points
+            .iter()
+            .zip(pixels.alphas.iter())
+            .map(|(pixel, &alpha)| Point4(pixel[0], pixel[1], pixel[2], alpha))
+            .collect();
+        return algorithm
+            .apply_with_progress(&points, progress)
+            .iter()
+            .filter_map(|cluster| {
+                alpha_cluster_to_swatch(
+                    cluster,
+                    &pixels.points,
+                    &weights,
+                    pixels.width,
+                    pixels.height,
+                )
+            })
+            .collect();
+    }
+
+    // Merge pixels that are close in color and position, and exclude outliers.
+    let pixel_clusters = algorithm.apply_with_progress(&pixels.points, progress);
+    pixel_clusters
+        .iter()
+        .filter_map(|cluster| {
+            pixel_cluster_to_swatch(cluster, &weights, pixels.width, pixels.height)
+        })
+        .collect()
+}
+
+/// Weight of a pixel for the center bias, 1 at the image center with a Gaussian
+/// falloff that reaches `exp(-strength)` in the corners.
+///
+/// # Arguments
+/// * `x` - The x coordinate of the pixel.
+/// * `y` - The y coordinate of the pixel.
+/// * `width` - The width of the image.
+/// * `height` - The height of the image.
+/// * `strength` - The strength of the falloff, 0 or less weighs every pixel 1.
+///
+/// # Returns
+/// The weight of the pixel in (0, 1].
+pub fn center_weight<F>(x: u32, y: u32, width: u32, height: u32, strength: F) -> F
+where
+    F: Float,
+{
+    if strength <= F::zero() {
+        return F::one();
+    }
+    let half = F::from_f64(0.5);
+    let dx = (F::from_u32(x) + half) / F::from_u32(width.max(1)) - half;
+    let dy = (F::from_u32(y) + half) / F::from_u32(height.max(1)) - half;
+    // squared distance scaled so the corners are at 1
+    let distance = (dx * dx + dy * dy) * F::from_f64(2.0);
+    (-strength * distance).exp()
+}
+
+/// The number of pixels whose neighbor distance is measured by `suggest_dbscan_epsilon`.
+const EPSILON_SAMPLES: usize = 2048;
+
+/// Suggests a DBSCAN neighborhood radius for the given image from its k-distance graph:
+/// the distance of sampled pixels to their `DBSCAN_MIN_POINTS`-th nearest neighbor, sorted,
+/// has a knee where dense regions end and outliers begin.
+///
+/// # Arguments
+/// * `image` - The image to analyze.
+///
+/// # Returns
+/// The radius at the knee in the units of `Algorithm::DBSCANWith`, or `None` when the
+/// image has too few opaque pixels.
+pub fn suggest_dbscan_epsilon<F>(image: &DynamicImage) -> Option<F>
+where
+    F: Float,
+{
+    let image_data = ImageData::from(&image.to_rgba8());
+    let pixels = convert_to_pixels::<F>(&image_data).points;
+    if pixels.len() <= DBSCAN_MIN_POINTS {
+        return None;
+    }
+
+    // Every pixel is a neighbor candidate, only the queries are sampled.
+    let search = KDTreeSearch::new(&pixels, &DistanceMetric::Euclidean);
+    let step = (pixels.len() / EPSILON_SAMPLES).max(1);
+    let mut distances: Vec<F> = pixels
+        .iter()
+        .step_by(step)
+        .filter_map(|pixel| {
+            // The query pixel itself comes first at distance 0.
+            search
+                .search(pixel, DBSCAN_MIN_POINTS + 1)
+                .last()
+                .map(|neighbor| neighbor.distance)
+        })
+        .collect();
+    distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    knee_point(&distances)
+}
+
+/// Finds the knee of an ascending curve, the value farthest below the line from the
+/// first to the last value once both axes are scaled to [0, 1].
+///
+/// # Arguments
+/// * `values` - The values of the curve in ascending order.
+///
+/// # Returns
+/// The value at the knee, or `None` when there are no values.
+fn knee_point<F>(values: &[F]) -> Option<F>
+where
+    F: Float,
+{
+    let first = *values.first()?;
+    let last = *values.last()?;
+    if values.len() < 3 || last <= first {
+        return Some(last);
+    }
+    let span = F::from_usize(values.len() - 1);
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            let x = F::from_usize(i) / span;
+            let y = (value - first) / (last - first);
+            (x - y, value)
+        })
+        .max_by(|(gap1, _), (gap2, _)| gap1.partial_cmp(gap2).unwrap_or(Ordering::Equal))
+        .map(|(_, value)| value)
+}
+
+/// Merges candidate swatches with small color differences.
+///
+/// # Arguments
+/// * `candidates` - The candidate swatches to merge.
+/// * `with_alpha` - Whether swatches of different opacity are kept apart.
+///
+/// # Returns
+/// The merged swatches.
+fn merge_candidates<F>(candidates: Vec<Swatch<F>>, with_alpha: bool) -> Vec<Swatch<F>>
+where
+    F: Float,
+{
+    merge_candidates_with(
+        candidates,
+        with_alpha,
+        F::from_f64(MERGE_THRESHOLD),
+        &DistanceMetric::Euclidean,
+    )
+}
+
+/// Merges candidate swatches closer than the given threshold.
+///
+/// # Arguments
+/// * `candidates` - The candidate swatches to merge.
+/// * `with_alpha` - Whether swatches of different opacity are kept apart.
+/// * `threshold` - The color difference under which swatches are merged.
+/// * `metric` - The distance metric the color difference is measured with.
+///
+/// # Returns
+/// The merged swatches.
+fn merge_candidates_with<F>(
+    candidates: Vec<Swatch<F>>,
+    with_alpha: bool,
+    threshold: F,
+    metric: &DistanceMetric,
+) -> Vec<Swatch<F>>
+where
+    F: Float,
+{
+    if with_alpha {
+        // The opacity is scaled like the lightness, so 2.3% of opacity weighs as a ∆E of 2.3.
+        let colors: Vec<_> = candidates
+            .iter()
+            .map(|swatch| {
+                let Lab { l, a, b, .. } = swatch.color().to_lab();
+                Point4(l, a, b, swatch.alpha() * F::from_f64(100.0))
+            })
+            .collect();
+        return merge_clusters(&colors, &candidates, threshold, metric);
+    }
+    let colors: Vec<_> = candidates
+        .iter()
+        .map(|swatch| {
+            let Lab { l, a, b, .. } = swatch.color().to_lab();
+            Point3(l, a, b)
+        })
+        .collect();
+    merge_clusters(&colors, &candidates, threshold, metric)
+}
+
+/// Clusters the given points of the candidate swatches and merges every cluster into one swatch.
+///
+/// # Arguments
+/// * `colors` - The point of every candidate swatch.
+/// * `candidates` - The candidate swatches.
+/// * `threshold` - The color difference under which swatches are merged.
+/// * `metric` - The distance metric the color difference is measured with.
+///
+/// # Returns
+/// The merged swatches.
+fn merge_clusters<F, P>(
+    colors: &[P],
+    candidates: &[Swatch<F>],
+    threshold: F,
+    metric: &DistanceMetric,
+) -> Vec<Swatch<F>>
+where
+    F: Float,
+    P: Point<F>,
+{
+    // Merge colors with small color differences and extract the dominant swatches.
+    let dbscan = DBSCAN::new(1, threshold, metric);
+    let (swatch_clusters, _) = dbscan.fit(colors);
+    swatch_clusters
+        .iter()
+        .filter_map(|cluster| color_cluster_to_swatch(cluster, candidates))
+        .collect()
+}
+
 /// Converts the given image data to pixels.
 ///
 /// # Arguments
 /// * `image_data` - The image data to convert.
 ///
 /// # Returns
-/// A vector of `Point5` instances.
+/// The `Point5` instances, the normalized alpha of each, which is 1 for images
+/// without an alpha channel, and the position of each.
 #[allow(unused)]
-fn convert_to_pixels<F>(image_data: &ImageData) -> Vec<Point5<F>>
+fn convert_to_pixels<F>(image_data: &ImageData) -> Pixels<F>
 where
     F: Float,
 {
     let width = image_data.width() as usize;
     let width_f = F::from_u32(image_data.width());
     let height_f = F::from_u32(image_data.height());
+    let mut points = Vec::new();
+    let mut alphas = Vec::new();
+    let mut coords = Vec::new();
     image_data
         .data()
         .chunks_exact(image_data.channels() as usize)
         .enumerate()
-        .filter_map(|(i, chunk)| {
+        .for_each(|(i, chunk)| {
             let r = chunk[0];
             let g = chunk[1];
             let b = chunk[2];
 
             // Ignore if the alpha channel exists and the transparency value is 0.
             if chunk.len() >= 4 && chunk[3].is_zero() {
-                return None;
+                return;
             }
+            let alpha = if chunk.len() >= 4 {
+                F::from_u8(chunk[3]) / F::from_u8(255)
+            } else {
+                F::one()
+            };
 
             let rgb = RGB::new(r, g, b);
             let xyz: XYZ<F> = XYZ::from(&rgb);
@@ -278,15 +1028,26 @@ where
                 F::from_usize(x) / width_f,
                 F::from_usize(y) / height_f,
             );
-            Some(pixel)
-        })
-        .collect()
+            points.push(pixel);
+            alphas.push(alpha);
+            coords.push((x as u32, y as u32));
+        });
+    Pixels {
+        width: image_data.width(),
+        height: image_data.height(),
+        has_alpha: image_data.channels() >= 4,
+        points,
+        alphas,
+        coords,
+        area: F::one(),
+    }
 }
 
 /// Converts the given pixel cluster to a swatch.
 ///
 /// # Arguments
 /// * `pixel_cluster` - The pixel cluster to convert.
+/// * `weights` - The weight of every pixel, indexed like the cluster membership.
 /// * `width` - The width of the source image.
 /// * `height` - The height of the source image.
 ///
@@ -295,6 +1056,7 @@ where
 #[allow(unused)]
 fn pixel_cluster_to_swatch<F>(
     pixel_cluster: &Cluster<F, Point5<F>>,
+    weights: &[F],
     width: u32,
     height: u32,
 ) -> Option<Swatch<F>>
@@ -321,7 +1083,67 @@ where
         x.to_u32().expect("Could not convert x to u32"),
         y.to_u32().expect("Could not convert y to u32"),
     );
-    Some(Swatch::new(color, position, pixel_cluster.size()))
+    // equal weights of 1 add up to the cluster size exactly
+    let population = pixel_cluster
+        .membership()
+        .iter()
+        .fold(F::zero(), |sum, &index| sum + weights[index]);
+    let population = population.round().to_usize().unwrap_or(0).max(1);
+    Some(Swatch::new(color, position, population))
+}
+
+/// Converts the given cluster of Lab and alpha points to a swatch.
+///
+/// # Arguments
+/// * `pixel_cluster` - The pixel cluster to convert.
+/// * `pixels` - The pixels with their positions, indexed like the cluster membership.
+/// * `weights` - The weight of every pixel, indexed like the cluster membership.
+/// * `width` - The width of the source image.
+/// * `height` - The height of the source image.
+///
+/// # Returns
+/// A swatch representing the given cluster, positioned at the mean of its pixels.
+fn alpha_cluster_to_swatch<F>(
+    pixel_cluster: &Cluster<F, Point4<F>>,
+    pixels: &[Point5<F>],
+    weights: &[F],
+    width: u32,
+    height: u32,
+) -> Option<Swatch<F>>
+where
+    F: Float,
+{
+    if pixel_cluster.is_empty() {
+        return None;
+    }
+
+    let centroid = pixel_cluster.centroid();
+    let lab = Lab::<F>::new(
+        centroid[0].denormalize(Lab::<F>::min_l(), Lab::<F>::max_l()),
+        centroid[1].denormalize(Lab::<F>::min_a(), Lab::<F>::max_a()),
+        centroid[2].denormalize(Lab::<F>::min_b(), Lab::<F>::max_b()),
+    );
+    let color = Color::from(&lab);
+
+    let membership = pixel_cluster.membership();
+    let (sum_x, sum_y) = membership
+        .iter()
+        .fold((F::zero(), F::zero()), |(x, y), &index| {
+            (x + pixels[index][3], y + pixels[index][4])
+        });
+    let size = F::from_usize(membership.len());
+    let x = (sum_x / size).denormalize(F::zero(), F::from_u32(width));
+    let y = (sum_y / size).denormalize(F::zero(), F::from_u32(height));
+    let position = (
+        x.to_u32().expect("Could not convert x to u32"),
+        y.to_u32().expect("Could not convert y to u32"),
+    );
+    let population = membership
+        .iter()
+        .fold(F::zero(), |sum, &index| sum + weights[index]);
+    let population = population.round().to_usize().unwrap_or(0).max(1);
+    let alpha = centroid[3].clamp(F::zero(), F::one());
+    Some(Swatch::new(color, position, population).with_alpha(alpha))
 }
 
 /// Converts the given color cluster to a swatch.
@@ -334,12 +1156,13 @@ where
 /// A swatch representing the given cluster.
 #[inline]
 #[allow(unused)]
-fn color_cluster_to_swatch<F>(
-    color_cluster: &Cluster<F, Point3<F>>,
+fn color_cluster_to_swatch<F, P>(
+    color_cluster: &Cluster<F, P>,
     candidates: &[Swatch<F>],
 ) -> Option<Swatch<F>>
 where
     F: Float,
+    P: Point<F>,
 {
     if color_cluster.is_empty() {
         return None;
@@ -363,13 +1186,16 @@ where
             } else {
                 current.position()
             };
-            Swatch::new(color, position, population)
+            let alpha = previous.alpha() + (current.alpha() - previous.alpha()) * fraction;
+            Swatch::new(color, position, population).with_alpha(alpha)
         });
     Some(best_swatch)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::delta_e::DeltaE;
+    use super::super::math::clustering::median_cut::MedianCut;
     use super::*;
     use image::RgbaImage;
 
@@ -399,6 +1225,437 @@ mod tests {
         assert_eq!(palette.len(), 0);
     }
 
+    #[test]
+    fn test_extract_tiled() {
+        // three quarters orange, a quarter teal, with a little texture,
+        // tiles large enough for the default DBSCAN radius to find neighbors
+        let image = DynamicImage::from(image::RgbImage::from_fn(256, 256, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            if y < 192 {
+                image::Rgb([230 - noise, 120 + noise, 40])
+            } else {
+                image::Rgb([20, 150 - noise, 140 + noise])
+            }
+        }));
+        let whole: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::DBSCAN);
+        let tiled: Palette<f64> =
+            Palette::extract_tiled(&image, &Algorithm::DBSCAN, 128, &|_, _| 1.0, false);
+        assert!(!whole.is_empty());
+        assert!(!tiled.is_empty());
+
+        let whole_swatches = whole.swatches(2);
+        let tiled_swatches = tiled.swatches(2);
+        assert_eq!(whole_swatches.len(), tiled_swatches.len());
+        for (w, t) in whole_swatches.iter().zip(tiled_swatches.iter()) {
+            let difference = w.color().difference(t.color(), &DeltaE::CIE2000);
+            assert!(difference < 5.0, "dominant colors differ by {}", difference);
+        }
+        // the orange area dominates in both
+        assert!(tiled_swatches[0].population() > tiled_swatches[1].population());
+        assert!(tiled_swatches[0].color().to_rgb().r::<u8>() > 200);
+    }
+
+    #[test]
+    fn test_center_weight() {
+        assert_eq!(center_weight(0, 0, 10, 10, 0.0), 1.0);
+        let center: f64 = center_weight(5, 5, 11, 11, 4.0);
+        let edge = center_weight(0, 5, 11, 11, 4.0);
+        let corner = center_weight(0, 0, 11, 11, 4.0);
+        assert!((center - 1.0).abs() < 1e-9);
+        assert!(center > edge && edge > corner);
+    }
+
+    #[test]
+    fn test_extract_center_weighted() {
+        // a small red square in the middle of a large blue frame, large enough
+        // for the default DBSCAN radius to find neighbors
+        let image = DynamicImage::from(image::RgbImage::from_fn(100, 100, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            if (30..70).contains(&x) && (30..70).contains(&y) {
+                image::Rgb([220 + noise, 30, 30])
+            } else {
+                image::Rgb([30, 60, 210 + noise])
+            }
+        }));
+        let is_red = |swatch: &Swatch<f64>| swatch.color().to_rgb().r::<u8>() > 150;
+
+        let center_biased = |strength: f64| {
+            Palette::extract_weighted(
+                &image,
+                &Algorithm::DBSCAN,
+                &|x, y| center_weight(x, y, 100, 100, strength),
+                false,
+            )
+        };
+        let uniform: Palette<f64> = center_biased(0.0);
+        let plain: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::DBSCAN);
+        assert_eq!(uniform, plain);
+        let swatches = uniform.swatches(2);
+        assert_eq!(swatches.len(), 2);
+        assert!(!is_red(&swatches[0]));
+
+        let biased: Palette<f64> = center_biased(12.0);
+        let swatches = biased.swatches(2);
+        assert_eq!(swatches.len(), 2);
+        assert!(is_red(&swatches[0]));
+    }
+
+    #[test]
+    fn test_extract_with_alpha() {
+        // opaque red on the left, the same red at half opacity on the right,
+        // and a fully transparent blue strip at the bottom
+        let image = DynamicImage::from(RgbaImage::from_fn(100, 100, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            if y >= 80 {
+                image::Rgba([0, 0, 255, 0])
+            } else if x < 50 {
+                image::Rgba([220 + noise, 30, 30, 255])
+            } else {
+                image::Rgba([220 + noise, 30, 30, 128])
+            }
+        }));
+        let is_blue = |swatch: &Swatch<f64>| swatch.color().to_rgb().b::<u8>() > 150;
+
+        let palette: Palette<f64> =
+            Palette::extract_weighted(&image, &Algorithm::DBSCAN, &|_, _| 1.0, true);
+        let swatches = palette.swatches(palette.len());
+        assert_eq!(swatches.len(), 2);
+        assert!(!swatches.iter().any(is_blue));
+        assert!(swatches.iter().any(|swatch| swatch.alpha() > 0.95));
+        assert!(swatches
+            .iter()
+            .any(|swatch| (swatch.alpha() - 128.0 / 255.0).abs() < 0.05));
+
+        // without alpha both halves are the same red
+        let plain: Palette<f64> =
+            Palette::extract_weighted(&image, &Algorithm::DBSCAN, &|_, _| 1.0, false);
+        assert!(!plain.is_empty());
+        let swatches = plain.swatches(plain.len());
+        assert!(swatches.iter().all(|swatch| swatch.alpha() == 1.0));
+    }
+
+    #[test]
+    fn test_knee_point() {
+        assert_eq!(knee_point::<f64>(&[]), None);
+        assert_eq!(knee_point(&[0.5]), Some(0.5));
+        // flat for a long while, then a steep rise
+        let mut values: Vec<f64> = (0..90).map(|i| 0.01 + i as f64 * 0.0001).collect();
+        values.extend((1..=10).map(|i| 0.02 + i as f64 * 0.1));
+        let knee = knee_point(&values).unwrap();
+        assert!(knee > 0.015 && knee < 0.2, "knee at {}", knee);
+    }
+
+    #[test]
+    fn test_suggest_dbscan_epsilon() {
+        let tiny = DynamicImage::from(image::RgbImage::new(2, 2));
+        assert_eq!(suggest_dbscan_epsilon::<f64>(&tiny), None);
+
+        let image = DynamicImage::from(image::RgbImage::from_fn(48, 48, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            if x < 24 {
+                image::Rgb([230 - noise, 120 + noise, 40])
+            } else {
+                image::Rgb([20, 150 - noise, 140 + noise])
+            }
+        }));
+        let epsilon = suggest_dbscan_epsilon::<f64>(&image).unwrap();
+        assert!(epsilon > 0.0 && epsilon < 1.0);
+        // the suggestion clusters the two halves
+        let palette: Palette<f64> = Palette::extract_with_algorithm(
+            &image,
+            &Algorithm::DBSCANWith {
+                epsilon,
+                metric: DistanceMetric::Euclidean,
+            },
+        );
+        assert!(!palette.is_empty());
+    }
+
+    #[test]
+    fn test_dbscan_with_default_epsilon() {
+        let image = DynamicImage::from(image::RgbImage::from_fn(32, 32, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            image::Rgb([30, 60 + noise, 210])
+        }));
+        let default: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::DBSCAN);
+        let explicit: Palette<f64> = Palette::extract_with_algorithm(
+            &image,
+            &Algorithm::DBSCANWith {
+                epsilon: super::super::DBSCAN_EPSILON,
+                metric: DistanceMetric::Euclidean,
+            },
+        );
+        assert_eq!(default, explicit);
+    }
+
+    #[test]
+    fn test_extract_with_metric() {
+        let image = DynamicImage::from(image::RgbImage::from_fn(32, 32, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            if x < 16 {
+                image::Rgb([230 - noise, 120, 40])
+            } else {
+                image::Rgb([20, 150, 140 + noise])
+            }
+        }));
+        let default: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::GMeans);
+        let euclidean: Palette<f64> = Palette::extract_with_algorithm(
+            &image,
+            &Algorithm::GMeansWith {
+                metric: DistanceMetric::Euclidean,
+            },
+        );
+        assert_eq!(default, euclidean);
+
+        for metric in [DistanceMetric::Manhattan, DistanceMetric::Chebyshev] {
+            let gmeans: Palette<f64> =
+                Palette::extract_with_algorithm(&image, &Algorithm::GMeansWith { metric });
+            assert!(gmeans.len() >= 2);
+            let dbscan: Palette<f64> = Palette::extract_with_algorithm(
+                &image,
+                &Algorithm::DBSCANWith {
+                    epsilon: 0.2,
+                    metric,
+                },
+            );
+            assert!(dbscan.len() >= 2);
+        }
+    }
+
+    #[test]
+    fn test_extract_kmeans() {
+        let image = DynamicImage::from(image::RgbImage::from_fn(48, 48, |x, _| {
+            if x < 24 {
+                image::Rgb([230, 120, 40])
+            } else {
+                image::Rgb([20, 150, 140])
+            }
+        }));
+        let palette: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::KMeans);
+        assert!(palette.len() >= 2);
+        // seeded, so the same image gives the same palette
+        let again: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::KMeans);
+        assert_eq!(palette, again);
+    }
+
+    #[test]
+    fn test_median_cut_four_colors() {
+        let colors = [[200, 30, 30], [30, 180, 60], [40, 60, 220], [240, 230, 90]];
+        let image = DynamicImage::from(image::RgbImage::from_fn(16, 16, |x, y| {
+            image::Rgb(colors[(y / 8 * 2 + x / 8) as usize])
+        }));
+        let image_data = ImageData::from(&image.to_rgb8());
+        let pixels = convert_to_pixels::<f64>(&image_data).points;
+        let median_cut = MedianCut::new(4);
+        let clusters = median_cut.fit(&pixels);
+        assert_eq!(clusters.len(), 4);
+        // one quadrant per bucket
+        for cluster in clusters.iter() {
+            assert_eq!(cluster.size(), 64);
+            let first = &pixels[cluster.membership()[0]];
+            for &index in cluster.membership() {
+                assert_eq!(pixels[index].0, first.0);
+                assert_eq!(pixels[index].1, first.1);
+                assert_eq!(pixels[index].2, first.2);
+            }
+        }
+
+        let palette: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::MedianCut);
+        assert_eq!(palette.len(), 4);
+    }
+
+    #[test]
+    fn test_extract_with_progress() {
+        let image = DynamicImage::from(image::RgbImage::from_fn(32, 32, |x, y| {
+            let noise = ((x * 7 + y * 3) % 5) as u8;
+            if x < 16 {
+                image::Rgb([230 - noise, 120, 40])
+            } else {
+                image::Rgb([20, 150, 140 + noise])
+            }
+        }));
+        for algorithm in [Algorithm::GMeans, Algorithm::DBSCAN] {
+            let reports = std::cell::RefCell::new(Vec::new());
+            let palette: Palette<f64> =
+                Palette::extract_with_progress(&image, &algorithm, &|_, _| 1.0, false, &|p| {
+                    reports.borrow_mut().push(p);
+                    true
+                });
+            // the same palette as without progress
+            assert_eq!(palette, Palette::extract_with_algorithm(&image, &algorithm));
+            let reports = reports.into_inner();
+            assert_eq!(reports.last(), Some(&1.0));
+            assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+        }
+
+        let reports = std::cell::RefCell::new(Vec::new());
+        let _: Palette<f64> = Palette::extract_tiled_with_progress(
+            &image,
+            &Algorithm::GMeans,
+            8,
+            &|_, _| 1.0,
+            false,
+            &|p| {
+                reports.borrow_mut().push(p);
+                true
+            },
+        );
+        assert_eq!(reports.into_inner().last(), Some(&1.0));
+    }
+
+    #[test]
+    fn test_extract_from_pixels() {
+        let image = DynamicImage::from(image::RgbaImage::from_fn(24, 24, |x, y| {
+            if x < 4 {
+                image::Rgba([0, 0, 0, 0])
+            } else if y < 12 {
+                image::Rgba([220, 60, 40, 255])
+            } else {
+                image::Rgba([40, 90, 200, 128])
+            }
+        }));
+        let pixels = Pixels::<f64>::new(&image);
+        // fully transparent pixels are skipped
+        assert_eq!(pixels.len(), 20 * 24);
+        assert_eq!(pixels.coords[0], (4, 0));
+
+        let weight = |x, y| center_weight(x, y, 24, 24, 2.0);
+        for algorithm in [Algorithm::GMeans, Algorithm::DBSCAN, Algorithm::KMeans] {
+            for with_alpha in [false, true] {
+                let expected: Palette<f64> =
+                    Palette::extract_weighted(&image, &algorithm, &weight, with_alpha);
+                let palette =
+                    Palette::extract_from_pixels(&pixels, &algorithm, &weight, with_alpha, &|_| {
+                        true
+                    });
+                assert_eq!(palette, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let image = DynamicImage::from(image::RgbImage::from_fn(32, 32, |x, _| {
+            if x < 16 {
+                image::Rgb([230, 120, 40])
+            } else {
+                image::Rgb([20, 150, 140])
+            }
+        }));
+        let builder: PaletteBuilder<f64> = PaletteBuilder::default();
+        assert_eq!(builder, PaletteBuilder::new());
+        assert_eq!(builder.build(&image), Palette::extract(&image));
+        assert_eq!(
+            builder.clone().algorithm(Algorithm::KMeans).build(&image),
+            Palette::extract_with_algorithm(&image, &Algorithm::KMeans)
+        );
+    }
+
+    #[test]
+    fn test_builder_merge_threshold() {
+        // eight bands of red, each clearly apart from the next
+        let image = DynamicImage::from(image::RgbImage::from_fn(64, 64, |x, _| {
+            let band = (x / 8) as u8;
+            image::Rgb([40 + band * 30, 20, 20])
+        }));
+        let low: Palette<f64> = PaletteBuilder::new().build(&image);
+        let high: Palette<f64> = PaletteBuilder::new().merge_threshold(100.0).build(&image);
+        assert!(high.len() >= 1);
+        assert!(
+            high.len() < low.len(),
+            "{} swatches at a high threshold, {} at the default",
+            high.len(),
+            low.len()
+        );
+
+        let manhattan: Palette<f64> = PaletteBuilder::new()
+            .merge_metric(DistanceMetric::Manhattan)
+            .merge_threshold(300.0)
+            .build(&image);
+        assert!(manhattan.len() < low.len());
+    }
+
+    #[test]
+    fn test_builder_max_pixels() {
+        // square, so the pixels stay as far apart in x as in y for the DBSCAN radius
+        let image = DynamicImage::from(image::RgbImage::from_fn(200, 200, |x, _| {
+            if x < 100 {
+                image::Rgb([230, 120, 40])
+            } else {
+                image::Rgb([20, 150, 140])
+            }
+        }));
+        let palette: Palette<f64> = PaletteBuilder::new().max_pixels(80 * 80).build(&image);
+        let swatches = palette.swatches(2);
+        assert_eq!(swatches.len(), 2);
+        for swatch in swatches {
+            let (x, y) = swatch.position();
+            assert!(x < 200 && y < 200);
+            // positions and populations are in the original image
+            let orange = swatch.color().to_rgb().r::<u8>() > 128;
+            assert_eq!(x < 100, orange);
+            assert!(swatch.population() > 200 * 200 / 4);
+        }
+        assert_eq!(Pixels::<f64>::scaled(&image, 1.0).len(), 200 * 200);
+        assert_eq!(Pixels::<f64>::scaled(&image, 0.4).len(), 80 * 80);
+    }
+
+    #[test]
+    fn test_extract_downsampled() {
+        let image = DynamicImage::from(image::RgbImage::from_pixel(
+            2000,
+            2000,
+            image::Rgb([40, 120, 200]),
+        ));
+        let pixels = Pixels::<f64>::new(&image);
+        assert_eq!(pixels.len(), (MAX_DIMENSION * MAX_DIMENSION) as usize);
+        assert!(pixels.coords.iter().all(|&(x, y)| x < 2000 && y < 2000));
+        assert_eq!(pixels.coords.last(), Some(&(1998, 1998)));
+
+        let palette: Palette<f64> = Palette::extract_with_algorithm(&image, &Algorithm::KMeans);
+        assert_eq!(palette.len(), 1);
+        let swatch = &palette.swatches(1)[0];
+        let rgb = swatch.color().to_rgb();
+        for (channel, expected) in [
+            (rgb.r::<u8>(), 40),
+            (rgb.g::<u8>(), 120),
+            (rgb.b::<u8>(), 200),
+        ] {
+            assert!(
+                channel.abs_diff(expected) <= 1,
+                "{} != {}",
+                channel,
+                expected
+            );
+        }
+        let (x, y) = swatch.position();
+        assert!(x < 2000 && y < 2000);
+        // the population counts the pixels of the original image
+        let population = swatch.population() as f64;
+        assert!(
+            (population - 4_000_000.0).abs() < 40_000.0,
+            "{}",
+            population
+        );
+    }
+
+    #[test]
+    fn test_pixels_other_color_types() {
+        let gray = DynamicImage::from(image::GrayImage::from_pixel(10, 10, image::Luma([128])));
+        let pixels = Pixels::<f64>::new(&gray);
+        assert_eq!(pixels.len(), 100);
+        assert!(!pixels.has_alpha);
+        assert_eq!(Pixels::<f64>::scaled(&gray, 0.5).len(), 25);
+
+        // 16 bit with alpha, the transparent half is dropped
+        let deep = DynamicImage::from(image::ImageBuffer::from_fn(10, 10, |x, _| {
+            image::Rgba::<u16>([65535, 0, 0, if x < 5 { 0 } else { 65535 }])
+        }));
+        let pixels = Pixels::<f64>::new(&deep);
+        assert_eq!(pixels.len(), 50);
+        assert!(pixels.has_alpha);
+    }
+
     #[test]
     fn test_default() {
         let palette: Palette<f64> = Palette::default();
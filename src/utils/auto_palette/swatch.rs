@@ -24,6 +24,7 @@ pub struct Swatch<F: Float> {
     color: Color<F>,
     position: (u32, u32),
     population: usize,
+    alpha: F,
 }
 
 impl<F> Swatch<F>
@@ -45,9 +46,23 @@ where
             color,
             position,
             population,
+            alpha: F::one(),
         }
     }
 
+    /// Returns this swatch with the given opacity, swatches are opaque by default.
+    ///
+    /// # Arguments
+    /// * `alpha` - The opacity of the swatch in [0, 1].
+    ///
+    /// # Returns
+    /// A `Swatch` instance with the given opacity.
+    #[allow(unused)]
+    pub fn with_alpha(mut self, alpha: F) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
     /// Returns the color of this swatch.
     ///
     /// # Returns
@@ -75,7 +90,17 @@ where
         self.population
     }
 
+    /// Returns the opacity of this swatch.
+    ///
+    /// # Returns
+    /// The opacity of this swatch in [0, 1], 1 unless extracted with alpha.
+    #[allow(unused)]
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+
     /// Calculates the distance between this swatch and another swatch.
+    /// A difference in opacity counts like a lightness difference of the same fraction.
     ///
     /// # Arguments
     /// * `other` - The other swatch.
@@ -88,6 +113,7 @@ where
     #[inline]
     #[allow(unused)]
     pub(super) fn distance(&self, other: &Self) -> F {
-        self.color.difference(&other.color, &DeltaE::CIE2000)
+        let alpha = (self.alpha - other.alpha).abs() * F::from_f64(100.0);
+        self.color.difference(&other.color, &DeltaE::CIE2000) + alpha
     }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use crate::color_item::ColorItem;
+
+pub const DEFAULT_ARRAY_NAME: &str = "PALETTE";
+/// left to right, used when no Line generator sets the direction
+pub const DEFAULT_CSS_ANGLE: f32 = 90.0;
+
+fn array_name(name: &str) -> &str {
+    let name = name.trim();
+    if name.is_empty() {
+        return DEFAULT_ARRAY_NAME;
+    }
+    return name;
+}
+
+/// `pub const NAME: [(u8, u8, u8); N] = [...];` with the color names as comments
+pub fn to_rust_array(colors: &[ColorItem], name: &str) -> String {
+    let mut out = format!(
+        "pub const {}: [(u8, u8, u8); {}] = [\n",
+        array_name(name),
+        colors.len()
+    );
+    for color in colors {
+        out.push_str(&format!(
+            "    ({}, {}, {}), // {}\n",
+            color.r, color.g, color.b, color.name
+        ));
+    }
+    out.push_str("];\n");
+    return out;
+}
+
+// up to two decimals, without trailing zeros
+fn css_number(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    return text.trim_end_matches('0').trim_end_matches('.').to_string();
+}
+
+/// `linear-gradient(<angle>deg, <color> <pct>%, ...)` with the colors evenly spaced,
+/// `rgba()` for every stop when any color is not opaque
+pub fn to_css_gradient(colors: &[ColorItem], angle: f32) -> String {
+    let alpha = colors.iter().any(|c| c.a != 255);
+    let stop = |color: &ColorItem, percent: f32| {
+        let value = if alpha {
+            format!(
+                "rgba({}, {}, {}, {})",
+                color.r,
+                color.g,
+                color.b,
+                css_number(color.a as f32 / 255.0)
+            )
+        } else {
+            format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+        };
+        return format!("{} {}%", value, css_number(percent));
+    };
+    let stops: Vec<String> = match colors {
+        // a single color still needs two stops
+        [color] => vec![stop(color, 0.0), stop(color, 100.0)],
+        _ => colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| stop(color, i as f32 * 100.0 / (colors.len() - 1) as f32))
+            .collect(),
+    };
+    return format!("linear-gradient({}deg, {})", css_number(angle), stops.join(", "));
+}
+
+/// `const vec3 NAME[N] = vec3[](...);` with components normalized to 0-1
+pub fn to_glsl_array(colors: &[ColorItem], name: &str) -> String {
+    let mut out = format!(
+        "const vec3 {}[{}] = vec3[](\n",
+        array_name(name),
+        colors.len()
+    );
+    for (i, color) in colors.iter().enumerate() {
+        let sep = if i + 1 == colors.len() { "" } else { "," };
+        out.push_str(&format!(
+            "    vec3({:.4}, {:.4}, {:.4}){} // {}\n",
+            color.r as f32 / 255.0,
+            color.g as f32 / 255.0,
+            color.b as f32 / 255.0,
+            sep,
+            color.name
+        ));
+    }
+    out.push_str(");\n");
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_gradient() {
+        let colors = vec![
+            ColorItem::new("red", 255, 0, 0),
+            ColorItem::new("green", 0, 128, 0),
+            ColorItem::new("blue", 0, 0, 255),
+        ];
+        assert_eq!(
+            to_css_gradient(&colors, DEFAULT_CSS_ANGLE),
+            "linear-gradient(90deg, #ff0000 0%, #008000 50%, #0000ff 100%)"
+        );
+        assert_eq!(
+            to_css_gradient(&colors[..1], 45.5),
+            "linear-gradient(45.5deg, #ff0000 0%, #ff0000 100%)"
+        );
+        let mut four = colors.clone();
+        four.push(ColorItem::new("clear", 0, 0, 0).with_alpha(0));
+        assert_eq!(
+            to_css_gradient(&four, 180.0),
+            "linear-gradient(180deg, rgba(255, 0, 0, 1) 0%, rgba(0, 128, 0, 1) 33.33%, \
+             rgba(0, 0, 255, 1) 66.67%, rgba(0, 0, 0, 0) 100%)"
+        );
+    }
+}
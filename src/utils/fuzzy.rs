@@ -0,0 +1,95 @@
+// forgiving name search: the pattern only has to appear as a subsequence
+
+const MATCH_SCORE: i32 = 1;
+const CONSECUTIVE_BONUS: i32 = 5;
+const WORD_START_BONUS: i32 = 3;
+const GAP_PENALTY: i32 = 1;
+
+fn is_word_start(prev: Option<char>) -> bool {
+    match prev {
+        None => return true,
+        Some(c) => return c.is_whitespace() || c == '-' || c == '_' || c == '(',
+    }
+}
+
+/// case-insensitive subsequence match, whitespace in the pattern is ignored.
+/// returns the score (higher is better) and the char indices of `text` that matched.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut next = 0;
+    let mut last: Option<usize> = None;
+    let mut prev = None;
+    for (i, c) in text.chars().enumerate() {
+        if next == pattern.len() {
+            break;
+        }
+        if c.to_lowercase().eq(std::iter::once(pattern[next])) {
+            score += MATCH_SCORE;
+            match last {
+                Some(last) if last + 1 == i => score += CONSECUTIVE_BONUS,
+                Some(last) => score -= GAP_PENALTY * (i - last - 1) as i32,
+                None => {}
+            }
+            if is_word_start(prev) {
+                score += WORD_START_BONUS;
+            }
+            positions.push(i);
+            last = Some(i);
+            next += 1;
+        }
+        prev = Some(c);
+    }
+    if next < pattern.len() {
+        return None;
+    }
+    return Some((score, positions));
+}
+
+/// turn matched char indices into byte ranges of `text`, merging neighbours
+pub fn to_byte_ranges(text: &str, positions: &[usize]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    let mut positions = positions.iter().peekable();
+    for (i, (start, c)) in text.char_indices().enumerate() {
+        if positions.peek() != Some(&&i) {
+            continue;
+        }
+        positions.next();
+        let end = start + c.len_utf8();
+        match ranges.last_mut() {
+            Some(range) if range.end == start => range.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    return ranges;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let (_, positions) = fuzzy_match("drkbl", "dark blue").unwrap();
+        assert_eq!(positions, vec![0, 2, 3, 5, 6]);
+        assert!(fuzzy_match("DARK", "dark blue").is_some());
+        assert!(fuzzy_match("xyz", "dark blue").is_none());
+
+        // contiguous and word-start matches rank above scattered ones
+        let (tight, _) = fuzzy_match("blue", "dark blue").unwrap();
+        let (loose, _) = fuzzy_match("blue", "black olive ue").unwrap();
+        assert!(tight > loose);
+    }
+
+    #[test]
+    fn test_to_byte_ranges() {
+        assert_eq!(to_byte_ranges("dark blue", &[0, 2, 3, 5, 6]), vec![0..1, 2..4, 5..7]);
+        // multi-byte chars keep whole code points
+        assert_eq!(to_byte_ranges("桜色", &[1]), vec![3..6]);
+    }
+}
@@ -0,0 +1,68 @@
+// GIMP .gpl palettes: a "GIMP Palette" header, then "r g b name" per line
+
+use crate::color_item::ColorItem;
+
+const HEADER: &str = "GIMP Palette";
+
+/// read the colors of a .gpl file, unnamed ones get their hex as name.
+/// returns the colors and how many color lines could not be read.
+pub fn parse(text: &str) -> Result<(Vec<ColorItem>, usize), String> {
+    let mut lines = text.lines();
+    if lines.next().map(|line| line.trim()) != Some(HEADER) {
+        return Err("missing GIMP Palette header".into());
+    }
+    let mut colors = Vec::new();
+    let mut skipped = 0;
+    for line in lines {
+        let line = line.trim();
+        // metadata and comments
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("Name:")
+            || line.starts_with("Columns:")
+        {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let rgb: Vec<Option<u8>> = fields.by_ref().take(3).map(|v| v.parse().ok()).collect();
+        match rgb.as_slice() {
+            [Some(r), Some(g), Some(b)] => {
+                let name = fields.collect::<Vec<&str>>().join(" ");
+                let mut color = ColorItem::new("", *r, *g, *b);
+                color.name = match name.is_empty() {
+                    true => color.get_hex()[1..].to_string(),
+                    false => name,
+                };
+                colors.push(color);
+            }
+            _ => skipped += 1,
+        }
+    }
+    return Ok((colors, skipped));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "GIMP Palette\nName: Test\nColumns: 4\n# comment\n\
+                    255   0   0\tBright Red\n  0 128 255\n12 bad 3 Broken\n300 0 0 Overflow\n";
+        let (colors, skipped) = parse(text).unwrap();
+        assert_eq!(
+            colors,
+            vec![
+                ColorItem::new("Bright Red", 255, 0, 0),
+                ColorItem::new("0080ff", 0, 128, 255),
+            ]
+        );
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert!(parse("255 0 0 red\n").is_err());
+        assert_eq!(parse("GIMP Palette\r\n").unwrap().0.len(), 0);
+    }
+}
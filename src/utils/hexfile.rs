@@ -0,0 +1,55 @@
+// lospec-style .hex palettes: one rrggbb per line
+
+use crate::color_item::ColorItem;
+
+/// parse the lines of a .hex file, colors are named by their hex.
+/// returns the colors and how many non-empty lines could not be read.
+pub fn parse(text: &str) -> (Vec<ColorItem>, usize) {
+    let mut colors = Vec::new();
+    let mut skipped = 0;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let hex = line.trim_start_matches('#').to_lowercase();
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            skipped += 1;
+            continue;
+        }
+        match ColorItem::from_hex(&hex, &hex) {
+            Some(color) => colors.push(color),
+            None => skipped += 1,
+        }
+    }
+    return (colors, skipped);
+}
+
+/// bare lowercase hex, one color per line
+pub fn to_string(colors: &[ColorItem]) -> String {
+    let mut text = String::new();
+    for color in colors {
+        text.push_str(&format!("{:02x}{:02x}{:02x}\n", color.r, color.g, color.b));
+    }
+    return text;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let colors = vec![
+            ColorItem::new("ff8000", 255, 128, 0),
+            ColorItem::new("00ff00", 0, 255, 0),
+        ];
+        let text = to_string(&colors);
+        assert_eq!(text, "ff8000\n00ff00\n");
+        assert_eq!(parse(&text), (colors.clone(), 0));
+        // a bad line is counted and skipped, the rest still load
+        let (parsed, skipped) = parse("#FF8000\n\nnot a color\n12345\n00ff00\n");
+        assert_eq!(parsed, colors);
+        assert_eq!(skipped, 2);
+    }
+}
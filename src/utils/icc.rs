@@ -0,0 +1,60 @@
+// minimal ICC profile reader, only what we need to tell sRGB from the rest.
+// see ICC.1:2010 section 7 for the header and tag table layout.
+
+const HEADER_SIZE: usize = 128;
+const TAG_ENTRY_SIZE: usize = 12;
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    return Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+fn find_tag<'a>(icc: &'a [u8], signature: &[u8; 4]) -> Option<&'a [u8]> {
+    let count = read_u32(icc, HEADER_SIZE)? as usize;
+    for i in 0..count {
+        let entry = HEADER_SIZE + 4 + i * TAG_ENTRY_SIZE;
+        if icc.get(entry..entry + 4)? != signature {
+            continue;
+        }
+        let offset = read_u32(icc, entry + 4)? as usize;
+        let size = read_u32(icc, entry + 8)? as usize;
+        return icc.get(offset..offset.checked_add(size)?);
+    }
+    return None;
+}
+
+/// read the human readable description of the profile from its `desc` tag.
+pub fn profile_description(icc: &[u8]) -> Option<String> {
+    let tag = find_tag(icc, b"desc")?;
+    match tag.get(0..4)? {
+        // v2 textDescriptionType: ascii count followed by the ascii string
+        b"desc" => {
+            let count = read_u32(tag, 8)? as usize;
+            let text = tag.get(12..12 + count)?;
+            let text = String::from_utf8_lossy(text);
+            return Some(text.trim_end_matches('\0').to_string());
+        }
+        // v4 multiLocalizedUnicodeType: take the first record, UTF-16BE
+        b"mluc" => {
+            if read_u32(tag, 8)? == 0 {
+                return None;
+            }
+            let length = read_u32(tag, 20)? as usize;
+            let offset = read_u32(tag, 24)? as usize;
+            let text = tag.get(offset..offset + length)?;
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            return Some(String::from_utf16_lossy(&units).trim_end_matches('\0').to_string());
+        }
+        _ => return None,
+    }
+}
+
+pub fn is_srgb(icc: &[u8]) -> bool {
+    match profile_description(icc) {
+        Some(desc) => desc.to_lowercase().contains("srgb"),
+        None => false,
+    }
+}
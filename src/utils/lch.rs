@@ -0,0 +1,114 @@
+// CIE L*a*b* / LCh(ab) conversions for perceptual edits.
+// unlike the auto_palette types nothing is clamped on the way back,
+// so out-of-gamut results can be detected instead of silently clipped.
+
+use crate::color_item::ColorItem;
+
+// D65, same reference white as auto_palette
+const WHITE: (f64, f64, f64) = (0.95046, 1.0, 1.08906);
+// channel slack for rounding noise when testing the gamut
+const GAMUT_EPSILON: f64 = 1e-4;
+
+pub fn to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        return value / 12.92;
+    }
+    return ((value + 0.055) / 1.055).powf(2.4);
+}
+
+pub fn from_linear(value: f64) -> f64 {
+    if value <= 0.0031308 {
+        return 12.92 * value;
+    }
+    return 1.055 * value.powf(1.0 / 2.4) - 0.055;
+}
+
+pub fn rgb_to_lab(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (r, g, b) = (
+        to_linear(r as f64 / 255.0),
+        to_linear(g as f64 / 255.0),
+        to_linear(b as f64 / 255.0),
+    );
+    let x = 0.412391 * r + 0.357584 * g + 0.180481 * b;
+    let y = 0.212639 * r + 0.715169 * g + 0.072192 * b;
+    let z = 0.019331 * r + 0.119195 * g + 0.950532 * b;
+    let f = |t: f64| {
+        if t > (6.0f64 / 29.0).powi(3) {
+            return t.cbrt();
+        }
+        return t * 841.0 / 108.0 + 4.0 / 29.0;
+    };
+    let (fx, fy, fz) = (f(x / WHITE.0), f(y / WHITE.1), f(z / WHITE.2));
+    return (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz));
+}
+
+// gamma-encoded sRGB in 0..1, may fall outside that range
+fn lab_to_srgb(l: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let f = |t: f64| {
+        if t > 6.0 / 29.0 {
+            return t.powi(3);
+        }
+        return 108.0 / 841.0 * (t - 4.0 / 29.0);
+    };
+    let fy = (l + 16.0) / 116.0;
+    let x = WHITE.0 * f(fy + a / 500.0);
+    let y = WHITE.1 * f(fy);
+    let z = WHITE.2 * f(fy - b / 200.0);
+    let r = 3.24097 * x - 1.537383 * y - 0.498611 * z;
+    let g = -0.969244 * x + 1.875968 * y + 0.041555 * z;
+    let b = 0.05563 * x - 0.203977 * y + 1.056972 * z;
+    return (from_linear(r), from_linear(g), from_linear(b));
+}
+
+/// whether the Lab color can be shown in sRGB without clipping
+pub fn in_gamut(l: f64, a: f64, b: f64) -> bool {
+    let (r, g, b) = lab_to_srgb(l, a, b);
+    return [r, g, b]
+        .iter()
+        .all(|c| *c >= -GAMUT_EPSILON && *c <= 1.0 + GAMUT_EPSILON);
+}
+
+/// nearest 8-bit sRGB color, clipping channels that are out of gamut
+pub fn lab_to_rgb(l: f64, a: f64, b: f64) -> (u8, u8, u8) {
+    let (r, g, b) = lab_to_srgb(l, a, b);
+    let to_u8 = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    return (to_u8(r), to_u8(g), to_u8(b));
+}
+
+/// (L, C, h) with h in degrees 0..360
+pub fn lab_to_lch(l: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    return (l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0));
+}
+
+pub fn lch_to_lab(l: f64, c: f64, h: f64) -> (f64, f64, f64) {
+    let h = h.to_radians();
+    return (l, c * h.cos(), c * h.sin());
+}
+
+/// scale LCh chroma by `factor` keeping lightness and hue.
+/// a boost that would leave sRGB stops at the largest chroma that still fits.
+pub fn scale_chroma(color: &ColorItem, factor: f64) -> ColorItem {
+    let (l, a, b) = color.get_lab();
+    let (l, c, h) = lab_to_lch(l, a, b);
+    let fits = |chroma: f64| {
+        let (l, a, b) = lch_to_lab(l, chroma, h);
+        return in_gamut(l, a, b);
+    };
+    let mut chroma = c * factor.max(0.0);
+    if !fits(chroma) {
+        // binary search between the original chroma, which fits, and the target
+        let (mut low, mut high) = (c.min(chroma), c.max(chroma));
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+            if fits(mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        chroma = low;
+    }
+    let (l, a, b) = lch_to_lab(l, chroma, h);
+    let (r, g, b) = lab_to_rgb(l, a, b);
+    return ColorItem::new(&color.name, r, g, b);
+}
@@ -1,21 +1,519 @@
-use rand::Rng;
+use image::{GenericImageView, ImageDecoder};
+use rand::{Rng, SeedableRng};
+use std::path::Path;
+pub mod ase;
+pub mod aseprite;
 pub mod auto_palette;
+pub mod export;
+pub mod fuzzy;
+pub mod gpl;
+pub mod hexfile;
+pub mod icc;
+pub mod lch;
+pub mod named;
+pub mod share_card;
+pub mod text;
 
 pub fn resized_str(name: &String , len: usize) -> String {
-    let mut name = name.clone();
-    if name.len() > len {
-        name.truncate(len);
+    // count chars, not bytes, so multi-byte names are never cut mid-character
+    if name.chars().count() > len {
+        let mut name: String = name.chars().take(len).collect();
         name.push_str("...");
+        return name;
     }
-    return name;
+    return name.clone();
 }
 
 pub fn get_random_name(len:usize) -> String {
-    let mut rng = rand::thread_rng();
+    return random_name(&mut rand::thread_rng(), len);
+}
+
+/// like get_random_name, but the same seed always gives the same name
+pub fn get_random_name_seeded(len: usize, seed: u64) -> String {
+    return random_name(&mut rand::rngs::StdRng::seed_from_u64(seed), len);
+}
+
+fn random_name(rng: &mut impl Rng, len: usize) -> String {
     let mut name = String::new();
     for _ in 0..len {
         let c:char = rng.gen_range('a'..='z');
         name.push(c);
     }
     return name;
-}
\ No newline at end of file
+}
+
+/// load an image together with its embedded ICC profile, if the format carries one.
+pub fn load_image(path: &Path) -> image::ImageResult<(image::DynamicImage, Option<Vec<u8>>)> {
+    let mut decoder = image::ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    // a broken profile should not stop us from showing the image
+    let icc = decoder.icc_profile().unwrap_or(None);
+    let img = image::DynamicImage::from_decoder(decoder)?;
+    return Ok((img, icc));
+}
+
+/// whether the file name looks like an image `load_image` can decode
+pub fn has_image_extension(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    return matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "webp" | "bmp");
+}
+
+/// turn a load failure into something the user can act on
+pub fn describe_load_error(path: &Path, err: &image::ImageError) -> String {
+    if let Ok(meta) = std::fs::metadata(path) {
+        if meta.len() == 0 {
+            return "File is empty".into();
+        }
+    }
+    let ext = match path.extension() {
+        Some(ext) => format!(".{}", ext.to_string_lossy()),
+        None => "no extension".into(),
+    };
+    match err {
+        image::ImageError::Unsupported(_) => {
+            return format!("Unsupported format: {} (supported: png, jpeg, webp, bmp)", ext);
+        }
+        image::ImageError::Decoding(e) => {
+            return format!("File looks corrupt or truncated ({}): {}", ext, e);
+        }
+        image::ImageError::Limits(_) => return "Image is too large to load".into(),
+        image::ImageError::IoError(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return "File not found".into();
+        }
+        image::ImageError::IoError(e) => return format!("Cannot read file: {}", e),
+        _ => return err.to_string(),
+    }
+}
+
+/// grab the image currently on the system clipboard
+pub fn clipboard_image() -> Result<image::DynamicImage, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    let data = clipboard.get_image().map_err(|e| match e {
+        arboard::Error::ContentNotAvailable => "No image on the clipboard".to_string(),
+        e => e.to_string(),
+    })?;
+    let buffer = image::RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or("Invalid clipboard image".to_string())?;
+    return Ok(image::DynamicImage::ImageRgba8(buffer));
+}
+
+/// put an image on the system clipboard
+pub fn set_clipboard_image(img: &image::RgbaImage) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    let data = arboard::ImageData {
+        width: img.width() as usize,
+        height: img.height() as usize,
+        bytes: std::borrow::Cow::Borrowed(img.as_raw()),
+    };
+    return clipboard.set_image(data).map_err(|e| e.to_string());
+}
+
+// larger downloads are refused
+const MAX_DOWNLOAD: u64 = 64 * 1024 * 1024;
+
+/// download and decode an image from a http(s) url
+pub fn download_image(url: &str) -> Result<image::DynamicImage, String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("Only http and https URLs are supported".into());
+    }
+    let mut response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Download failed: {}", e))?;
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    // servers without a content type still get a chance to decode
+    if !content_type.is_empty() && !content_type.starts_with("image/") {
+        return Err(format!("Not an image: {}", content_type));
+    }
+    let bytes = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_DOWNLOAD)
+        .read_to_vec()
+        .map_err(|e| match e {
+            ureq::Error::BodyExceedsLimit(_) => {
+                format!("Image is larger than {} MB", MAX_DOWNLOAD / 1024 / 1024)
+            }
+            e => format!("Download failed: {}", e),
+        })?;
+    return image::load_from_memory(&bytes).map_err(|e| format!("Cannot decode image: {}", e));
+}
+
+/// formats the working image can be saved as, picked by the file extension
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SaveFormat {
+    Png,
+    Jpeg,
+    WebP,
+    Bmp,
+}
+
+impl SaveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        return match ext.as_str() {
+            "png" => Some(SaveFormat::Png),
+            "jpg" | "jpeg" => Some(SaveFormat::Jpeg),
+            "webp" => Some(SaveFormat::WebP),
+            "bmp" => Some(SaveFormat::Bmp),
+            _ => None,
+        };
+    }
+    // name of the encoder, for the toast after saving
+    pub fn name(&self) -> &'static str {
+        return match self {
+            SaveFormat::Png => "PNG",
+            SaveFormat::Jpeg => "JPEG",
+            // the image crate only encodes lossless webp
+            SaveFormat::WebP => "WebP (lossless)",
+            SaveFormat::Bmp => "BMP",
+        };
+    }
+}
+
+/// whether the save dialog accepts the file name
+pub fn is_save_name(name: &str) -> bool {
+    return SaveFormat::from_path(Path::new(name)).is_some();
+}
+
+/// save the image in the format of its extension, jpeg with the given quality (1 to 100), webp lossless.
+/// png files also get the metadata as `colorlook:key=value` tEXt chunks
+pub fn save_image(
+    img: &image::DynamicImage,
+    path: &Path,
+    metadata: &[(String, String)],
+    quality: u8,
+) -> Result<SaveFormat, String> {
+    let format = SaveFormat::from_path(path)
+        .ok_or_else(|| "Unknown image format, use .png, .jpg, .jpeg, .webp or .bmp".to_string())?;
+    match format {
+        SaveFormat::Png if !metadata.is_empty() => {}
+        SaveFormat::Png | SaveFormat::Bmp => {
+            img.save(path).map_err(|e| e.to_string())?;
+            return Ok(format);
+        }
+        SaveFormat::Jpeg => {
+            let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
+                std::io::BufWriter::new(file),
+                quality.clamp(1, 100),
+            );
+            // jpeg has no alpha channel
+            image::DynamicImage::from(img.to_rgb8())
+                .write_with_encoder(encoder)
+                .map_err(|e| e.to_string())?;
+            return Ok(format);
+        }
+        SaveFormat::WebP => {
+            let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+            let encoder = image::codecs::webp::WebPEncoder::new_lossless(std::io::BufWriter::new(file));
+            image::DynamicImage::from(img.to_rgba8())
+                .write_with_encoder(encoder)
+                .map_err(|e| e.to_string())?;
+            return Ok(format);
+        }
+    }
+    let (color_type, data) = if img.color().has_alpha() {
+        (png::ColorType::Rgba, img.to_rgba8().into_raw())
+    } else {
+        (png::ColorType::Rgb, img.to_rgb8().into_raw())
+    };
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), img.width(), img.height());
+    encoder.set_color(color_type);
+    encoder.set_depth(png::BitDepth::Eight);
+    for (key, value) in metadata {
+        encoder
+            .add_text_chunk("Comment".into(), format!("colorlook:{}={}", key, value))
+            .map_err(|e| e.to_string())?;
+    }
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&data).map_err(|e| e.to_string())?;
+    return Ok(format);
+}
+
+/// Rec. 709 luma of an sRGB pixel
+pub fn luma(r: u8, g: u8, b: u8) -> u8 {
+    return (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round() as u8;
+}
+
+// side of a checkerboard cell behind see-through colors in generated images, in pixels
+const CHECKER_PIXELS: u32 = 8;
+
+/// a see-through pixel at (x, y) composited over the gray checkerboard swatches are drawn on
+pub fn over_checker(x: u32, y: u32, [r, g, b, a]: [u8; 4]) -> [u8; 3] {
+    if a == 255 {
+        return [r, g, b];
+    }
+    let gray = if (x / CHECKER_PIXELS + y / CHECKER_PIXELS) % 2 == 0 { 102f32 } else { 153f32 };
+    let alpha = a as f32 / 255.0;
+    let mix = |c: u8| (c as f32 * alpha + gray * (1.0 - alpha)).round() as u8;
+    return [mix(r), mix(g), mix(b)];
+}
+
+/// the color every pixel shares, none when the image has more than one color
+pub fn uniform_color(img: &image::DynamicImage) -> Option<[u8; 4]> {
+    // read pixel by pixel rather than copying the whole image
+    let mut pixels = img.pixels().map(|(_, _, p)| p.0);
+    let first = pixels.next()?;
+    if pixels.all(|p| p == first) {
+        return Some(first);
+    }
+    return None;
+}
+
+/// Sobel gradient magnitude of every pixel in row-major order, scaled so the
+/// strongest edge is 1. borders repeat the outermost pixels, a flat image is all 0
+pub fn edge_map(img: &image::GrayImage) -> Vec<f32> {
+    let (width, height) = (img.width() as i64, img.height() as i64);
+    let at = |x: i64, y: i64| {
+        let x = x.clamp(0, width - 1) as u32;
+        let y = y.clamp(0, height - 1) as u32;
+        return img.get_pixel(x, y).0[0] as f32;
+    };
+    let mut edges = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let gx = at(x + 1, y - 1) + 2f32 * at(x + 1, y) + at(x + 1, y + 1)
+                - at(x - 1, y - 1)
+                - 2f32 * at(x - 1, y)
+                - at(x - 1, y + 1);
+            let gy = at(x - 1, y + 1) + 2f32 * at(x, y + 1) + at(x + 1, y + 1)
+                - at(x - 1, y - 1)
+                - 2f32 * at(x, y - 1)
+                - at(x + 1, y - 1);
+            edges.push(gx.hypot(gy));
+        }
+    }
+    let max = edges.iter().cloned().fold(0f32, f32::max);
+    if max > 0f32 {
+        edges.iter_mut().for_each(|e| *e /= max);
+    }
+    return edges;
+}
+
+/// (year, month, day) of a count of days since 1970-01-01,
+/// after Howard Hinnant's civil_from_days
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    return (year, month, day);
+}
+
+/// today as (year, month, day) in UTC
+pub fn today() -> (i64, u32, u32) {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    return civil_from_days((secs / 86400) as i64);
+}
+
+/// an inspirational color for a date, the same one all day for everybody
+pub fn color_of_the_day((year, month, day): (i64, u32, u32)) -> crate::color_item::ColorItem {
+    let seed = (year * 10000 + month as i64 * 100 + day as i64) as u64;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    // keep it lively, no grays and no near-black
+    let hue = rng.gen_range(0f32..360f32);
+    let saturation = rng.gen_range(0.45f32..=0.9f32);
+    let value = rng.gen_range(0.6f32..=0.95f32);
+    return crate::color_item::ColorItem::from_hsv(
+        hue,
+        saturation,
+        value,
+        &format!("day-{}-{:02}-{:02}", year, month, day),
+    );
+}
+
+/// blackbody color of `kelvin` (1000-40000 K) scaled by `brightness` (0-1),
+/// after Tanner Helland's curve fit of the CIE 1964 10° color matching data
+pub fn kelvin_to_rgb(kelvin: f32, brightness: f32) -> (u8, u8, u8) {
+    let temp = kelvin.clamp(1000f32, 40000f32) / 100f32;
+    let r = if temp <= 66f32 {
+        255f32
+    } else {
+        329.698727446 * (temp - 60f32).powf(-0.1332047592)
+    };
+    let g = if temp <= 66f32 {
+        99.4708025861 * temp.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (temp - 60f32).powf(-0.0755148492)
+    };
+    let b = if temp >= 66f32 {
+        255f32
+    } else if temp <= 19f32 {
+        0f32
+    } else {
+        138.5177312231 * (temp - 10f32).ln() - 305.0447927307
+    };
+    let scale = |c: f32| (c.clamp(0f32, 255f32) * brightness.clamp(0f32, 1f32)) as u8;
+    return (scale(r), scale(g), scale(b));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kelvin_to_rgb() {
+        // 6600K is close to neutral white
+        let (r, g, b) = kelvin_to_rgb(6600f32, 1f32);
+        assert!(r >= 250 && g >= 250 && b >= 250);
+
+        // candle light is red-orange, clear sky is blue
+        let (r, g, b) = kelvin_to_rgb(1000f32, 1f32);
+        assert_eq!((r, b), (255, 0));
+        assert!(g > 50 && g < 90);
+        let (r, _, b) = kelvin_to_rgb(40000f32, 1f32);
+        assert_eq!(b, 255);
+        assert!(r < 170);
+
+        // brightness scales every channel, out of range input is clamped
+        assert_eq!(kelvin_to_rgb(6600f32, 0f32), (0, 0, 0));
+        assert_eq!(kelvin_to_rgb(500f32, 1f32), kelvin_to_rgb(1000f32, 1f32));
+    }
+
+    #[test]
+    fn test_has_image_extension() {
+        assert!(has_image_extension(Path::new("a/b/photo.PNG")));
+        assert!(has_image_extension(Path::new("photo.jpeg")));
+        assert!(has_image_extension(Path::new("photo.webp")));
+        assert!(has_image_extension(Path::new("scan.BMP")));
+        assert!(!has_image_extension(Path::new("palette.json")));
+        assert!(!has_image_extension(Path::new("png")));
+    }
+
+    #[test]
+    fn test_save_format() {
+        assert_eq!(SaveFormat::from_path(Path::new("out/a.PNG")), Some(SaveFormat::Png));
+        assert_eq!(SaveFormat::from_path(Path::new("a.jpg")), Some(SaveFormat::Jpeg));
+        assert_eq!(SaveFormat::from_path(Path::new("a.jpeg")), Some(SaveFormat::Jpeg));
+        assert_eq!(SaveFormat::from_path(Path::new("a.webp")), Some(SaveFormat::WebP));
+        assert_eq!(SaveFormat::from_path(Path::new("a.bmp")), Some(SaveFormat::Bmp));
+        assert_eq!(SaveFormat::from_path(Path::new("a.gif")), None);
+        assert_eq!(SaveFormat::from_path(Path::new("jpg")), None);
+        assert!(is_save_name("untitled.webp"));
+        assert!(!is_save_name("untitled"));
+    }
+
+    #[test]
+    fn test_save_jpeg_quality() {
+        let img = image::DynamicImage::from(image::RgbaImage::from_fn(64, 64, |x, y| {
+            image::Rgba([(x * 4) as u8, (y * 4) as u8, ((x ^ y) * 4) as u8, 128])
+        }));
+        let dir = std::env::temp_dir();
+        let low = dir.join(format!("colorlook-test-{}-low.jpg", std::process::id()));
+        let high = dir.join(format!("colorlook-test-{}-high.jpg", std::process::id()));
+        assert_eq!(save_image(&img, &low, &[], 10), Ok(SaveFormat::Jpeg));
+        assert_eq!(save_image(&img, &high, &[], 95), Ok(SaveFormat::Jpeg));
+        let low_size = std::fs::metadata(&low).unwrap().len();
+        let high_size = std::fs::metadata(&high).unwrap().len();
+        let decoded = image::open(&high).unwrap();
+        let _ = std::fs::remove_file(&low);
+        let _ = std::fs::remove_file(&high);
+        assert!(low_size < high_size);
+        assert_eq!((decoded.width(), decoded.height()), (64, 64));
+        assert!(!decoded.color().has_alpha());
+    }
+
+    #[test]
+    fn test_over_checker() {
+        assert_eq!(over_checker(3, 5, [10, 20, 30, 255]), [10, 20, 30]);
+        // fully transparent shows the checkerboard, alternating per cell
+        assert_eq!(over_checker(0, 0, [255, 0, 0, 0]), [102, 102, 102]);
+        assert_eq!(over_checker(CHECKER_PIXELS, 0, [255, 0, 0, 0]), [153, 153, 153]);
+        assert_eq!(over_checker(CHECKER_PIXELS, CHECKER_PIXELS, [255, 0, 0, 0]), [102, 102, 102]);
+        let [r, g, _] = over_checker(0, 0, [255, 0, 0, 128]);
+        assert!(r > 170 && g < 60);
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20742), (2026, 10, 16));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_color_of_the_day() {
+        // the same date always gives the same color
+        let today = color_of_the_day((2026, 10, 16));
+        assert!(today == color_of_the_day((2026, 10, 16)));
+        assert_eq!(today.name, "day-2026-10-16");
+        assert!(today.get_v() >= 0.55 && today.get_s() >= 0.4);
+
+        // and a week of days does not repeat
+        let week: Vec<_> = (10..17)
+            .map(|day| {
+                let color = color_of_the_day((2026, 10, day));
+                (color.r, color.g, color.b)
+            })
+            .collect();
+        for i in 0..week.len() {
+            for j in (i + 1)..week.len() {
+                assert_ne!(week[i], week[j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_name_seeded() {
+        let name = get_random_name_seeded(8, 42);
+        assert_eq!(name, get_random_name_seeded(8, 42));
+        assert_eq!(name.len(), 8);
+        assert!(name.chars().all(|c| c.is_ascii_lowercase()));
+        assert_ne!(name, get_random_name_seeded(8, 43));
+        // a shorter name is the start of the longer one
+        assert!(name.starts_with(&get_random_name_seeded(5, 42)));
+    }
+
+    #[test]
+    fn test_uniform_color() {
+        let black = image::RgbImage::from_pixel(4, 4, image::Rgb([0, 0, 0]));
+        assert_eq!(
+            uniform_color(&image::DynamicImage::ImageRgb8(black.clone())),
+            Some([0, 0, 0, 255])
+        );
+        let mut dotted = black;
+        dotted.put_pixel(3, 3, image::Rgb([1, 0, 0]));
+        assert_eq!(uniform_color(&image::DynamicImage::ImageRgb8(dotted)), None);
+    }
+
+    #[test]
+    fn test_edge_map() {
+        let flat = image::GrayImage::from_pixel(5, 5, image::Luma([90]));
+        assert!(edge_map(&flat).iter().all(|e| *e == 0f32));
+        assert!(edge_map(&image::GrayImage::new(0, 0)).is_empty());
+
+        // a vertical step from black to white between columns 2 and 3
+        let step = image::GrayImage::from_fn(6, 4, |x, _| image::Luma([if x < 3 { 0 } else { 255 }]));
+        let edges = edge_map(&step);
+        assert_eq!(edges.len(), 24);
+        for y in 0..4 {
+            let row = &edges[y * 6..(y + 1) * 6];
+            assert_eq!(row[0], 0f32);
+            assert_eq!(row[2], 1f32);
+            assert_eq!(row[3], 1f32);
+            assert_eq!(row[5], 0f32);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// CSS named colors, the WebStandard preset built into the binary so it
+// is there even when the preset folder is missing or edited
+
+use super::auto_palette::color_struct::Color;
+use super::auto_palette::delta_e::DeltaE;
+use super::auto_palette::rgb::RGB;
+
+const TABLE_JSON: &str = include_str!("../../assets/presets/WebStandard.json");
+
+lazy_static::lazy_static! {
+    static ref TABLE: Vec<(String, [u8; 3])> = {
+        let colors: Vec<crate::color_item::ColorItem> =
+            serde_json::from_str(TABLE_JSON).expect("bundled WebStandard.json is valid");
+        // the basic colors are listed again among the extended ones
+        let mut seen = std::collections::HashSet::new();
+        colors
+            .into_iter()
+            .filter(|c| seen.insert(c.name.clone()))
+            .map(|c| (c.name, [c.r, c.g, c.b]))
+            .collect()
+    };
+    // the table in Lab for nearest lookups, same order
+    static ref TABLE_LAB: Vec<Color<f64>> = TABLE
+        .iter()
+        .map(|(_, [r, g, b])| Color::from(&RGB::new(*r, *g, *b)))
+        .collect();
+}
+
+/// every (name, rgb) of the table
+pub fn table() -> &'static [(String, [u8; 3])] {
+    return &TABLE;
+}
+
+/// names containing `query` ignoring case and spaces, names starting with it first
+pub fn search(query: &str, limit: usize) -> Vec<(&'static str, [u8; 3])> {
+    let query: String = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let (mut prefixed, contained): (Vec<_>, Vec<_>) = table()
+        .iter()
+        .filter(|(name, _)| name.to_lowercase().contains(&query))
+        .partition(|(name, _)| name.to_lowercase().starts_with(&query));
+    prefixed.extend(contained);
+    return prefixed
+        .into_iter()
+        .take(limit)
+        .map(|(name, rgb)| (name.as_str(), *rgb))
+        .collect();
+}
+
+/// closest named color by CIEDE2000 and its distance
+pub fn nearest(r: u8, g: u8, b: u8) -> (&'static str, f32) {
+    let color: Color<f64> = Color::from(&RGB::new(r, g, b));
+    let mut best = (0, f64::MAX);
+    for (i, named) in TABLE_LAB.iter().enumerate() {
+        let distance = color.difference(named, &DeltaE::CIE2000);
+        if distance < best.1 {
+            best = (i, distance);
+        }
+    }
+    return (table()[best.0].0.as_str(), best.1 as f32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        let found = search("corn", 8);
+        assert_eq!(found[0], ("cornflowerblue", [100, 149, 237]));
+        assert!(found.iter().any(|(name, _)| *name == "cornsilk"));
+        // prefix matches come before the rest
+        let blue = search("Blue", 50);
+        assert_eq!(blue[0].0, "blue");
+        let first_inner = blue.iter().position(|(n, _)| !n.starts_with("blue")).unwrap();
+        assert!(blue[first_inner..].iter().all(|(n, _)| !n.starts_with("blue")));
+        assert_eq!(search("blue", 3).len(), 3);
+        assert!(search("  ", 8).is_empty());
+        assert!(search("notacolor", 8).is_empty());
+        assert_eq!(table().iter().filter(|(n, _)| n == "blue").count(), 1);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let (name, distance) = nearest(255, 99, 71);
+        assert_eq!(name, "tomato");
+        assert!(distance < 1e-3);
+        // a little off still finds it, rgb distance alone would not decide by perception
+        let (name, distance) = nearest(250, 100, 75);
+        assert_eq!(name, "tomato");
+        assert!(distance > 0.0 && distance < 5.0);
+        assert_eq!(nearest(0, 0, 0).0, "black");
+    }
+}
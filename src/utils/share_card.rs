@@ -0,0 +1,54 @@
+// a compact banner of the palette: color strip on top, name and hex below
+
+use crate::color_item::ColorItem;
+
+const MAX_COLUMNS: usize = 8;
+const CELL_WIDTH: u32 = 160;
+const STRIP_HEIGHT: u32 = 120;
+const FOOTER_HEIGHT: u32 = 52;
+const PADDING: u32 = 16;
+const NAME_SIZE: f32 = 15.0;
+const HEX_SIZE: f32 = 13.0;
+const BACKGROUND: [u8; 3] = [246, 246, 246];
+const TEXT: [u8; 3] = [32, 32, 32];
+const WEAK_TEXT: [u8; 3] = [110, 110, 110];
+
+/// render the card, none for an empty palette.
+/// big palettes wrap after `MAX_COLUMNS` colors.
+pub fn render(colors: &[ColorItem]) -> Option<image::RgbImage> {
+    if colors.is_empty() {
+        return None;
+    }
+    let columns = colors.len().min(MAX_COLUMNS) as u32;
+    let rows = colors.len().div_ceil(MAX_COLUMNS) as u32;
+    let row_height = STRIP_HEIGHT + FOOTER_HEIGHT;
+    let width = columns * CELL_WIDTH + PADDING * 2;
+    let height = rows * row_height + PADDING * 2;
+    let mut img = image::RgbImage::from_pixel(width, height, image::Rgb(BACKGROUND));
+    // keep labels inside their column
+    let max_chars = ((CELL_WIDTH - 12) as f32 / super::text::text_width(NAME_SIZE, "M")) as usize;
+    for (i, color) in colors.iter().enumerate() {
+        let column = i as u32 % columns;
+        let row = i as u32 / columns;
+        let x = PADDING + column * CELL_WIDTH;
+        let y = PADDING + row * row_height;
+        for py in y..y + STRIP_HEIGHT {
+            for px in x..x + CELL_WIDTH {
+                img.put_pixel(px, py, image::Rgb([color.r, color.g, color.b]));
+            }
+        }
+        let name = super::resized_str(&color.name, max_chars.saturating_sub(3));
+        let text_x = (x + 6) as f32;
+        let text_y = (y + STRIP_HEIGHT + 8) as f32;
+        super::text::draw_text(&mut img, text_x, text_y, NAME_SIZE, &name, TEXT);
+        super::text::draw_text(
+            &mut img,
+            text_x,
+            text_y + NAME_SIZE + 6.0,
+            HEX_SIZE,
+            &color.get_hex(),
+            WEAK_TEXT,
+        );
+    }
+    return Some(img);
+}
@@ -0,0 +1,43 @@
+// rasterize text straight into images, with the same Hack font the UI uses
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+
+fn font() -> FontRef<'static> {
+    // the font is embedded at build time, failing to parse it is a build problem
+    return FontRef::try_from_slice(&crate::HACKFONT).unwrap();
+}
+
+/// width in pixels `text` takes at `size`
+pub fn text_width(size: f32, text: &str) -> f32 {
+    let font = font();
+    let scaled = font.as_scaled(PxScale::from(size));
+    return text.chars().map(|c| scaled.h_advance(scaled.glyph_id(c))).sum();
+}
+
+/// draw `text` with its top-left corner at (x, y), blending by glyph coverage
+pub fn draw_text(img: &mut image::RgbImage, x: f32, y: f32, size: f32, text: &str, color: [u8; 3]) {
+    let font = font();
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+    let mut caret = x;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        let glyph = id.with_scale_and_position(scale, ab_glyph::point(caret, y + scaled.ascent()));
+        caret += scaled.h_advance(id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= img.width() as i32 || py >= img.height() as i32 {
+                return;
+            }
+            let pixel = img.get_pixel_mut(px as u32, py as u32);
+            for i in 0..3 {
+                pixel.0[i] = (pixel.0[i] as f32 * (1.0 - coverage) + color[i] as f32 * coverage) as u8;
+            }
+        });
+    }
+}